@@ -21,4 +21,10 @@ pub enum Actors {
     Verifier,
     Sender,
     Receiver,
+    Mediator,
+    Recipient,
+    #[serde(rename = "rotating_party")]
+    RotatingParty,
+    #[serde(rename = "observing_party")]
+    ObservingParty,
 }
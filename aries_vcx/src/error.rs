@@ -43,6 +43,8 @@ pub enum VcxErrorKind {
     AlreadyInitialized,
     #[fail(display = "Action is not supported")]
     ActionNotSupported,
+    #[fail(display = "Message has expired")]
+    MessageExpired,
 
     // Connection
     #[fail(display = "Could not create connection")]
@@ -105,6 +107,10 @@ pub enum VcxErrorKind {
     InvalidCredential,
     #[fail(display = "Attributes provided to Credential Offer are not correct, possibly malformed")]
     InvalidAttributesStructure,
+    #[fail(display = "Credential preview attributes do not match the schema")]
+    CredentialPreviewSchemaMismatch,
+    #[fail(display = "Issued credential values do not match the credential preview")]
+    CredentialPreviewValuesMismatch,
 
     // Proof
     #[fail(display = "Invalid proof handle")]
@@ -505,6 +511,9 @@ impl From<VcxErrorKind> for u32 {
             VcxErrorKind::MissingBackupKey => error::MISSING_BACKUP_KEY.code_num,
             VcxErrorKind::UnknownLibndyError => error::UNKNOWN_LIBINDY_ERROR.code_num,
             VcxErrorKind::ActionNotSupported => error::ACTION_NOT_SUPPORTED.code_num,
+            VcxErrorKind::MessageExpired => error::MESSAGE_EXPIRED.code_num,
+            VcxErrorKind::CredentialPreviewSchemaMismatch => error::CREDENTIAL_PREVIEW_SCHEMA_MISMATCH.code_num,
+            VcxErrorKind::CredentialPreviewValuesMismatch => error::CREDENTIAL_PREVIEW_VALUES_MISMATCH.code_num,
             VcxErrorKind::Common(num) => num,
             VcxErrorKind::LibndyError(num) => num,
             VcxErrorKind::NoAgentInformation => error::NO_AGENT_INFO.code_num,
@@ -608,6 +617,13 @@ impl From<u32> for VcxErrorKind {
             _ if { error::MISSING_BACKUP_KEY.code_num == code } => VcxErrorKind::MissingBackupKey,
             _ if { error::UNKNOWN_LIBINDY_ERROR.code_num == code } => VcxErrorKind::UnknownLibndyError,
             _ if { error::ACTION_NOT_SUPPORTED.code_num == code } => VcxErrorKind::ActionNotSupported,
+            _ if { error::MESSAGE_EXPIRED.code_num == code } => VcxErrorKind::MessageExpired,
+            _ if { error::CREDENTIAL_PREVIEW_SCHEMA_MISMATCH.code_num == code } => {
+                VcxErrorKind::CredentialPreviewSchemaMismatch
+            }
+            _ if { error::CREDENTIAL_PREVIEW_VALUES_MISMATCH.code_num == code } => {
+                VcxErrorKind::CredentialPreviewValuesMismatch
+            }
             _ if { error::NO_AGENT_INFO.code_num == code } => VcxErrorKind::NoAgentInformation,
             _ if { error::REV_REG_DEF_NOT_FOUND.code_num == code } => VcxErrorKind::RevRegDefNotFound,
             _ if { error::REV_DELTA_NOT_FOUND.code_num == code } => VcxErrorKind::RevDeltaNotFound,
@@ -0,0 +1,223 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+
+use crate::messages::a2a::A2AMessage;
+use crate::messages::error::ProblemReport;
+use crate::utils::uuid;
+
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+pub type EventCallback = Arc<dyn Fn(&Event) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProtocolKind {
+    Connection,
+    Issuer,
+    Holder,
+    Prover,
+    Verifier,
+}
+
+impl ToString for ProtocolKind {
+    fn to_string(&self) -> String {
+        match self {
+            ProtocolKind::Connection => "connection".to_string(),
+            ProtocolKind::Issuer => "issuer".to_string(),
+            ProtocolKind::Holder => "holder".to_string(),
+            ProtocolKind::Prover => "prover".to_string(),
+            ProtocolKind::Verifier => "verifier".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventPayload {
+    StateChanged { previous_state: String, state: String },
+    MessageReceived { message: A2AMessage },
+    ProblemReport { problem_report: ProblemReport },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Event {
+    pub id: String,
+    pub protocol: ProtocolKind,
+    pub source_id: String,
+    pub thread_id: String,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub payload: EventPayload,
+}
+
+impl Event {
+    pub fn new(protocol: ProtocolKind, source_id: &str, thread_id: &str, payload: EventPayload) -> Self {
+        Self {
+            id: uuid::uuid(),
+            protocol,
+            source_id: source_id.to_string(),
+            thread_id: thread_id.to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            payload,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: RwLock<Vec<(u32, EventCallback)>>,
+    last_id: AtomicU32,
+}
+
+impl EventBus {
+    pub fn subscribe(&self, callback: EventCallback) -> u32 {
+        let id = self.last_id.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Ok(mut subscribers) = self.subscribers.write() {
+            subscribers.push((id, callback));
+        }
+        id
+    }
+
+    pub fn unsubscribe(&self, subscription_id: u32) -> bool {
+        match self.subscribers.write() {
+            Ok(mut subscribers) => {
+                let count = subscribers.len();
+                subscribers.retain(|(id, _)| *id != subscription_id);
+                subscribers.len() != count
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Calls subscribers in the order they subscribed. Callbacks are called synchronously
+    /// and should hand off any long running work.
+    pub fn publish(&self, event: &Event) {
+        trace!("EventBus::publish >>> event: {:?}", event);
+        let subscribers: Vec<EventCallback> = match self.subscribers.read() {
+            Ok(subscribers) => subscribers.iter().map(|(_, callback)| callback.clone()).collect(),
+            Err(_) => return,
+        };
+        for callback in subscribers {
+            callback(event);
+        }
+    }
+}
+
+lazy_static! {
+    static ref EVENT_BUS: EventBus = EventBus::default();
+}
+
+pub fn subscribe(callback: EventCallback) -> u32 {
+    EVENT_BUS.subscribe(callback)
+}
+
+pub fn unsubscribe(subscription_id: u32) -> bool {
+    EVENT_BUS.unsubscribe(subscription_id)
+}
+
+pub fn publish(event: Event) {
+    EVENT_BUS.publish(&event)
+}
+
+pub fn publish_message_received(protocol: ProtocolKind, source_id: &str, thread_id: &str, message: &A2AMessage) {
+    publish(Event::new(
+        protocol,
+        source_id,
+        thread_id,
+        EventPayload::MessageReceived {
+            message: message.clone(),
+        },
+    ))
+}
+
+/// Publishes state change if the state differs from the previous one, and the problem report which
+/// terminated the protocol if it was not known before the transition.
+pub fn publish_transition<S: Debug + PartialEq>(
+    protocol: ProtocolKind,
+    source_id: &str,
+    thread_id: &str,
+    (previous_state, state): (&S, &S),
+    (previous_problem_report, problem_report): (Option<ProblemReport>, Option<ProblemReport>),
+) {
+    if previous_state != state {
+        publish(Event::new(
+            protocol,
+            source_id,
+            thread_id,
+            EventPayload::StateChanged {
+                previous_state: format!("{:?}", previous_state),
+                state: format!("{:?}", state),
+            },
+        ));
+    }
+    if let Some(problem_report) = problem_report {
+        if previous_problem_report.as_ref() != Some(&problem_report) {
+            publish(Event::new(
+                protocol,
+                source_id,
+                thread_id,
+                EventPayload::ProblemReport { problem_report },
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::Mutex;
+
+    use crate::messages::error::test_utils::_problem_report;
+
+    use super::*;
+
+    fn _recording_bus() -> (EventBus, Arc<Mutex<Vec<Event>>>, u32) {
+        let bus = EventBus::default();
+        let events = Arc::new(Mutex::new(vec![]));
+        let recorded = events.clone();
+        let id = bus.subscribe(Arc::new(move |event: &Event| {
+            recorded.lock().unwrap().push(event.clone())
+        }));
+        (bus, events, id)
+    }
+
+    #[test]
+    fn test_event_bus_delivers_until_unsubscribed() {
+        let (bus, events, id) = _recording_bus();
+        let event = Event::new(
+            ProtocolKind::Issuer,
+            "source_id",
+            "thread_id",
+            EventPayload::StateChanged {
+                previous_state: "OfferSent".to_string(),
+                state: "RequestReceived".to_string(),
+            },
+        );
+        bus.publish(&event);
+        assert!(bus.unsubscribe(id));
+        assert!(!bus.unsubscribe(id));
+        bus.publish(&event);
+
+        assert_eq!(vec![event], *events.lock().unwrap());
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = Event::new(
+            ProtocolKind::Verifier,
+            "source_id",
+            "thread_id",
+            EventPayload::ProblemReport {
+                problem_report: _problem_report(),
+            },
+        );
+        let value = json!(event);
+        assert_eq!(value["type"], "problem_report");
+        assert_eq!(value["protocol"], "verifier");
+        assert_eq!(event, serde_json::from_value(value).unwrap());
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use tokio::runtime::Handle;
+
+use crate::error::prelude::*;
+use crate::events::{subscribe, Event};
+use crate::global::settings;
+
+/// Subscribes a sink which POSTs every published event as JSON to the given url. Requests are
+/// spawned on the current tokio runtime, so events published outside of a runtime are dropped.
+pub fn subscribe_webhook(url: &str) -> VcxResult<u32> {
+    trace!("subscribe_webhook >>> url: {}", url);
+    let url = url::Url::parse(url).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidUrl,
+            format!("Invalid webhook url {}, err: {:?}", url, err),
+        )
+    })?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::PostMessageFailed,
+                format!("Building webhook http client failed: {:?}", err),
+            )
+        })?;
+    Ok(subscribe(Arc::new(move |event: &Event| {
+        let handle = match Handle::try_current() {
+            Ok(handle) => handle,
+            Err(_) => {
+                warn!(
+                    "subscribe_webhook >> no tokio runtime available, dropping event {}",
+                    event.id
+                );
+                return;
+            }
+        };
+        let request = client
+            .post(url.as_str())
+            .header(CONTENT_TYPE, "application/json")
+            .body(json!(event).to_string());
+        let event_id = event.id.clone();
+        handle.spawn(async move {
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    debug!("subscribe_webhook >> delivered event {}", event_id)
+                }
+                Ok(response) => warn!(
+                    "subscribe_webhook >> delivery of event {} failed with status {}",
+                    event_id,
+                    response.status()
+                ),
+                Err(err) => warn!("subscribe_webhook >> delivery of event {} failed: {}", event_id, err),
+            }
+        });
+    })))
+}
+
+/// Subscribes the webhook sink if `webhook_url` is configured, returns the subscription id.
+pub fn init_webhook_from_settings() -> VcxResult<Option<u32>> {
+    match settings::get_config_value(settings::CONFIG_WEBHOOK_URL) {
+        Ok(url) if !url.is_empty() => subscribe_webhook(&url).map(Some),
+        _ => Ok(None),
+    }
+}
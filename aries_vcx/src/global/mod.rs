@@ -1,2 +1,4 @@
+pub mod outbound_queue;
 pub mod pool;
+pub mod protocol_repository;
 pub mod settings;
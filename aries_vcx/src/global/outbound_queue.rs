@@ -0,0 +1,21 @@
+use std::sync::{Arc, RwLock};
+
+use crate::utils::outbound_queue::OutboundQueue;
+
+lazy_static! {
+    static ref OUTBOUND_QUEUE: RwLock<Option<Arc<OutboundQueue>>> = RwLock::new(None);
+}
+
+/// Once set, all messages sent to peers are delivered through the queue instead of being posted directly.
+pub fn set_outbound_queue(queue: Option<Arc<OutboundQueue>>) {
+    let mut q = OUTBOUND_QUEUE.write().unwrap();
+    *q = queue;
+}
+
+pub fn get_outbound_queue() -> Option<Arc<OutboundQueue>> {
+    OUTBOUND_QUEUE.read().ok().and_then(|queue| queue.clone())
+}
+
+pub fn reset_outbound_queue() {
+    set_outbound_queue(None);
+}
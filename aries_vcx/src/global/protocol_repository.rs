@@ -0,0 +1,24 @@
+use std::sync::{Arc, RwLock};
+
+use crate::utils::protocol_repository::ProtocolRepository;
+
+lazy_static! {
+    static ref PROTOCOL_REPOSITORY: RwLock<Option<Arc<ProtocolRepository>>> = RwLock::new(None);
+}
+
+/// Once set, handlers save themselves into the repository after every state transition.
+pub fn set_protocol_repository(repository: Option<Arc<ProtocolRepository>>) {
+    let mut r = PROTOCOL_REPOSITORY.write().unwrap();
+    *r = repository;
+}
+
+pub fn get_protocol_repository() -> Option<Arc<ProtocolRepository>> {
+    PROTOCOL_REPOSITORY
+        .read()
+        .ok()
+        .and_then(|repository| repository.clone())
+}
+
+pub fn reset_protocol_repository() {
+    set_protocol_repository(None);
+}
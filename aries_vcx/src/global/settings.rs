@@ -11,7 +11,7 @@ pub static CONFIG_POOL_NAME: &str = "pool_name";
 pub static CONFIG_SDK_TO_REMOTE_ROLE: &str = "sdk_to_remote_role";
 pub static CONFIG_INSTITUTION_DID: &str = "institution_did";
 pub static CONFIG_INSTITUTION_VERKEY: &str = "institution_verkey";
-// functionally not used
+// used by the webhook event sink, see events::webhook
 pub static CONFIG_WEBHOOK_URL: &str = "webhook_url";
 pub static CONFIG_ENABLE_TEST_MODE: &str = "enable_test_mode";
 pub static CONFIG_GENESIS_PATH: &str = "genesis_path";
@@ -28,6 +28,9 @@ pub static CONFIG_POOL_CONFIG: &str = "pool_config";
 pub static CONFIG_DID_METHOD: &str = "did_method";
 // proprietary or aries
 pub static CONFIG_ACTORS: &str = "actors";
+// json arrays disclosed by discover-features 2.0 in addition to the defaults
+pub static CONFIG_GOAL_CODES: &str = "goal_codes";
+pub static CONFIG_ATTACHMENT_FORMATS: &str = "attachment_formats";
 
 pub static DEFAULT_PROTOCOL_VERSION: usize = 2;
 pub static MAX_SUPPORTED_PROTOCOL_VERSION: usize = 2;
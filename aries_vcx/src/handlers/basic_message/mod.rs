@@ -0,0 +1,184 @@
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::libindy::utils::wallet::{add_wallet_record, search_wallet_records};
+use crate::messages::basic_message::message::BasicMessage;
+
+pub const BASIC_MESSAGE_RECORD_TYPE: &str = "basic_message";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BasicMessageDirection {
+    Sent,
+    Received,
+}
+
+impl ToString for BasicMessageDirection {
+    fn to_string(&self) -> String {
+        match self {
+            BasicMessageDirection::Sent => "sent".to_string(),
+            BasicMessageDirection::Received => "received".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BasicMessageRecord {
+    pub connection_id: String,
+    pub direction: BasicMessageDirection,
+    pub message: BasicMessage,
+}
+
+impl BasicMessageRecord {
+    pub fn get_thread_id(&self) -> String {
+        self.message.get_thread_id()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BasicMessagePage {
+    pub messages: Vec<BasicMessageRecord>,
+    pub total_count: usize,
+}
+
+/// Builds basic message, replying within the thread of `reply_to` if provided.
+pub fn build_basic_message(content: &str, locale: Option<&str>, reply_to: Option<&BasicMessage>) -> BasicMessage {
+    let message = BasicMessage::create()
+        .set_content(content.to_string())
+        .set_time()
+        .set_out_time();
+    let message = match locale {
+        Some(locale) => message.set_localization(locale),
+        None => message,
+    };
+    match reply_to {
+        Some(reply_to) => message.set_thread_id(&reply_to.get_thread_id()),
+        None => message,
+    }
+}
+
+pub async fn store_basic_message(
+    wallet_handle: WalletHandle,
+    connection_id: &str,
+    direction: BasicMessageDirection,
+    message: &BasicMessage,
+) -> VcxResult<()> {
+    trace!(
+        "store_basic_message >>> connection_id: {}, direction: {:?}, message id: {}",
+        connection_id,
+        direction,
+        message.id.0
+    );
+    let record = BasicMessageRecord {
+        connection_id: connection_id.to_string(),
+        direction,
+        message: message.clone(),
+    };
+    let value = serde_json::to_string(&record).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::SerializationError,
+            format!("Cannot serialize basic message record: {}", err),
+        )
+    })?;
+    let tags = json!({
+        "connection_id": connection_id,
+        "thread_id": record.get_thread_id(),
+        "direction": direction.to_string(),
+    })
+    .to_string();
+    let record_id = format!("{}:{}", connection_id, message.id.0);
+    add_wallet_record(
+        wallet_handle,
+        BASIC_MESSAGE_RECORD_TYPE,
+        &record_id,
+        &value,
+        Some(&tags),
+    )
+    .await
+}
+
+/// Returns page of conversation history of the connection, newest messages first.
+pub async fn get_basic_messages(
+    wallet_handle: WalletHandle,
+    connection_id: &str,
+    thread_id: Option<&str>,
+    offset: usize,
+    limit: usize,
+) -> VcxResult<BasicMessagePage> {
+    trace!(
+        "get_basic_messages >>> connection_id: {}, thread_id: {:?}, offset: {}, limit: {}",
+        connection_id,
+        thread_id,
+        offset,
+        limit
+    );
+    let query = match thread_id {
+        Some(thread_id) => json!({ "connection_id": connection_id, "thread_id": thread_id }),
+        None => json!({ "connection_id": connection_id }),
+    };
+    let records = search_wallet_records(wallet_handle, BASIC_MESSAGE_RECORD_TYPE, &query.to_string())
+        .await?
+        .iter()
+        .filter_map(|record| {
+            serde_json::from_str::<BasicMessageRecord>(record.value.as_deref().unwrap_or_default())
+                .map_err(|err| warn!("get_basic_messages >>> skipping malformed record, err: {:?}", err))
+                .ok()
+        })
+        .collect();
+    Ok(paginate(records, offset, limit))
+}
+
+fn paginate(mut records: Vec<BasicMessageRecord>, offset: usize, limit: usize) -> BasicMessagePage {
+    records.sort_by(|a, b| b.message.sent_time.cmp(&a.message.sent_time));
+    let total_count = records.len();
+    let messages = records.into_iter().skip(offset).take(limit).collect();
+    BasicMessagePage { messages, total_count }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    fn _record(id: &str, sent_time: &str) -> BasicMessageRecord {
+        let mut message = build_basic_message(id, None, None);
+        message.sent_time = sent_time.to_string();
+        BasicMessageRecord {
+            connection_id: "connection_id".to_string(),
+            direction: BasicMessageDirection::Received,
+            message,
+        }
+    }
+
+    #[test]
+    fn test_build_basic_message_reply() {
+        let message = build_basic_message("Hello", Some("de"), None);
+        assert_eq!(message.get_locale(), Some("de".to_string()));
+
+        let reply = build_basic_message("Hi", None, Some(&message));
+        assert_eq!(reply.get_thread_id(), message.get_thread_id());
+        assert_eq!(reply.get_locale(), None);
+    }
+
+    #[test]
+    fn test_paginate_returns_newest_first() {
+        let records = vec![
+            _record("first", "2022-01-01T10:00:00Z"),
+            _record("third", "2022-01-01T12:00:00Z"),
+            _record("second", "2022-01-01T11:00:00Z"),
+        ];
+
+        let page = paginate(records.clone(), 0, 2);
+        assert_eq!(page.total_count, 3);
+        let contents: Vec<String> = page
+            .messages
+            .iter()
+            .map(|record| record.message.content.clone())
+            .collect();
+        assert_eq!(contents, vec!["third".to_string(), "second".to_string()]);
+
+        let page = paginate(records, 2, 2);
+        assert_eq!(page.messages.len(), 1);
+        assert_eq!(page.messages[0].message.content, "first");
+    }
+}
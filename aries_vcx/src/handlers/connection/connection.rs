@@ -15,23 +15,38 @@ use agency_client::MessageStatusCode;
 
 use crate::did_doc::DidDoc;
 use crate::error::prelude::*;
+use crate::events::{self, ProtocolKind};
+use crate::handlers::basic_message::{
+    build_basic_message, get_basic_messages, store_basic_message, BasicMessageDirection, BasicMessagePage,
+};
 use crate::handlers::connection::cloud_agent::CloudAgentInfo;
+use crate::handlers::connection::health::{
+    get_connection_health, record_last_seen, record_ping_response, record_ping_sent, ConnectionHealth,
+};
 use crate::handlers::connection::legacy_agent_info::LegacyAgentInfo;
 use crate::handlers::connection::public_agent::PublicAgent;
-use crate::handlers::discovery::{respond_discovery_query, send_discovery_query};
-use crate::handlers::trust_ping::TrustPingSender;
+use crate::handlers::connection::rotated_pairwise_info::RotatedPairwiseInfo;
+use crate::handlers::discovery::{
+    respond_discovery_query, respond_feature_queries, send_discovery_query, send_feature_queries,
+};
+use crate::handlers::mediation::MediationRecipient;
+use crate::handlers::trust_ping::{respond_to_ping, TrustPingSender};
 use crate::messages::a2a::protocol_registry::ProtocolRegistry;
 use crate::messages::a2a::A2AMessage;
 use crate::messages::basic_message::message::BasicMessage;
 use crate::messages::connection::invite::Invitation;
 use crate::messages::connection::request::Request;
 use crate::messages::discovery::disclose::{Disclose, ProtocolDescriptor};
+use crate::messages::discovery::disclosures::{Disclosure, FeatureDisclosures};
+use crate::messages::discovery::queries::FeatureQuery;
 use crate::protocols::connection::invitee::state_machine::{InviteeFullState, InviteeState, SmConnectionInvitee};
 use crate::protocols::connection::inviter::state_machine::{InviterFullState, InviterState, SmConnectionInviter};
 use crate::protocols::connection::pairwise_info::PairwiseInfo;
+use crate::protocols::did_rotate::{build_did_doc, build_rotate_ack, build_rotate_msg, verify_rotate_msg};
 use crate::protocols::oob::{build_handshake_reuse_accepted_msg, build_handshake_reuse_msg};
-use crate::protocols::trustping::build_ping_response;
 use crate::protocols::SendClosure;
+use crate::utils::migrations::CONNECTION_MIGRATIONS;
+use crate::utils::protocol_repository;
 use crate::utils::send_message;
 use crate::utils::serialization::SerializableObjectWithState;
 
@@ -40,6 +55,7 @@ pub struct Connection {
     connection_sm: SmConnection,
     cloud_agent_info: CloudAgentInfo,
     autohop_enabled: bool,
+    rotated_pairwise_info: Option<RotatedPairwiseInfo>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -97,6 +113,7 @@ impl Connection {
             cloud_agent_info,
             connection_sm: SmConnection::Inviter(SmConnectionInviter::new(source_id, pairwise_info)),
             autohop_enabled,
+            rotated_pairwise_info: None,
         })
     }
 
@@ -118,6 +135,7 @@ impl Connection {
             cloud_agent_info,
             connection_sm: SmConnection::Invitee(SmConnectionInvitee::new(source_id, pairwise_info)),
             autohop_enabled,
+            rotated_pairwise_info: None,
         };
         connection.process_invite(invitation)?;
         Ok(connection)
@@ -139,6 +157,7 @@ impl Connection {
             cloud_agent_info: public_agent.cloud_agent_info(),
             connection_sm: SmConnection::Inviter(SmConnectionInviter::new(&request.id.0, pairwise_info)),
             autohop_enabled: true,
+            rotated_pairwise_info: None,
         };
         connection.process_request(wallet_handle, request, agency_client).await
     }
@@ -161,6 +180,7 @@ impl Connection {
                     state,
                 )),
                 autohop_enabled,
+                rotated_pairwise_info: None,
             },
             SmConnectionState::Invitee(state) => Self {
                 cloud_agent_info,
@@ -171,6 +191,7 @@ impl Connection {
                     state,
                 )),
                 autohop_enabled,
+                rotated_pairwise_info: None,
             },
         }
     }
@@ -251,6 +272,13 @@ impl Connection {
         }
     }
 
+    pub fn get_remote_features(&self) -> Option<Vec<Disclosure>> {
+        match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => sm_inviter.get_remote_features(),
+            SmConnection::Invitee(sm_invitee) => sm_invitee.get_remote_features(),
+        }
+    }
+
     pub fn their_did_doc(&self) -> Option<DidDoc> {
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => sm_inviter.their_did_doc(),
@@ -330,6 +358,7 @@ impl Connection {
             connection_sm,
             cloud_agent_info: new_cloud_agent_info,
             autohop_enabled: self.autohop_enabled,
+            rotated_pairwise_info: self.rotated_pairwise_info.clone(),
         })
     }
 
@@ -355,11 +384,28 @@ impl Connection {
         message: Option<A2AMessage>,
     ) -> BoxFuture<'_, VcxResult<()>> {
         Box::pin(async move {
+            if let Some(message) = &message {
+                events::publish_message_received(
+                    ProtocolKind::Connection,
+                    &self.source_id(),
+                    &self.get_thread_id(),
+                    message,
+                );
+            }
+            let previous_state = self.get_state();
             let (new_connection_sm, can_autohop) = match &self.connection_sm {
                 SmConnection::Inviter(_) => self.step_inviter(wallet_handle, message, &agency_client).await?,
                 SmConnection::Invitee(_) => self.step_invitee(wallet_handle, message).await?,
             };
             *self = new_connection_sm;
+            events::publish_transition(
+                ProtocolKind::Connection,
+                &self.source_id(),
+                &self.get_thread_id(),
+                (&previous_state, &self.get_state()),
+                (None, None),
+            );
+            protocol_repository::persist(&*self, Some(&self.get_thread_id())).await;
             if can_autohop && self.autohop_enabled {
                 let res = self.update_state_with_message(wallet_handle, agency_client, None).await;
                 res
@@ -397,7 +443,12 @@ impl Connection {
                 | A2AMessage::OutOfBandHandshakeReuse(_)
                 | A2AMessage::OutOfBandHandshakeReuseAccepted(_)
                 | A2AMessage::Query(_)
-                | A2AMessage::Disclose(_) => return Some((uid, message)),
+                | A2AMessage::Disclose(_)
+                | A2AMessage::FeatureQueries(_)
+                | A2AMessage::FeatureDisclosures(_)
+                | A2AMessage::DidRotate(_)
+                | A2AMessage::DidRotateAck(_)
+                | A2AMessage::BasicMessage(_) => return Some((uid, message)),
                 _ => {}
             }
         }
@@ -412,18 +463,36 @@ impl Connection {
                 message
             ),
         ))?;
+        if let Err(err) = record_last_seen(wallet_handle, &self.get_thread_id()).await {
+            warn!(
+                "Connection::handle_message >>> failed to record last seen, err: {:?}",
+                err
+            );
+        }
+        events::publish_message_received(
+            ProtocolKind::Connection,
+            &self.source_id(),
+            &self.get_thread_id(),
+            &message,
+        );
         let pw_vk = &self.pairwise_info().pw_vk;
         match message {
             A2AMessage::Ping(ping) => {
                 info!("Answering ping, thread: {}", ping.get_thread_id());
-                if ping.response_requested {
-                    send_message(
-                        wallet_handle,
-                        pw_vk.to_string(),
-                        did_doc.clone(),
-                        build_ping_response(&ping).to_a2a_message(),
-                    )
-                    .await?;
+                respond_to_ping(&ping, self.send_message_closure(wallet_handle)?).await?;
+            }
+            A2AMessage::PingResponse(ping_response) => {
+                match record_ping_response(wallet_handle, &self.get_thread_id(), &ping_response.get_thread_id()).await {
+                    Ok(rtt) => info!(
+                        "Received ping response, thread: {}, round-trip time: {:?} ms",
+                        ping_response.get_thread_id(),
+                        rtt
+                    ),
+                    Err(err) => warn!(
+                        "Received ping response, thread: {}, failed to record it, err: {:?}",
+                        ping_response.get_thread_id(),
+                        err
+                    ),
                 }
             }
             A2AMessage::OutOfBandHandshakeReuse(handshake_reuse) => {
@@ -446,12 +515,57 @@ impl Connection {
                 info!("Handling disclose message, thread: {}", disclose.get_thread_id());
                 self.connection_sm = self.handle_disclose(disclose).await?;
             }
+            A2AMessage::FeatureQueries(queries) => {
+                let supported_features = ProtocolRegistry::init().get_features_for_queries(&queries.queries);
+                info!(
+                    "Answering discover features queries, @id: {}, with supported features: {:?}",
+                    queries.id.0, &supported_features
+                );
+                respond_feature_queries(wallet_handle, queries, &did_doc, pw_vk, supported_features).await?;
+            }
+            A2AMessage::FeatureDisclosures(disclosures) => {
+                info!("Handling disclosures message, thread: {}", disclosures.get_thread_id());
+                self.connection_sm = self.handle_disclosures(disclosures)?;
+            }
+            A2AMessage::DidRotate(rotate) => {
+                info!("Handling did rotate message, new did: {}", rotate.to_did);
+                let pw_vk = pw_vk.to_string();
+                let new_did_doc = verify_rotate_msg(&rotate, &self.remote_vk()?).await?;
+                self.connection_sm = self.handle_did_rotate(new_did_doc.clone())?;
+                send_message(
+                    wallet_handle,
+                    pw_vk,
+                    new_did_doc,
+                    build_rotate_ack(&rotate).to_a2a_message(),
+                )
+                .await?;
+            }
+            A2AMessage::BasicMessage(basic_message) => {
+                info!(
+                    "Storing received basic message, thread: {}",
+                    basic_message.get_thread_id()
+                );
+                store_basic_message(
+                    wallet_handle,
+                    &self.get_thread_id(),
+                    BasicMessageDirection::Received,
+                    &basic_message,
+                )
+                .await?;
+            }
+            A2AMessage::DidRotateAck(ack) => {
+                info!(
+                    "Counterparty acknowledged did rotation, thread: {}",
+                    ack.get_thread_id()
+                );
+            }
             _ => {
                 // todo: implement to_string for A2AMessage, printing only type of the message, not entire payload
                 // todo: attempt to print @id / thread_id of the message
                 info!("Message of type {:?} will not be answered", message);
             }
         }
+        protocol_repository::persist(self, Some(&self.get_thread_id())).await;
         Ok(())
     }
 
@@ -460,6 +574,12 @@ impl Connection {
         wallet_handle: WalletHandle,
         agency_client: &AgencyClient,
     ) -> VcxResult<()> {
+        if let Err(err) = self.remove_expired_rotated_did(agency_client).await {
+            warn!(
+                "Connection::update_state >>> failed to delete cloud agent of rotated DID, err: {:?}",
+                err
+            );
+        }
         if self.is_in_null_state() {
             warn!("Connection::update_state :: update state on connection in null state is ignored");
             return Ok(());
@@ -479,6 +599,12 @@ impl Connection {
         match self.find_message_to_update_state(messages) {
             Some((uid, message)) => {
                 trace!("Connection::update_state >>> handling message uid: {:?}", uid);
+                if let Err(err) = record_last_seen(wallet_handle, &self.get_thread_id()).await {
+                    warn!(
+                        "Connection::update_state >>> failed to record last seen, err: {:?}",
+                        err
+                    );
+                }
                 self.update_state_with_message(wallet_handle, agency_client.clone(), Some(message))
                     .await?;
                 self.cloud_agent_info()
@@ -548,6 +674,7 @@ impl Connection {
                     cloud_agent_info: new_cloud_agent_info.unwrap_or(self.cloud_agent_info.clone()),
                     connection_sm: SmConnection::Inviter(sm_inviter),
                     autohop_enabled: self.autohop_enabled,
+                    rotated_pairwise_info: self.rotated_pairwise_info.clone(),
                 };
 
                 Ok((connection, can_autohop))
@@ -585,6 +712,7 @@ impl Connection {
                     connection_sm: SmConnection::Invitee(sm_invitee),
                     cloud_agent_info: self.cloud_agent_info.clone(),
                     autohop_enabled: self.autohop_enabled,
+                    rotated_pairwise_info: self.rotated_pairwise_info.clone(),
                 };
                 Ok((connection, can_autohop))
             }
@@ -607,22 +735,195 @@ impl Connection {
         }
     }
 
+    fn handle_disclosures(&self, disclosures: FeatureDisclosures) -> VcxResult<SmConnection> {
+        match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => Ok(SmConnection::Inviter(
+                sm_inviter.clone().handle_disclosures(disclosures)?,
+            )),
+            SmConnection::Invitee(sm_invitee) => Ok(SmConnection::Invitee(
+                sm_invitee.clone().handle_disclosures(disclosures)?,
+            )),
+        }
+    }
+
+    fn handle_did_rotate(&self, did_doc: DidDoc) -> VcxResult<SmConnection> {
+        match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => {
+                Ok(SmConnection::Inviter(sm_inviter.clone().handle_did_rotate(did_doc)?))
+            }
+            SmConnection::Invitee(sm_invitee) => {
+                Ok(SmConnection::Invitee(sm_invitee.clone().handle_did_rotate(did_doc)?))
+            }
+        }
+    }
+
+    /// Replaces pairwise DID and keys of this connection and notifies the counterparty by message signed
+    /// with the old key. Messages delivered to the old keys keep being accepted during `grace_period`.
+    pub async fn rotate_did(
+        &mut self,
+        wallet_handle: WalletHandle,
+        agency_client: &AgencyClient,
+        grace_period: chrono::Duration,
+    ) -> VcxResult<()> {
+        let send_message = self.send_message_closure(wallet_handle)?;
+        self._rotate_did(wallet_handle, agency_client, grace_period, send_message)
+            .await
+    }
+
+    async fn _rotate_did(
+        &mut self,
+        wallet_handle: WalletHandle,
+        agency_client: &AgencyClient,
+        grace_period: chrono::Duration,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        trace!("Connection::rotate_did >>> source_id: {}", self.source_id());
+        if self.their_did_doc().is_none() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::NotReady,
+                "Cannot rotate DID: Remote Connection information is not set",
+            ));
+        }
+        let old_pairwise_info = self.pairwise_info().clone();
+        let new_pairwise_info = PairwiseInfo::create(wallet_handle).await?;
+        let new_cloud_agent = CloudAgentInfo::create(agency_client, &new_pairwise_info).await?;
+        let connection_sm = match self
+            ._send_rotate(
+                wallet_handle,
+                agency_client,
+                &old_pairwise_info,
+                &new_pairwise_info,
+                &new_cloud_agent,
+                send_message,
+            )
+            .await
+        {
+            Ok(connection_sm) => connection_sm,
+            Err(err) => {
+                if let Err(destroy_err) = new_cloud_agent.destroy(agency_client, &new_pairwise_info).await {
+                    warn!(
+                        "Connection::rotate_did >>> failed to delete cloud agent of the new DID, err: {:?}",
+                        destroy_err
+                    );
+                }
+                return Err(err);
+            }
+        };
+        let replaced = self.rotated_pairwise_info.replace(RotatedPairwiseInfo::new(
+            old_pairwise_info,
+            self.cloud_agent_info.clone(),
+            grace_period,
+        ));
+        self.connection_sm = connection_sm;
+        self.cloud_agent_info = new_cloud_agent;
+        if let Some(replaced) = replaced {
+            if let Err(err) = replaced
+                .cloud_agent_info
+                .destroy(agency_client, &replaced.pairwise_info)
+                .await
+            {
+                warn!(
+                    "Connection::rotate_did >>> failed to delete cloud agent of previously rotated DID, err: {:?}",
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn _send_rotate(
+        &self,
+        wallet_handle: WalletHandle,
+        agency_client: &AgencyClient,
+        old_pairwise_info: &PairwiseInfo,
+        new_pairwise_info: &PairwiseInfo,
+        new_cloud_agent: &CloudAgentInfo,
+        send_message: SendClosure,
+    ) -> VcxResult<SmConnection> {
+        let new_did_doc = build_did_doc(
+            &new_pairwise_info.pw_did,
+            &new_pairwise_info.pw_vk,
+            new_cloud_agent.routing_keys(agency_client)?,
+            new_cloud_agent.service_endpoint(agency_client)?,
+        );
+        let rotate = build_rotate_msg(
+            wallet_handle,
+            &old_pairwise_info.pw_vk,
+            &new_pairwise_info.pw_did,
+            new_did_doc,
+        )
+        .await?;
+        let connection_sm = match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => {
+                SmConnection::Inviter(sm_inviter.clone().rotate_pairwise_info(new_pairwise_info.clone())?)
+            }
+            SmConnection::Invitee(sm_invitee) => {
+                SmConnection::Invitee(sm_invitee.clone().rotate_pairwise_info(new_pairwise_info.clone())?)
+            }
+        };
+        send_message(rotate.to_a2a_message()).await?;
+        Ok(connection_sm)
+    }
+
+    pub fn rotated_pairwise_info(&self) -> Option<&RotatedPairwiseInfo> {
+        self.rotated_pairwise_info.as_ref().filter(|rotated| rotated.is_valid())
+    }
+
+    /// Deletes the cloud agent of the rotated DID once its grace period has passed.
+    pub async fn remove_expired_rotated_did(&mut self, agency_client: &AgencyClient) -> VcxResult<()> {
+        match &self.rotated_pairwise_info {
+            Some(rotated) if !rotated.is_valid() => {
+                trace!(
+                    "Connection::remove_expired_rotated_did >>> deleting cloud agent of rotated DID {}",
+                    rotated.pairwise_info.pw_did
+                );
+                rotated
+                    .cloud_agent_info
+                    .destroy(agency_client, &rotated.pairwise_info)
+                    .await?;
+                self.rotated_pairwise_info = None;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub async fn connect(&mut self, wallet_handle: WalletHandle, agency_client: &AgencyClient) -> VcxResult<()> {
         trace!("Connection::connect >>> source_id: {}", self.source_id());
+        let routing_keys = self.cloud_agent_info.routing_keys(agency_client)?;
+        let service_endpoint = self.cloud_agent_info.service_endpoint(agency_client)?;
+        self.connect_with_routing(wallet_handle, routing_keys, service_endpoint)
+            .await
+    }
+
+    pub async fn connect_with_mediator(
+        &mut self,
+        wallet_handle: WalletHandle,
+        mediation_recipient: &MediationRecipient,
+    ) -> VcxResult<()> {
+        trace!("Connection::connect_with_mediator >>> source_id: {}", self.source_id());
+        self.connect_with_routing(
+            wallet_handle,
+            mediation_recipient.routing_keys()?,
+            mediation_recipient.service_endpoint()?,
+        )
+        .await
+    }
+
+    async fn connect_with_routing(
+        &mut self,
+        wallet_handle: WalletHandle,
+        routing_keys: Vec<String>,
+        service_endpoint: String,
+    ) -> VcxResult<()> {
         self.connection_sm = match &self.connection_sm {
-            SmConnection::Inviter(sm_inviter) => SmConnection::Inviter(sm_inviter.clone().create_invitation(
-                self.cloud_agent_info.routing_keys(agency_client)?,
-                self.cloud_agent_info.service_endpoint(agency_client)?,
-            )?),
+            SmConnection::Inviter(sm_inviter) => {
+                SmConnection::Inviter(sm_inviter.clone().create_invitation(routing_keys, service_endpoint)?)
+            }
             SmConnection::Invitee(sm_invitee) => SmConnection::Invitee(
                 sm_invitee
                     .clone()
-                    .send_connection_request(
-                        wallet_handle,
-                        self.cloud_agent_info.routing_keys(agency_client)?,
-                        self.cloud_agent_info.service_endpoint(agency_client)?,
-                        send_message,
-                    )
+                    .send_connection_request(wallet_handle, routing_keys, service_endpoint, send_message)
                     .await?,
             ),
         };
@@ -631,42 +932,52 @@ impl Connection {
 
     pub async fn update_message_status(&self, uid: &str, agency_client: &AgencyClient) -> VcxResult<()> {
         trace!("Connection::update_message_status >>> uid: {:?}", uid);
-        self.cloud_agent_info()
+        let res = self
+            .cloud_agent_info()
             .update_message_status(agency_client, self.pairwise_info(), uid.to_string())
-            .await
+            .await;
+        match (res, self.rotated_pairwise_info()) {
+            (Err(_), Some(rotated)) => {
+                rotated
+                    .cloud_agent_info
+                    .update_message_status(agency_client, &rotated.pairwise_info, uid.to_string())
+                    .await
+            }
+            (res, _) => res,
+        }
     }
 
     pub async fn get_messages_noauth(&self, agency_client: &AgencyClient) -> VcxResult<HashMap<String, A2AMessage>> {
-        match &self.connection_sm {
-            SmConnection::Inviter(sm_inviter) => {
-                let messages = self
-                    .cloud_agent_info()
-                    .get_messages_noauth(agency_client, sm_inviter.pairwise_info(), None)
-                    .await?;
-                Ok(messages)
-            }
-            SmConnection::Invitee(sm_invitee) => {
-                let messages = self
-                    .cloud_agent_info()
-                    .get_messages_noauth(agency_client, sm_invitee.pairwise_info(), None)
-                    .await?;
-                Ok(messages)
-            }
+        let mut messages = self
+            .cloud_agent_info()
+            .get_messages_noauth(agency_client, self.pairwise_info(), None)
+            .await?;
+        if let Some(rotated) = self.rotated_pairwise_info() {
+            messages.extend(
+                rotated
+                    .cloud_agent_info
+                    .get_messages_noauth(agency_client, &rotated.pairwise_info, None)
+                    .await?,
+            );
         }
+        Ok(messages)
     }
 
     pub async fn get_messages(&self, agency_client: &AgencyClient) -> VcxResult<HashMap<String, A2AMessage>> {
         let expected_sender_vk = self.get_expected_sender_vk()?;
-        match &self.connection_sm {
-            SmConnection::Inviter(sm_inviter) => Ok(self
-                .cloud_agent_info()
-                .get_messages(agency_client, &expected_sender_vk, sm_inviter.pairwise_info())
-                .await?),
-            SmConnection::Invitee(sm_invitee) => Ok(self
-                .cloud_agent_info()
-                .get_messages(agency_client, &expected_sender_vk, sm_invitee.pairwise_info())
-                .await?),
+        let mut messages = self
+            .cloud_agent_info()
+            .get_messages(agency_client, &expected_sender_vk, self.pairwise_info())
+            .await?;
+        if let Some(rotated) = self.rotated_pairwise_info() {
+            messages.extend(
+                rotated
+                    .cloud_agent_info
+                    .get_messages(agency_client, &expected_sender_vk, &rotated.pairwise_info)
+                    .await?,
+            );
         }
+        Ok(messages)
     }
 
     fn get_expected_sender_vk(&self) -> VcxResult<String> {
@@ -717,6 +1028,37 @@ impl Connection {
         send_message(message).await.map(|_| String::new())
     }
 
+    pub async fn send_basic_message(
+        &self,
+        wallet_handle: WalletHandle,
+        content: &str,
+        locale: Option<&str>,
+        reply_to: Option<&BasicMessage>,
+    ) -> VcxResult<BasicMessage> {
+        trace!("Connection::send_basic_message >>> content: {:?}", content);
+        let message = build_basic_message(content, locale, reply_to);
+        let send_message = self.send_message_closure(wallet_handle)?;
+        send_message(message.to_a2a_message()).await?;
+        store_basic_message(
+            wallet_handle,
+            &self.get_thread_id(),
+            BasicMessageDirection::Sent,
+            &message,
+        )
+        .await?;
+        Ok(message)
+    }
+
+    pub async fn get_basic_messages(
+        &self,
+        wallet_handle: WalletHandle,
+        thread_id: Option<&str>,
+        offset: usize,
+        limit: usize,
+    ) -> VcxResult<BasicMessagePage> {
+        get_basic_messages(wallet_handle, &self.get_thread_id(), thread_id, offset, limit).await
+    }
+
     pub async fn send_a2a_message(&self, wallet_handle: WalletHandle, message: &A2AMessage) -> VcxResult<String> {
         trace!("Connection::send_a2a_message >>> message: {:?}", message);
         let send_message = self.send_message_closure(wallet_handle)?;
@@ -730,9 +1072,17 @@ impl Connection {
     ) -> VcxResult<TrustPingSender> {
         let mut trust_ping = TrustPingSender::build(true, comment);
         trust_ping.send_ping(self.send_message_closure(wallet_handle)?).await?;
+        if let Err(err) = record_ping_sent(wallet_handle, &self.get_thread_id(), &trust_ping.get_thread_id()).await {
+            warn!("Connection::send_ping >>> failed to record sent ping, err: {:?}", err);
+        }
         Ok(trust_ping)
     }
 
+    /// Returns last-seen timestamp and the latest ping round-trip time of this connection.
+    pub async fn get_health(&self, wallet_handle: WalletHandle) -> VcxResult<ConnectionHealth> {
+        get_connection_health(wallet_handle, &self.get_thread_id()).await
+    }
+
     pub async fn send_handshake_reuse(&self, wallet_handle: WalletHandle, oob_msg: &str) -> VcxResult<()> {
         trace!("Connection::send_handshake_reuse >>>");
         // todo: oob_msg argument should be typed OutOfBandInvitation, not string
@@ -768,6 +1118,12 @@ impl Connection {
 
     pub async fn delete(&self, agency_client: &AgencyClient) -> VcxResult<()> {
         trace!("Connection: delete >>> {:?}", self.source_id());
+        if let Some(rotated) = &self.rotated_pairwise_info {
+            rotated
+                .cloud_agent_info
+                .destroy(agency_client, &rotated.pairwise_info)
+                .await?;
+        }
         self.cloud_agent_info()
             .destroy(agency_client, self.pairwise_info())
             .await
@@ -792,6 +1148,15 @@ impl Connection {
         Ok(())
     }
 
+    pub async fn send_feature_queries(&self, wallet_handle: WalletHandle, queries: Vec<FeatureQuery>) -> VcxResult<()> {
+        trace!("Connection::send_feature_queries >>> queries: {:?}", queries);
+        let did_doc = self.their_did_doc().ok_or(VcxError::from_msg(
+            VcxErrorKind::NotReady,
+            "Can't send feature queries to the counterparty, because their did doc is not available",
+        ))?;
+        send_feature_queries(wallet_handle, queries, &did_doc, &self.pairwise_info().pw_vk).await
+    }
+
     pub fn get_connection_info(&self, agency_client: &AgencyClient) -> VcxResult<String> {
         trace!("Connection::get_connection_info >>>");
 
@@ -856,15 +1221,28 @@ impl Connection {
             }
             _ => {
                 let expected_sender_vk = self.remote_vk()?;
-                let msgs = futures::stream::iter(
-                    self.cloud_agent_info()
-                        .download_encrypted_messages(agency_client, uids, status_codes, self.pairwise_info())
-                        .await?,
-                )
-                .then(|msg| msg.decrypt_auth(agency_client.get_wallet_handle(), &expected_sender_vk))
-                .filter_map(|res| async { res.ok() })
-                .collect::<Vec<DownloadedMessage>>()
-                .await;
+                let mut encrypted_msgs = self
+                    .cloud_agent_info()
+                    .download_encrypted_messages(
+                        agency_client,
+                        uids.clone(),
+                        status_codes.clone(),
+                        self.pairwise_info(),
+                    )
+                    .await?;
+                if let Some(rotated) = self.rotated_pairwise_info() {
+                    encrypted_msgs.extend(
+                        rotated
+                            .cloud_agent_info
+                            .download_encrypted_messages(agency_client, uids, status_codes, &rotated.pairwise_info)
+                            .await?,
+                    );
+                }
+                let msgs = futures::stream::iter(encrypted_msgs)
+                    .then(|msg| msg.decrypt_auth(agency_client.get_wallet_handle(), &expected_sender_vk))
+                    .filter_map(|res| async { res.ok() })
+                    .collect::<Vec<DownloadedMessage>>()
+                    .await;
                 Ok(msgs)
             }
         }
@@ -900,6 +1278,7 @@ impl Serialize for Connection {
             pw_vk: pairwise_info.pw_vk,
             agent_did: cloud_agent_info.agent_did,
             agent_vk: cloud_agent_info.agent_vk,
+            rotated: self.rotated_pairwise_info.clone(),
         };
         let object = SerializableObjectWithState::V1 {
             data,
@@ -907,7 +1286,8 @@ impl Serialize for Connection {
             source_id,
             thread_id,
         };
-        serializer.serialize_some(&object)
+        let object = serde_json::to_value(&object).map_err(<S::Error as serde::ser::Error>::custom)?;
+        serializer.serialize_some(&CONNECTION_MIGRATIONS.with_schema_version(object))
     }
 }
 
@@ -930,7 +1310,9 @@ impl<'de> Visitor<'de> for ConnectionVisitor {
             let v: Value = map.next_value()?;
             map_value.insert(k, v);
         }
-        let obj = Value::from(map_value);
+        let obj = CONNECTION_MIGRATIONS
+            .migrate(Value::from(map_value))
+            .map_err(|err| A::Error::custom(err.to_string()))?;
         let ver: SerializableObjectWithState<LegacyAgentInfo, SmConnectionState> =
             serde_json::from_value(obj).map_err(|err| A::Error::custom(err.to_string()))?;
         match ver {
@@ -948,7 +1330,9 @@ impl<'de> Visitor<'de> for ConnectionVisitor {
                     agent_did: data.agent_did,
                     agent_vk: data.agent_vk,
                 };
-                Ok((state, pairwise_info, cloud_agent_info, source_id, thread_id).into())
+                let mut connection: Connection = (state, pairwise_info, cloud_agent_info, source_id, thread_id).into();
+                connection.rotated_pairwise_info = data.rotated;
+                Ok(connection)
             }
         }
     }
@@ -1116,6 +1500,30 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rotate_did_keeps_connection_when_sending_fails() {
+        let _setup = SetupMocks::init();
+        let agency_client = AgencyClient::new();
+        enable_agency_mocks();
+        let mut connection = Connection::from_string(CONNECTION_SM_INVITER_COMPLETED).unwrap();
+        let send_message: SendClosure =
+            Box::new(|_: A2AMessage| Box::pin(async { Err(VcxError::from(VcxErrorKind::IOError)) }));
+
+        let err = connection
+            ._rotate_did(
+                WalletHandle(0),
+                &agency_client,
+                chrono::Duration::minutes(5),
+                send_message,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::IOError);
+        assert_eq!(connection.pairwise_info().pw_did, "2ZHFFhzA2XtTD6hJqzL7ux");
+        assert_eq!(connection.cloud_agent_info().agent_did, "EZrZyu4bfydm4ByNm56kPP");
+        assert!(connection.rotated_pairwise_info().is_none());
+    }
+
     #[tokio::test]
     async fn test_deserialize_connection_inviter_completed() {
         let _setup = SetupMocks::init();
@@ -1145,7 +1553,12 @@ mod tests {
         let reserialized = connection.to_string().unwrap();
         let reserialized_object: Value = serde_json::from_str(&reserialized).unwrap();
 
-        assert_eq!(original_object, reserialized_object);
+        assert_eq!(
+            CONNECTION_MIGRATIONS.with_schema_version(original_object),
+            reserialized_object
+        );
+        let connection = Connection::from_string(&reserialized).unwrap();
+        assert_eq!(connection.to_string().unwrap(), reserialized);
     }
 
     #[tokio::test]
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::Utc;
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::libindy::utils::wallet::{add_wallet_record, search_wallet_records, update_wallet_record_value};
+
+pub const CONNECTION_HEALTH_RECORD_TYPE: &str = "connection_health";
+
+const MAX_PENDING_PINGS: usize = 10;
+
+// Receiving a message refreshes the last-seen time in memory on every call, but writes it to the wallet
+// at most once per this interval for each connection.
+const LAST_SEEN_WRITE_INTERVAL_MS: i64 = 60_000;
+
+#[derive(Debug, Clone, Copy)]
+struct LastSeen {
+    seen_at: i64,
+    written_at: i64,
+}
+
+lazy_static! {
+    static ref LAST_SEEN: Mutex<HashMap<String, LastSeen>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PendingPing {
+    pub thread_id: String,
+    pub sent_at: i64,
+}
+
+/// Liveness of a connection. Timestamps are unix time in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ConnectionHealth {
+    pub connection_id: String,
+    pub last_seen: Option<i64>,
+    pub last_ping_sent: Option<i64>,
+    pub last_rtt_ms: Option<u64>,
+    #[serde(default)]
+    pub pending_pings: Vec<PendingPing>,
+}
+
+impl ConnectionHealth {
+    pub fn new(connection_id: &str) -> Self {
+        Self {
+            connection_id: connection_id.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn record_seen(&mut self, now: i64) {
+        self.last_seen = Some(now);
+    }
+
+    pub fn record_ping_sent(&mut self, thread_id: &str, now: i64) {
+        self.last_ping_sent = Some(now);
+        self.pending_pings.push(PendingPing {
+            thread_id: thread_id.to_string(),
+            sent_at: now,
+        });
+        if self.pending_pings.len() > MAX_PENDING_PINGS {
+            self.pending_pings.remove(0);
+        }
+    }
+
+    /// Returns round-trip time of the ping the response belongs to, if the ping is known.
+    pub fn record_ping_response(&mut self, thread_id: &str, now: i64) -> Option<u64> {
+        self.record_seen(now);
+        let index = self.pending_pings.iter().position(|ping| ping.thread_id == thread_id)?;
+        let ping = self.pending_pings.remove(index);
+        let rtt = (now - ping.sent_at).max(0) as u64;
+        self.last_rtt_ms = Some(rtt);
+        Some(rtt)
+    }
+
+    /// Connection is stale if nothing has been received from the counterparty for longer than `max_idle`.
+    pub fn is_stale(&self, max_idle: Duration, now: i64) -> bool {
+        match self.last_seen {
+            Some(last_seen) => now - last_seen > max_idle.as_millis() as i64,
+            None => true,
+        }
+    }
+}
+
+pub async fn get_connection_health(wallet_handle: WalletHandle, connection_id: &str) -> VcxResult<ConnectionHealth> {
+    trace!("get_connection_health >>> connection_id: {}", connection_id);
+    let health = _find_connection_health(wallet_handle, connection_id)
+        .await?
+        .unwrap_or_else(|| ConnectionHealth::new(connection_id));
+    Ok(_with_unwritten_last_seen(health))
+}
+
+pub async fn get_all_connection_health(wallet_handle: WalletHandle) -> VcxResult<Vec<ConnectionHealth>> {
+    trace!("get_all_connection_health >>>");
+    Ok(_search_connection_health(wallet_handle, json!({}))
+        .await?
+        .into_iter()
+        .map(_with_unwritten_last_seen)
+        .collect())
+}
+
+/// Returns connections from which nothing has been received for longer than `max_idle`.
+pub async fn get_stale_connections(
+    wallet_handle: WalletHandle,
+    max_idle: Duration,
+) -> VcxResult<Vec<ConnectionHealth>> {
+    trace!("get_stale_connections >>> max_idle: {:?}", max_idle);
+    let now = Utc::now().timestamp_millis();
+    Ok(get_all_connection_health(wallet_handle)
+        .await?
+        .into_iter()
+        .filter(|health| health.is_stale(max_idle, now))
+        .collect())
+}
+
+/// Records that a message was received from the counterparty. The wallet record is updated at most once
+/// per `LAST_SEEN_WRITE_INTERVAL_MS`, reads of the connection health include the latest value regardless.
+pub async fn record_last_seen(wallet_handle: WalletHandle, connection_id: &str) -> VcxResult<()> {
+    let now = Utc::now().timestamp_millis();
+    if !_should_write_last_seen(connection_id, now) {
+        return Ok(());
+    }
+    _update_connection_health(wallet_handle, connection_id, |health| health.record_seen(now)).await?;
+    Ok(())
+}
+
+fn _should_write_last_seen(connection_id: &str, now: i64) -> bool {
+    let mut last_seen = match LAST_SEEN.lock() {
+        Ok(last_seen) => last_seen,
+        Err(_) => return true,
+    };
+    match last_seen.get_mut(connection_id) {
+        Some(entry) if now - entry.written_at < LAST_SEEN_WRITE_INTERVAL_MS => {
+            entry.seen_at = entry.seen_at.max(now);
+            false
+        }
+        _ => {
+            last_seen.insert(
+                connection_id.to_string(),
+                LastSeen {
+                    seen_at: now,
+                    written_at: now,
+                },
+            );
+            true
+        }
+    }
+}
+
+fn _with_unwritten_last_seen(mut health: ConnectionHealth) -> ConnectionHealth {
+    let seen_at = LAST_SEEN
+        .lock()
+        .ok()
+        .and_then(|last_seen| last_seen.get(&health.connection_id).map(|entry| entry.seen_at));
+    if let Some(seen_at) = seen_at {
+        if health.last_seen.map_or(true, |last_seen| last_seen < seen_at) {
+            health.record_seen(seen_at);
+        }
+    }
+    health
+}
+
+pub async fn record_ping_sent(
+    wallet_handle: WalletHandle,
+    connection_id: &str,
+    thread_id: &str,
+) -> VcxResult<ConnectionHealth> {
+    let now = Utc::now().timestamp_millis();
+    _update_connection_health(wallet_handle, connection_id, |health| {
+        health.record_ping_sent(thread_id, now)
+    })
+    .await
+}
+
+pub async fn record_ping_response(
+    wallet_handle: WalletHandle,
+    connection_id: &str,
+    thread_id: &str,
+) -> VcxResult<Option<u64>> {
+    let now = Utc::now().timestamp_millis();
+    let mut rtt = None;
+    _update_connection_health(wallet_handle, connection_id, |health| {
+        rtt = health.record_ping_response(thread_id, now)
+    })
+    .await?;
+    Ok(rtt)
+}
+
+async fn _update_connection_health<F>(
+    wallet_handle: WalletHandle,
+    connection_id: &str,
+    update: F,
+) -> VcxResult<ConnectionHealth>
+where
+    F: FnOnce(&mut ConnectionHealth),
+{
+    let existing = _find_connection_health(wallet_handle, connection_id).await?;
+    let is_new = existing.is_none();
+    let mut health = existing.unwrap_or_else(|| ConnectionHealth::new(connection_id));
+    update(&mut health);
+    let value = serde_json::to_string(&health).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::SerializationError,
+            format!("Cannot serialize connection health: {}", err),
+        )
+    })?;
+    if is_new {
+        let tags = json!({ "connection_id": connection_id }).to_string();
+        add_wallet_record(
+            wallet_handle,
+            CONNECTION_HEALTH_RECORD_TYPE,
+            connection_id,
+            &value,
+            Some(&tags),
+        )
+        .await?;
+    } else {
+        update_wallet_record_value(wallet_handle, CONNECTION_HEALTH_RECORD_TYPE, connection_id, &value).await?;
+    }
+    Ok(health)
+}
+
+async fn _find_connection_health(
+    wallet_handle: WalletHandle,
+    connection_id: &str,
+) -> VcxResult<Option<ConnectionHealth>> {
+    Ok(
+        _search_connection_health(wallet_handle, json!({ "connection_id": connection_id }))
+            .await?
+            .pop(),
+    )
+}
+
+async fn _search_connection_health(
+    wallet_handle: WalletHandle,
+    query: serde_json::Value,
+) -> VcxResult<Vec<ConnectionHealth>> {
+    search_wallet_records(wallet_handle, CONNECTION_HEALTH_RECORD_TYPE, &query.to_string())
+        .await?
+        .iter()
+        .map(|record| {
+            serde_json::from_str(record.value.as_deref().unwrap_or_default()).map_err(|err| {
+                VcxError::from_msg(
+                    VcxErrorKind::InvalidJson,
+                    format!("Cannot deserialize connection health: {}", err),
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_response_measures_rtt() {
+        let mut health = ConnectionHealth::new("connection_id");
+        health.record_ping_sent("thread_1", 1000);
+        health.record_ping_sent("thread_2", 1500);
+
+        assert_eq!(health.record_ping_response("thread_1", 1250), Some(250));
+        assert_eq!(health.last_rtt_ms, Some(250));
+        assert_eq!(health.last_seen, Some(1250));
+        assert_eq!(health.pending_pings.len(), 1);
+
+        assert_eq!(health.record_ping_response("unknown", 1600), None);
+        assert_eq!(health.last_rtt_ms, Some(250));
+        assert_eq!(health.last_seen, Some(1600));
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let mut health = ConnectionHealth::new("connection_id");
+        assert!(health.is_stale(Duration::from_secs(60), 0));
+
+        health.record_seen(10_000);
+        assert!(!health.is_stale(Duration::from_secs(60), 70_000));
+        assert!(health.is_stale(Duration::from_secs(60), 70_001));
+    }
+
+    #[test]
+    fn test_last_seen_writes_are_throttled() {
+        let connection_id = "test_last_seen_writes_are_throttled";
+        assert!(_should_write_last_seen(connection_id, 1_000));
+        assert!(!_should_write_last_seen(connection_id, 2_000));
+        assert!(!_should_write_last_seen(
+            connection_id,
+            1_000 + LAST_SEEN_WRITE_INTERVAL_MS - 1
+        ));
+
+        let mut health = ConnectionHealth::new(connection_id);
+        health.record_seen(1_000);
+        let health = _with_unwritten_last_seen(health);
+        assert_eq!(health.last_seen, Some(1_000 + LAST_SEEN_WRITE_INTERVAL_MS - 1));
+
+        assert!(_should_write_last_seen(
+            connection_id,
+            1_000 + LAST_SEEN_WRITE_INTERVAL_MS
+        ));
+    }
+}
@@ -1,7 +1,12 @@
+use crate::handlers::connection::rotated_pairwise_info::RotatedPairwiseInfo;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LegacyAgentInfo {
     pub pw_did: String,
     pub pw_vk: String,
     pub agent_did: String,
     pub agent_vk: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotated: Option<RotatedPairwiseInfo>,
 }
@@ -1,4 +1,6 @@
 pub mod cloud_agent;
 pub mod connection;
+pub mod health;
 pub mod legacy_agent_info;
 pub mod public_agent;
+pub mod rotated_pairwise_info;
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::handlers::connection::cloud_agent::CloudAgentInfo;
+use crate::protocols::connection::pairwise_info::PairwiseInfo;
+
+/// Pairwise keys and cloud agent which were replaced by DID rotation. Messages sent by the counterparty
+/// to the old keys are still accepted until `valid_until`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RotatedPairwiseInfo {
+    pub pairwise_info: PairwiseInfo,
+    pub cloud_agent_info: CloudAgentInfo,
+    pub valid_until: String,
+}
+
+impl RotatedPairwiseInfo {
+    pub fn new(pairwise_info: PairwiseInfo, cloud_agent_info: CloudAgentInfo, grace_period: Duration) -> Self {
+        Self {
+            pairwise_info,
+            cloud_agent_info,
+            valid_until: (Utc::now() + grace_period).to_rfc3339(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match DateTime::parse_from_rfc3339(&self.valid_until) {
+            Ok(valid_until) => Utc::now() < valid_until,
+            Err(err) => {
+                warn!(
+                    "Failed to parse validity {} of rotated pairwise info, err: {:?}",
+                    self.valid_until, err
+                );
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    fn _rotated(grace_period: Duration) -> RotatedPairwiseInfo {
+        RotatedPairwiseInfo::new(
+            PairwiseInfo::default(),
+            CloudAgentInfo {
+                agent_did: "agent_did".to_string(),
+                agent_vk: "agent_vk".to_string(),
+            },
+            grace_period,
+        )
+    }
+
+    #[test]
+    fn test_rotated_pairwise_info_validity() {
+        assert!(_rotated(Duration::hours(1)).is_valid());
+        assert!(!_rotated(Duration::seconds(-1)).is_valid());
+    }
+}
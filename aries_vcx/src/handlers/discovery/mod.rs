@@ -4,6 +4,8 @@ use crate::did_doc::DidDoc;
 use crate::error::VcxResult;
 
 use crate::messages::discovery::disclose::{Disclose, ProtocolDescriptor};
+use crate::messages::discovery::disclosures::{Disclosure, FeatureDisclosures};
+use crate::messages::discovery::queries::{FeatureQueries, FeatureQuery};
 use crate::messages::discovery::query::Query;
 use crate::utils::send_message;
 
@@ -44,3 +46,40 @@ pub async fn respond_discovery_query(
     )
     .await
 }
+
+pub async fn send_feature_queries(
+    wallet_handle: WalletHandle,
+    queries: Vec<FeatureQuery>,
+    did_doc: &DidDoc,
+    pw_vk: &str,
+) -> VcxResult<()> {
+    let queries_ = FeatureQueries::create().set_queries(queries).set_out_time();
+    send_message(
+        wallet_handle,
+        pw_vk.to_string(),
+        did_doc.clone(),
+        queries_.to_a2a_message(),
+    )
+    .await
+}
+
+pub async fn respond_feature_queries(
+    wallet_handle: WalletHandle,
+    queries: FeatureQueries,
+    did_doc: &DidDoc,
+    pw_vk: &str,
+    supported_features: Vec<Disclosure>,
+) -> VcxResult<()> {
+    let disclosures = FeatureDisclosures::create()
+        .set_disclosures(supported_features)
+        .set_thread_id(&queries.id.0)
+        .set_out_time();
+
+    send_message(
+        wallet_handle,
+        pw_vk.to_string(),
+        did_doc.clone(),
+        disclosures.to_a2a_message(),
+    )
+    .await
+}
@@ -0,0 +1,44 @@
+/// Steps the holder takes on its own while updating state, none by default.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HolderAutoPolicy {
+    #[serde(default)]
+    pub trusted_issuers: Vec<String>,
+}
+
+impl HolderAutoPolicy {
+    pub fn create() -> Self {
+        HolderAutoPolicy::default()
+    }
+
+    /// Issuer DIDs whose credential offers are accepted by sending credential request right away.
+    pub fn set_trusted_issuers(mut self, trusted_issuers: Vec<String>) -> Self {
+        self.trusted_issuers = trusted_issuers;
+        self
+    }
+
+    pub fn is_trusted_issuer(&self, issuer_did: &str) -> bool {
+        self.trusted_issuers
+            .iter()
+            .any(|trusted_issuer| trusted_issuer == issuer_did)
+    }
+}
+
+/// Steps the issuer takes on its own while updating state, none by default.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct IssuerAutoPolicy {
+    #[serde(default)]
+    pub auto_issue: bool,
+}
+
+impl IssuerAutoPolicy {
+    pub fn create() -> Self {
+        IssuerAutoPolicy::default()
+    }
+
+    /// Issues the credential as soon as credential request for the offered credential definition
+    /// is received.
+    pub fn set_auto_issue(mut self, auto_issue: bool) -> Self {
+        self.auto_issue = auto_issue;
+        self
+    }
+}
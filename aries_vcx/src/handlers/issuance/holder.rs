@@ -1,28 +1,72 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 use indy_sys::WalletHandle;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
 
 use agency_client::agency_client::AgencyClient;
 
 use crate::error::prelude::*;
+use crate::events::{self, ProtocolKind};
 use crate::handlers::connection::connection::Connection;
+use crate::handlers::issuance::auto_policy::HolderAutoPolicy;
+use crate::handlers::util::deserialize_auto_policy;
+use crate::libindy::credentials::credential_store::{self, CredentialInfo, CredentialPage};
+use crate::libindy::proofs::proof_request_internal::Filter;
 use crate::messages::a2a::A2AMessage;
+use crate::messages::error::ProblemReport;
 use crate::messages::issuance::credential_offer::CredentialOffer;
 use crate::messages::issuance::credential_proposal::CredentialProposalData;
 use crate::protocols::issuance::actions::CredentialIssuanceAction;
-use crate::protocols::issuance::holder::state_machine::{HolderSM, HolderState};
+use crate::protocols::issuance::holder::state_machine::{parse_cred_def_id_from_cred_offer, HolderSM, HolderState};
+use crate::protocols::issuance::CredentialNegotiationMessage;
 use crate::protocols::SendClosure;
+use crate::utils::migrations::{HOLDER_MIGRATIONS, SCHEMA_VERSION_KEY};
+use crate::utils::protocol_repository;
+use crate::utils::qualifier;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(try_from = "Value")]
 pub struct Holder {
     holder_sm: HolderSM,
+    auto_policy: HolderAutoPolicy,
+}
+
+impl Serialize for Holder {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Holder", 3)?;
+        state.serialize_field(SCHEMA_VERSION_KEY, &HOLDER_MIGRATIONS.schema_version())?;
+        state.serialize_field("holder_sm", &self.holder_sm)?;
+        state.serialize_field("auto_policy", &self.auto_policy)?;
+        state.end()
+    }
+}
+
+impl TryFrom<Value> for Holder {
+    type Error = VcxError;
+
+    fn try_from(value: Value) -> VcxResult<Self> {
+        let auto_policy = deserialize_auto_policy(&value)?;
+        Ok(Self {
+            holder_sm: HOLDER_MIGRATIONS.state_machine(value, "holder_sm")?,
+            auto_policy,
+        })
+    }
 }
 
 impl Holder {
     pub fn create(source_id: &str) -> VcxResult<Holder> {
         trace!("Holder::create >>> source_id: {:?}", source_id);
         let holder_sm = HolderSM::new(source_id.to_string());
-        Ok(Holder { holder_sm })
+        Ok(Holder {
+            holder_sm,
+            auto_policy: HolderAutoPolicy::default(),
+        })
     }
 
     pub fn create_from_offer(source_id: &str, credential_offer: CredentialOffer) -> VcxResult<Holder> {
@@ -32,7 +76,19 @@ impl Holder {
             credential_offer
         );
         let holder_sm = HolderSM::from_offer(credential_offer, source_id.to_string());
-        Ok(Holder { holder_sm })
+        Ok(Holder {
+            holder_sm,
+            auto_policy: HolderAutoPolicy::default(),
+        })
+    }
+
+    pub fn set_auto_policy(&mut self, auto_policy: HolderAutoPolicy) {
+        trace!("Holder::set_auto_policy >>> auto_policy: {:?}", auto_policy);
+        self.auto_policy = auto_policy;
+    }
+
+    pub fn get_auto_policy(&self) -> &HolderAutoPolicy {
+        &self.auto_policy
     }
 
     pub async fn send_proposal(
@@ -89,6 +145,10 @@ impl Holder {
         self.holder_sm.get_state()
     }
 
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        self.holder_sm.get_problem_report()
+    }
+
     pub fn get_source_id(&self) -> String {
         self.holder_sm.get_source_id()
     }
@@ -125,6 +185,10 @@ impl Holder {
         self.holder_sm.get_thread_id()
     }
 
+    pub fn get_negotiation_history(&self) -> Vec<CredentialNegotiationMessage> {
+        self.holder_sm.get_negotiation_history()
+    }
+
     pub async fn is_revokable(&self, wallet_handle: WalletHandle) -> VcxResult<bool> {
         self.holder_sm.is_revokable(wallet_handle).await
     }
@@ -133,24 +197,117 @@ impl Holder {
         self.holder_sm.delete_credential(wallet_handle).await
     }
 
+    /// Reads the credential received by this holder back from the wallet.
+    pub async fn get_stored_credential(&self, wallet_handle: WalletHandle) -> VcxResult<CredentialInfo> {
+        let (cred_id, _) = self.get_credential()?;
+        credential_store::get_credential(wallet_handle, &cred_id).await
+    }
+
+    /// Returns page of all credentials held in the wallet matching the filter, regardless of the
+    /// exchange they were received in.
+    pub async fn get_stored_credentials(
+        wallet_handle: WalletHandle,
+        filter: &Filter,
+        offset: usize,
+        limit: usize,
+    ) -> VcxResult<CredentialPage> {
+        credential_store::get_credentials(wallet_handle, filter, offset, limit).await
+    }
+
     pub fn get_credential_status(&self) -> VcxResult<u32> {
         Ok(self.holder_sm.credential_status())
     }
 
+    /// Fails the issuance in any state, notifying the issuer only if `send_message` is provided.
+    pub async fn abandon(&mut self, reason: Option<&str>, send_message: Option<SendClosure>) -> VcxResult<()> {
+        trace!("Holder::abandon >>> reason: {:?}", reason);
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
+        self.holder_sm = self
+            .holder_sm
+            .clone()
+            .abandon(reason.map(String::from), send_message)
+            .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, None).await;
+        Ok(())
+    }
+
     pub async fn step(
         &mut self,
         wallet_handle: WalletHandle,
         message: CredentialIssuanceAction,
         send_message: Option<SendClosure>,
     ) -> VcxResult<()> {
+        self.step_on_connection(wallet_handle, message, send_message, None)
+            .await
+    }
+
+    /// Same as `step`, linking the stored record with `connection_id` if given.
+    async fn step_on_connection(
+        &mut self,
+        wallet_handle: WalletHandle,
+        message: CredentialIssuanceAction,
+        send_message: Option<SendClosure>,
+        connection_id: Option<&str>,
+    ) -> VcxResult<()> {
+        if let Some(received) = message.received_message() {
+            events::publish_message_received(
+                ProtocolKind::Holder,
+                &self.holder_sm.get_source_id(),
+                &self.get_thread_id().unwrap_or_default(),
+                &received,
+            );
+        }
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
         self.holder_sm = self
             .holder_sm
             .clone()
             .handle_message(wallet_handle, message, send_message)
             .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, connection_id).await;
+        Ok(())
+    }
+
+    /// Takes the steps automated by the auto policy which are possible in the current state, that
+    /// is accepting credential offer of a trusted issuer.
+    pub async fn apply_auto_policy(
+        &mut self,
+        wallet_handle: WalletHandle,
+        my_pw_did: &str,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        trace!("Holder::apply_auto_policy >>> auto_policy: {:?}", self.auto_policy);
+        if self.get_state() != HolderState::OfferReceived || self.auto_policy.trusted_issuers.is_empty() {
+            return Ok(());
+        }
+        let cred_def_id = parse_cred_def_id_from_cred_offer(&self.get_attachment()?)?;
+        let issuer_did = match qualifier::cred_def_issuer_did(&cred_def_id) {
+            Some(issuer_did) => issuer_did,
+            None => {
+                warn!("Cannot parse issuer DID from credential definition id {}", cred_def_id);
+                return Ok(());
+            }
+        };
+        if self.auto_policy.is_trusted_issuer(issuer_did) {
+            self.send_request(wallet_handle, my_pw_did.to_string(), send_message)
+                .await?;
+        }
         Ok(())
     }
 
+    fn publish_transition(&self, previous_state: HolderState, previous_problem_report: Option<ProblemReport>) {
+        events::publish_transition(
+            ProtocolKind::Holder,
+            &self.holder_sm.get_source_id(),
+            &self.get_thread_id().unwrap_or_default(),
+            (&previous_state, &self.get_state()),
+            (previous_problem_report, self.get_problem_report()),
+        )
+    }
+
     pub async fn update_state(
         &mut self,
         wallet_handle: WalletHandle,
@@ -165,9 +322,25 @@ impl Holder {
 
         let messages = connection.get_messages(agency_client).await?;
         if let Some((uid, msg)) = self.find_message_to_handle(messages) {
-            self.step(wallet_handle, msg.into(), Some(send_message)).await?;
+            self.step_on_connection(
+                wallet_handle,
+                msg.into(),
+                Some(send_message),
+                Some(&connection.get_thread_id()),
+            )
+            .await?;
             connection.update_message_status(&uid, agency_client).await?;
         }
+        if let Err(err) = self
+            .apply_auto_policy(
+                wallet_handle,
+                &connection.pairwise_info().pw_did,
+                connection.send_message_closure(wallet_handle)?,
+            )
+            .await
+        {
+            warn!("Holder::update_state >>> failed to apply auto policy, err: {:?}", err);
+        }
         Ok(self.get_state())
     }
 }
@@ -201,11 +374,13 @@ pub mod test_utils {
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 pub mod unit_tests {
-    use crate::messages::issuance::credential::test_utils::_credential;
+    use crate::messages::attachment::{AttachmentId, Attachments};
+    use crate::messages::issuance::credential::test_utils::_credential_issued_from_offer;
     use crate::messages::issuance::credential_offer::test_utils::_credential_offer;
     use crate::messages::issuance::credential_proposal::test_utils::_credential_proposal_data;
     use crate::messages::issuance::credential_request::test_utils::_my_pw_did;
     use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
 
     use super::*;
 
@@ -250,7 +425,7 @@ pub mod unit_tests {
             .unwrap();
             self.step(
                 _dummy_wallet_handle(),
-                CredentialIssuanceAction::Credential(_credential()),
+                CredentialIssuanceAction::Credential(_credential_issued_from_offer()),
                 _send_message(),
             )
             .await
@@ -259,6 +434,73 @@ pub mod unit_tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_apply_auto_policy_accepts_offer_of_trusted_issuer() {
+        let _setup = SetupMocks::init();
+        let mut holder = _holder_from_offer();
+
+        holder
+            .apply_auto_policy(_dummy_wallet_handle(), &_my_pw_did(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(HolderState::OfferReceived, holder.get_state());
+
+        holder.set_auto_policy(HolderAutoPolicy::create().set_trusted_issuers(vec!["V4SGRU86Z58d6TV7PBUe6f".into()]));
+        holder
+            .apply_auto_policy(_dummy_wallet_handle(), &_my_pw_did(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(HolderState::OfferReceived, holder.get_state());
+
+        holder.set_auto_policy(HolderAutoPolicy::create().set_trusted_issuers(vec!["NcYxiDXkpYi6ov5FcYDi1e".into()]));
+        holder
+            .apply_auto_policy(_dummy_wallet_handle(), &_my_pw_did(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(HolderState::RequestSent, holder.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_apply_auto_policy_accepts_qualified_offer_of_trusted_issuer() {
+        let _setup = SetupMocks::init();
+        let mut offer = _credential_offer();
+        offer.offers_attach = Attachments::new();
+        offer
+            .offers_attach
+            .add_base64_encoded_json_attachment(
+                AttachmentId::CredentialOffer,
+                json!({
+                    "schema_id": "schema:sov:did:sov:NcYxiDXkpYi6ov5FcYDi1e:2:gvt:1.0",
+                    "cred_def_id": "creddef:sov:did:sov:NcYxiDXkpYi6ov5FcYDi1e:3:CL:NcYxiDXkpYi6ov5FcYDi1e:2:gvt:1.0:TAG1"
+                }),
+            )
+            .unwrap();
+        let mut holder = Holder::create_from_offer("test_source_id", offer).unwrap();
+
+        holder.set_auto_policy(HolderAutoPolicy::create().set_trusted_issuers(vec!["NcYxiDXkpYi6ov5FcYDi1e".into()]));
+        holder
+            .apply_auto_policy(_dummy_wallet_handle(), &_my_pw_did(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(HolderState::RequestSent, holder.get_state());
+    }
+
+    #[test]
+    fn test_holder_serialization_keeps_auto_policy() {
+        let _setup = SetupMocks::init();
+        let mut holder = _holder_from_offer();
+        holder.set_auto_policy(HolderAutoPolicy::create().set_trusted_issuers(vec!["NcYxiDXkpYi6ov5FcYDi1e".into()]));
+
+        let deserialized: Holder = serde_json::from_str(&serde_json::to_string(&holder).unwrap()).unwrap();
+        assert_eq!(deserialized.get_auto_policy(), holder.get_auto_policy());
+
+        let mut serialized = serde_json::to_value(&holder).unwrap();
+        serialized.as_object_mut().unwrap().remove("auto_policy");
+        let deserialized: Holder = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized.get_auto_policy(), &HolderAutoPolicy::default());
+        assert_eq!(deserialized.get_state(), HolderState::OfferReceived);
+    }
+
     #[tokio::test]
     async fn exchange_credential_from_proposal_without_negotiation() {
         let _setup = SetupMocks::init();
@@ -266,6 +508,31 @@ pub mod unit_tests {
         assert_eq!(HolderState::Finished, holder.get_state());
     }
 
+    #[tokio::test]
+    async fn test_get_stored_credentials() {
+        let _setup = SetupMocks::init();
+        let holder = _holder_from_offer();
+        assert_eq!(
+            holder
+                .get_stored_credential(_dummy_wallet_handle())
+                .await
+                .unwrap_err()
+                .kind(),
+            VcxErrorKind::NotReady
+        );
+
+        let holder = _holder().to_finished_state().await;
+        let credential = holder.get_stored_credential(_dummy_wallet_handle()).await.unwrap();
+        assert_eq!(credential.attrs["name"], "Alice");
+
+        let _mock_builder = MockBuilder::init().set_mock_creds_found_by_search(&json!([credential]).to_string());
+        let page = Holder::get_stored_credentials(_dummy_wallet_handle(), &Filter::create(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.total_count, 1);
+        assert_eq!(page.credentials, vec![credential]);
+    }
+
     #[tokio::test]
     async fn exchange_credential_from_proposal_with_negotiation() {
         let _setup = SetupMocks::init();
@@ -311,6 +578,7 @@ pub mod unit_tests {
             .await
             .unwrap();
         assert_eq!(HolderState::OfferReceived, holder.get_state());
+        assert_eq!(holder.get_negotiation_history().len(), 4);
 
         holder
             .send_request(_dummy_wallet_handle(), _my_pw_did(), _send_message().unwrap())
@@ -319,7 +587,7 @@ pub mod unit_tests {
         assert_eq!(HolderState::RequestSent, holder.get_state());
 
         let messages = map!(
-            "key_1".to_string() => A2AMessage::Credential(_credential())
+            "key_1".to_string() => A2AMessage::Credential(_credential_issued_from_offer())
         );
         let (_, msg) = holder.find_message_to_handle(messages).unwrap();
         holder
@@ -1,24 +1,64 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
+use chrono::{Duration, Utc};
 use indy_sys::WalletHandle;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
 
 use agency_client::agency_client::AgencyClient;
 
 use crate::error::prelude::*;
+use crate::events::{self, ProtocolKind};
 use crate::handlers::connection::connection::Connection;
+use crate::handlers::issuance::auto_policy::IssuerAutoPolicy;
+use crate::handlers::util::deserialize_auto_policy;
 use crate::libindy::utils::anoncreds::libindy_issuer_create_credential_offer;
 use crate::messages::a2a::A2AMessage;
+use crate::messages::error::ProblemReport;
 use crate::messages::issuance::credential_offer::OfferInfo;
 use crate::messages::issuance::credential_proposal::CredentialProposal;
 use crate::messages::issuance::CredentialPreviewData;
 use crate::messages::mime_type::MimeType;
 use crate::protocols::issuance::actions::CredentialIssuanceAction;
 use crate::protocols::issuance::issuer::state_machine::{IssuerSM, IssuerState};
+use crate::protocols::issuance::{validate_credential_preview, CredentialNegotiationMessage};
 use crate::protocols::SendClosure;
+use crate::utils::encoding::parse_mime_type;
+use crate::utils::migrations::{ISSUER_MIGRATIONS, SCHEMA_VERSION_KEY};
+use crate::utils::protocol_repository;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(try_from = "Value")]
 pub struct Issuer {
     issuer_sm: IssuerSM,
+    auto_policy: IssuerAutoPolicy,
+}
+
+impl Serialize for Issuer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Issuer", 3)?;
+        state.serialize_field(SCHEMA_VERSION_KEY, &ISSUER_MIGRATIONS.schema_version())?;
+        state.serialize_field("issuer_sm", &self.issuer_sm)?;
+        state.serialize_field("auto_policy", &self.auto_policy)?;
+        state.end()
+    }
+}
+
+impl TryFrom<Value> for Issuer {
+    type Error = VcxError;
+
+    fn try_from(value: Value) -> VcxResult<Self> {
+        let auto_policy = deserialize_auto_policy(&value)?;
+        Ok(Self {
+            issuer_sm: ISSUER_MIGRATIONS.state_machine(value, "issuer_sm")?,
+            auto_policy,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -28,7 +68,7 @@ pub struct IssuerConfig {
     pub tails_file: Option<String>,
 }
 
-fn _build_credential_preview(credential_json: &str) -> VcxResult<CredentialPreviewData> {
+pub(crate) fn _build_credential_preview(credential_json: &str) -> VcxResult<CredentialPreviewData> {
     trace!(
         "Issuer::_build_credential_preview >>> credential_json: {:?}",
         secret!(credential_json)
@@ -47,16 +87,21 @@ fn _build_credential_preview(credential_json: &str) -> VcxResult<CredentialPrevi
     match cred_values {
         serde_json::Value::Array(cred_values) => {
             for cred_value in cred_values.iter() {
-                let key = cred_value.get("name").ok_or(VcxError::from_msg(
-                    VcxErrorKind::InvalidAttributesStructure,
-                    format!("No 'name' field in cred_value: {:?}", cred_value),
-                ))?;
-                let value = cred_value.get("value").ok_or(VcxError::from_msg(
-                    VcxErrorKind::InvalidAttributesStructure,
-                    format!("No 'value' field in cred_value: {:?}", cred_value),
-                ))?;
-                credential_preview =
-                    credential_preview.add_value(&key.to_string(), &value.to_string(), MimeType::Plain);
+                let key = cred_value
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or(VcxError::from_msg(
+                        VcxErrorKind::InvalidAttributesStructure,
+                        format!("No 'name' field in cred_value: {:?}", cred_value),
+                    ))?;
+                let value = cred_value
+                    .get("value")
+                    .and_then(Value::as_str)
+                    .ok_or(VcxError::from_msg(
+                        VcxErrorKind::InvalidAttributesStructure,
+                        format!("No 'value' field in cred_value: {:?}", cred_value),
+                    ))?;
+                credential_preview = credential_preview.add_value(key, value, parse_mime_type(cred_value)?);
             }
         }
         serde_json::Value::Object(values_map) => {
@@ -83,7 +128,10 @@ impl Issuer {
     pub fn create(source_id: &str) -> VcxResult<Issuer> {
         trace!("Issuer::create >>> source_id: {:?}", source_id);
         let issuer_sm = IssuerSM::new(source_id);
-        Ok(Issuer { issuer_sm })
+        Ok(Issuer {
+            issuer_sm,
+            auto_policy: IssuerAutoPolicy::default(),
+        })
     }
 
     pub fn create_from_proposal(source_id: &str, credential_proposal: &CredentialProposal) -> VcxResult<Issuer> {
@@ -93,7 +141,10 @@ impl Issuer {
             credential_proposal
         );
         let issuer_sm = IssuerSM::from_proposal(source_id, credential_proposal);
-        Ok(Issuer { issuer_sm })
+        Ok(Issuer {
+            issuer_sm,
+            auto_policy: IssuerAutoPolicy::default(),
+        })
     }
 
     // todo: "build_credential_offer_msg" should take optional revReg as parameter, build OfferInfo from that
@@ -105,22 +156,39 @@ impl Issuer {
     ) -> VcxResult<()> {
         let credential_preview = _build_credential_preview(&offer_info.credential_json)?;
         let libindy_cred_offer = libindy_issuer_create_credential_offer(wallet_handle, &offer_info.cred_def_id).await?;
+        validate_credential_preview(wallet_handle, &libindy_cred_offer, &credential_preview).await?;
+        let previous_state = self.get_state();
         self.issuer_sm = self.issuer_sm.clone().build_credential_offer_msg(
             &libindy_cred_offer,
             credential_preview,
             comment,
             &offer_info,
         )?;
+        self.publish_transition(previous_state, None);
         Ok(())
     }
 
+    /// Sets the time the holder has to respond to the credential offer which is not sent yet. The
+    /// issuer fails with a problem report if no credential request is received until then.
+    pub fn set_offer_expiration(&mut self, expires_in: Duration) -> VcxResult<()> {
+        trace!("Issuer::set_offer_expiration >>> expires_in: {:?}", expires_in);
+        self.issuer_sm = self.issuer_sm.clone().set_offer_expiration(Utc::now() + expires_in)?;
+        Ok(())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.issuer_sm.is_expired()
+    }
+
     pub fn get_credential_offer_msg(&self) -> VcxResult<A2AMessage> {
         let offer = self.issuer_sm.get_credential_offer_msg()?;
         Ok(offer.to_a2a_message())
     }
 
     pub fn mark_credential_offer_msg_sent(&mut self) -> VcxResult<()> {
+        let previous_state = self.get_state();
         self.issuer_sm = self.issuer_sm.clone().mark_credential_offer_msg_sent()?;
+        self.publish_transition(previous_state, None);
         Ok(())
     }
 
@@ -128,7 +196,8 @@ impl Issuer {
         if self.issuer_sm.get_state() == IssuerState::OfferSet {
             let cred_offer_msg = self.get_credential_offer_msg()?;
             send_message(cred_offer_msg).await?;
-            self.issuer_sm = self.issuer_sm.clone().mark_credential_offer_msg_sent()?;
+            self.mark_credential_offer_msg_sent()?;
+            protocol_repository::persist(self, None).await;
         } else {
             return Err(VcxError::from_msg(
                 VcxErrorKind::InvalidState,
@@ -138,6 +207,30 @@ impl Issuer {
         Ok(())
     }
 
+    pub fn set_auto_policy(&mut self, auto_policy: IssuerAutoPolicy) {
+        trace!("Issuer::set_auto_policy >>> auto_policy: {:?}", auto_policy);
+        self.auto_policy = auto_policy;
+    }
+
+    pub fn get_auto_policy(&self) -> &IssuerAutoPolicy {
+        &self.auto_policy
+    }
+
+    /// Takes the steps automated by the auto policy which are possible in the current state, that
+    /// is issuing credential requested for the offered credential definition.
+    pub async fn apply_auto_policy(&mut self, wallet_handle: WalletHandle, send_message: SendClosure) -> VcxResult<()> {
+        trace!("Issuer::apply_auto_policy >>> auto_policy: {:?}", self.auto_policy);
+        if !self.auto_policy.auto_issue || self.get_state() != IssuerState::RequestReceived {
+            return Ok(());
+        }
+        if self.issuer_sm.is_request_matching_offer()? {
+            self.send_credential(wallet_handle, send_message).await?;
+        } else {
+            warn!("Credential request does not match the offer, credential is not issued automatically");
+        }
+        Ok(())
+    }
+
     pub async fn send_credential(&mut self, wallet_handle: WalletHandle, send_message: SendClosure) -> VcxResult<()> {
         self.step(
             wallet_handle,
@@ -151,6 +244,10 @@ impl Issuer {
         self.issuer_sm.get_state()
     }
 
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        self.issuer_sm.get_problem_report()
+    }
+
     pub fn get_source_id(&self) -> VcxResult<String> {
         Ok(self.issuer_sm.get_source_id())
     }
@@ -179,6 +276,10 @@ impl Issuer {
         self.issuer_sm.get_proposal()
     }
 
+    pub fn get_negotiation_history(&self) -> Vec<CredentialNegotiationMessage> {
+        self.issuer_sm.get_negotiation_history()
+    }
+
     pub fn get_credential_status(&self) -> VcxResult<u32> {
         Ok(self.issuer_sm.credential_status())
     }
@@ -187,20 +288,78 @@ impl Issuer {
         self.issuer_sm.is_revokable()
     }
 
+    /// Fails the issuance in any state, notifying the holder only if `send_message` is provided.
+    pub async fn abandon(&mut self, reason: Option<&str>, send_message: Option<SendClosure>) -> VcxResult<()> {
+        trace!("Issuer::abandon >>> reason: {:?}", reason);
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
+        self.issuer_sm = self
+            .issuer_sm
+            .clone()
+            .abandon(reason.map(String::from), send_message)
+            .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, None).await;
+        Ok(())
+    }
+
     pub async fn step(
         &mut self,
         wallet_handle: WalletHandle,
         message: CredentialIssuanceAction,
         send_message: Option<SendClosure>,
     ) -> VcxResult<()> {
+        self.step_on_connection(wallet_handle, message, send_message, None)
+            .await
+    }
+
+    /// Same as `step`, linking the stored record with `connection_id` if given.
+    async fn step_on_connection(
+        &mut self,
+        wallet_handle: WalletHandle,
+        message: CredentialIssuanceAction,
+        send_message: Option<SendClosure>,
+        connection_id: Option<&str>,
+    ) -> VcxResult<()> {
+        if let Some(received) = message.received_message() {
+            events::publish_message_received(
+                ProtocolKind::Issuer,
+                &self.issuer_sm.get_source_id(),
+                &self.get_thread_id().unwrap_or_default(),
+                &received,
+            );
+        }
+        let message = if self.is_expired() {
+            warn!(
+                "Issuer::step >>> credential offer has expired, failing instead of handling {:?}",
+                message
+            );
+            CredentialIssuanceAction::CredentialOfferExpired
+        } else {
+            message
+        };
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
         self.issuer_sm = self
             .issuer_sm
             .clone()
             .handle_message(wallet_handle, message, send_message)
             .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, connection_id).await;
         Ok(())
     }
 
+    fn publish_transition(&self, previous_state: IssuerState, previous_problem_report: Option<ProblemReport>) {
+        events::publish_transition(
+            ProtocolKind::Issuer,
+            &self.issuer_sm.get_source_id(),
+            &self.get_thread_id().unwrap_or_default(),
+            (&previous_state, &self.get_state()),
+            (previous_problem_report, self.get_problem_report()),
+        )
+    }
+
     pub async fn update_state(
         &mut self,
         wallet_handle: WalletHandle,
@@ -215,8 +374,27 @@ impl Issuer {
 
         let messages = connection.get_messages(agency_client).await?;
         if let Some((uid, msg)) = self.find_message_to_handle(messages) {
-            self.step(wallet_handle, msg.into(), Some(send_message)).await?;
+            self.step_on_connection(
+                wallet_handle,
+                msg.into(),
+                Some(send_message),
+                Some(&connection.get_thread_id()),
+            )
+            .await?;
             connection.update_message_status(&uid, agency_client).await?;
+        } else if self.is_expired() {
+            self.step(
+                wallet_handle,
+                CredentialIssuanceAction::CredentialOfferExpired,
+                Some(send_message),
+            )
+            .await?;
+        }
+        if let Err(err) = self
+            .apply_auto_policy(wallet_handle, connection.send_message_closure(wallet_handle)?)
+            .await
+        {
+            warn!("Issuer::update_state >>> failed to apply auto policy, err: {:?}", err);
         }
         Ok(self.get_state())
     }
@@ -253,7 +431,10 @@ pub mod test_utils {
 #[cfg(feature = "general_test")]
 pub mod unit_tests {
     use crate::messages::ack::test_utils::_ack;
-    use crate::messages::issuance::credential_offer::test_utils::{_offer_info, _offer_info_unrevokable};
+    use crate::messages::error::ProblemCode;
+    use crate::messages::issuance::credential_offer::test_utils::{
+        _attachment, _offer_info, _offer_info_unrevokable, _preview_data,
+    };
     use crate::messages::issuance::credential_proposal::test_utils::_credential_proposal;
     use crate::messages::issuance::credential_request::test_utils::_credential_request;
     use crate::protocols::issuance::issuer::state_machine::unit_tests::_send_message;
@@ -347,6 +528,27 @@ pub mod unit_tests {
         assert_eq!(value_age.value, "123");
     }
 
+    #[tokio::test]
+    async fn test_build_credential_preview_from_attribute_list() {
+        let _setup = SetupMocks::init();
+        let input = json!([
+            {"name": "name", "value": "Alice"},
+            {"name": "birthdate", "value": "1990-04-21", "mime-type": "text/date"}
+        ])
+        .to_string();
+        let preview = _build_credential_preview(&input).unwrap();
+        assert_eq!(preview.attributes[0].name, "name");
+        assert_eq!(preview.attributes[0].value, "Alice");
+        assert_eq!(preview.attributes[0].mime_type(), MimeType::Plain);
+        assert_eq!(preview.attributes[1].mime_type(), MimeType::Date);
+
+        let input = json!([{"name": "name", "value": 1}]).to_string();
+        assert_eq!(
+            _build_credential_preview(&input).unwrap_err().kind(),
+            VcxErrorKind::InvalidAttributesStructure
+        );
+    }
+
     #[tokio::test]
     async fn test_cant_revoke_without_revocation_details() {
         let setup = SetupMocks::init();
@@ -375,6 +577,85 @@ pub mod unit_tests {
         assert_eq!(IssuerState::CredentialSent, issuer.get_state());
     }
 
+    #[tokio::test]
+    async fn test_step_fails_expired_offer() {
+        let _setup = SetupMocks::init();
+        let mut issuer = _issuer();
+        issuer
+            .build_credential_offer_msg(_dummy_wallet_handle(), _offer_info_unrevokable(), None)
+            .await
+            .unwrap();
+        issuer.set_offer_expiration(Duration::seconds(-1)).unwrap();
+        issuer.mark_credential_offer_msg_sent().unwrap();
+        assert!(issuer.is_expired());
+
+        issuer
+            .step(
+                _dummy_wallet_handle(),
+                CredentialIssuanceAction::CredentialProposal(_credential_proposal()),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(IssuerState::Failed, issuer.get_state());
+        assert_eq!(
+            Some(ProblemCode::RequestTime),
+            issuer.get_problem_report().unwrap().get_code()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_auto_policy_skips_request_not_matching_offer() {
+        let _setup = SetupMocks::init();
+        let mut issuer = _issuer().to_request_received_state().await;
+
+        issuer
+            .apply_auto_policy(_dummy_wallet_handle(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(IssuerState::RequestReceived, issuer.get_state());
+
+        issuer.set_auto_policy(IssuerAutoPolicy::create().set_auto_issue(true));
+        issuer
+            .apply_auto_policy(_dummy_wallet_handle(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(IssuerState::RequestReceived, issuer.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_apply_auto_policy_issues_credential_for_request_matching_offer() {
+        let _setup = SetupMocks::init();
+        let mut issuer = _issuer();
+        issuer.issuer_sm = issuer
+            .issuer_sm
+            .clone()
+            .build_credential_offer_msg(
+                &_attachment().to_string(),
+                _preview_data(),
+                None,
+                &_offer_info_unrevokable(),
+            )
+            .unwrap();
+        issuer.mark_credential_offer_msg_sent().unwrap();
+        issuer
+            .step(
+                _dummy_wallet_handle(),
+                CredentialIssuanceAction::CredentialRequest(_credential_request()),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(IssuerState::RequestReceived, issuer.get_state());
+
+        issuer.set_auto_policy(IssuerAutoPolicy::create().set_auto_issue(true));
+        issuer
+            .apply_auto_policy(_dummy_wallet_handle(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(IssuerState::CredentialSent, issuer.get_state());
+    }
+
     #[tokio::test]
     async fn exchange_credential_from_proposal_without_negotiation() {
         let _setup = SetupMocks::init();
@@ -444,6 +725,7 @@ pub mod unit_tests {
             .unwrap();
         issuer.send_credential_offer(_send_message().unwrap()).await.unwrap();
         assert_eq!(IssuerState::OfferSent, issuer.get_state());
+        assert_eq!(issuer.get_negotiation_history().len(), 4);
 
         let messages = map!(
             "key_1".to_string() => A2AMessage::CredentialRequest(_credential_request())
@@ -1,2 +1,3 @@
+pub mod auto_policy;
 pub mod holder;
 pub mod issuer;
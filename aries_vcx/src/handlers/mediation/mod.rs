@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use crate::error::prelude::*;
+use crate::messages::a2a::A2AMessage;
+use crate::protocols::mediation::recipient::{MediationRecipientSM, MediationRecipientState};
+use crate::protocols::SendClosure;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MediationRecipient {
+    recipient_sm: MediationRecipientSM,
+}
+
+impl MediationRecipient {
+    pub fn create(source_id: &str) -> Self {
+        trace!("MediationRecipient::create >>> source_id: {}", source_id);
+        Self {
+            recipient_sm: MediationRecipientSM::new(source_id),
+        }
+    }
+
+    pub async fn send_mediation_request(&mut self, send_message: SendClosure) -> VcxResult<()> {
+        self.recipient_sm = self.recipient_sm.clone().send_mediation_request(send_message).await?;
+        Ok(())
+    }
+
+    pub async fn update_keylist(
+        &mut self,
+        add: Vec<String>,
+        remove: Vec<String>,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        self.recipient_sm = self
+            .recipient_sm
+            .clone()
+            .send_keylist_update(add, remove, send_message)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn query_keylist(&mut self, paginate: Option<(u32, u32)>, send_message: SendClosure) -> VcxResult<()> {
+        self.recipient_sm = self
+            .recipient_sm
+            .clone()
+            .send_keylist_query(paginate, send_message)
+            .await?;
+        Ok(())
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
+        self.recipient_sm.find_message_to_handle(messages)
+    }
+
+    pub fn handle_message(&mut self, message: A2AMessage) -> VcxResult<()> {
+        trace!("MediationRecipient::handle_message >>> message: {:?}", message);
+        self.recipient_sm = self.recipient_sm.clone().handle_message(message)?;
+        Ok(())
+    }
+
+    pub fn get_state(&self) -> MediationRecipientState {
+        self.recipient_sm.get_state()
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.recipient_sm.get_source_id()
+    }
+
+    pub fn get_thread_id(&self) -> String {
+        self.recipient_sm.get_thread_id()
+    }
+
+    pub fn routing_keys(&self) -> VcxResult<Vec<String>> {
+        self.recipient_sm.get_routing_keys()
+    }
+
+    pub fn service_endpoint(&self) -> VcxResult<String> {
+        self.recipient_sm.get_endpoint()
+    }
+
+    pub fn get_keys(&self) -> VcxResult<Vec<String>> {
+        self.recipient_sm.get_keys()
+    }
+
+    pub fn to_string(&self) -> VcxResult<String> {
+        serde_json::to_string(&self).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::SerializationError,
+                format!("Cannot serialize MediationRecipient: {:?}", err),
+            )
+        })
+    }
+
+    pub fn from_string(recipient_data: &str) -> VcxResult<Self> {
+        serde_json::from_str(recipient_data).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Cannot deserialize MediationRecipient: {:?}", err),
+            )
+        })
+    }
+}
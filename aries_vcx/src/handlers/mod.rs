@@ -6,10 +6,13 @@ use crate::protocols::issuance::issuer::state_machine::IssuerState;
 use crate::protocols::proof_presentation::prover::state_machine::ProverState;
 use crate::protocols::proof_presentation::verifier::state_machine::VerifierState;
 
+pub mod basic_message;
 pub mod connection;
 pub mod discovery;
 pub mod issuance;
+pub mod mediation;
 pub mod out_of_band;
+pub mod pickup;
 pub mod proof_presentation;
 pub mod trust_ping;
 pub mod util;
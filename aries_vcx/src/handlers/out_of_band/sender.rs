@@ -1,15 +1,30 @@
+use chrono::{DateTime, Utc};
+use indy_sys::WalletHandle;
+
 use crate::error::prelude::*;
+use crate::handlers::connection::connection::Connection;
 use crate::handlers::out_of_band::{GoalCode, HandshakeProtocol, OutOfBandInvitation};
 use crate::messages::a2a::message_family::MessageFamilies;
 use crate::messages::a2a::message_type::MessageType;
 use crate::messages::a2a::A2AMessage;
 use crate::messages::attachment::AttachmentId;
+use crate::messages::connection::request::Request;
+use crate::messages::out_of_band::handshake_reuse::OutOfBandHandshakeReuse;
+use crate::protocols::oob::build_handshake_reuse_accepted_msg;
+use crate::protocols::oob::sender::{OutOfBandSenderSM, OutOfBandSenderState};
 
 use crate::utils::service_resolvable::ServiceResolvable;
 
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct OutOfBandSender {
     pub oob: OutOfBandInvitation,
+    sender_sm: OutOfBandSenderSM,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutOfBandSenderData {
+    oob: OutOfBandInvitation,
+    state: OutOfBandSenderSM,
 }
 
 impl OutOfBandSender {
@@ -32,6 +47,22 @@ impl OutOfBandSender {
         self
     }
 
+    pub fn set_multi_use(mut self, multi_use: bool) -> Self {
+        self.sender_sm = self.sender_sm.set_multi_use(multi_use);
+        self
+    }
+
+    pub fn set_expires_time(mut self, expires_time: DateTime<Utc>) -> Self {
+        self.oob.timing = Some(
+            self.oob
+                .timing
+                .take()
+                .unwrap_or_default()
+                .set_expires_time(expires_time),
+        );
+        self
+    }
+
     pub fn append_service(mut self, service: &ServiceResolvable) -> Self {
         self.oob.services.push(service.clone());
         self
@@ -45,6 +76,46 @@ impl OutOfBandSender {
         self.oob.id.0.clone()
     }
 
+    pub fn get_state(&self) -> OutOfBandSenderState {
+        self.sender_sm.get_state(&self.oob)
+    }
+
+    pub fn is_multi_use(&self) -> bool {
+        self.sender_sm.is_multi_use()
+    }
+
+    pub fn get_connections(&self) -> Vec<String> {
+        self.sender_sm.get_connections()
+    }
+
+    pub fn handle_connection_request(&mut self, request: &Request) -> VcxResult<()> {
+        trace!("OutOfBandSender::handle_connection_request >>> request: {:?}", request);
+        self.sender_sm = self.sender_sm.clone().handle_connection_request(&self.oob, request)?;
+        Ok(())
+    }
+
+    pub async fn handle_handshake_reuse(
+        &mut self,
+        wallet_handle: WalletHandle,
+        connection: &Connection,
+        handshake_reuse: &OutOfBandHandshakeReuse,
+    ) -> VcxResult<()> {
+        trace!(
+            "OutOfBandSender::handle_handshake_reuse >>> handshake_reuse: {:?}",
+            handshake_reuse
+        );
+        let sender_sm = self
+            .sender_sm
+            .clone()
+            .handle_handshake_reuse(&self.oob, handshake_reuse)?;
+        let msg = build_handshake_reuse_accepted_msg(handshake_reuse)?;
+        connection
+            .send_a2a_message(wallet_handle, &msg.to_a2a_message())
+            .await?;
+        self.sender_sm = sender_sm;
+        Ok(())
+    }
+
     pub fn append_handshake_protocol(mut self, protocol: &HandshakeProtocol) -> VcxResult<Self> {
         let new_protocol = match protocol {
             HandshakeProtocol::ConnectionV1 => MessageType::build(MessageFamilies::Connections, ""),
@@ -88,13 +159,30 @@ impl OutOfBandSender {
     }
 
     pub fn to_string(&self) -> VcxResult<String> {
-        self.oob.to_string()
+        let data = OutOfBandSenderData {
+            oob: self.oob.clone(),
+            state: self.sender_sm.clone(),
+        };
+        serde_json::to_string(&data).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::SerializationError,
+                format!("Cannot serialize out of band sender: {:?}", err),
+            )
+        })
     }
 
-    pub fn from_string(oob_data: &str) -> VcxResult<Self> {
-        Ok(Self {
-            oob: OutOfBandInvitation::from_string(oob_data)?,
-        })
+    /// Also accepts a bare invitation, as serialized before the sender state was kept.
+    pub fn from_string(sender_data: &str) -> VcxResult<Self> {
+        match serde_json::from_str::<OutOfBandSenderData>(sender_data) {
+            Ok(data) => Ok(Self {
+                oob: data.oob,
+                sender_sm: data.state,
+            }),
+            Err(_) => Ok(Self {
+                oob: OutOfBandInvitation::from_string(sender_data)?,
+                sender_sm: OutOfBandSenderSM::default(),
+            }),
+        }
     }
 }
 
@@ -148,6 +236,57 @@ mod unit_tests {
         assert_eq!(service, resolved_service[0]);
     }
 
+    #[test]
+    fn test_oob_sender_tracks_connection_requests() {
+        let _setup = SetupMocks::init();
+
+        let mut oob = _create_oob();
+        assert_eq!(oob.get_state(), OutOfBandSenderState::Initial);
+        let request = Request::create().set_parent_thread_id(&oob.get_id());
+        oob.handle_connection_request(&request).unwrap();
+        assert_eq!(oob.get_state(), OutOfBandSenderState::Completed);
+        oob.handle_connection_request(&request).unwrap_err();
+
+        let mut oob = _create_oob().set_multi_use(true);
+        oob.handle_connection_request(&request).unwrap();
+        oob.handle_connection_request(&request).unwrap();
+        assert_eq!(oob.get_state(), OutOfBandSenderState::InUse);
+        assert_eq!(oob.get_connections().len(), 2);
+    }
+
+    #[test]
+    fn test_oob_sender_expires() {
+        let _setup = SetupMocks::init();
+
+        let oob = _create_oob().set_expires_time(Utc::now() - chrono::Duration::seconds(1));
+        assert_eq!(oob.get_state(), OutOfBandSenderState::Expired);
+        let oob = _create_oob().set_expires_time(Utc::now() + chrono::Duration::hours(1));
+        assert_eq!(oob.get_state(), OutOfBandSenderState::Initial);
+    }
+
+    #[test]
+    fn test_oob_sender_serialize_deserialize_keeps_state() {
+        let _setup = SetupMocks::init();
+
+        let mut oob = _create_oob().set_multi_use(true);
+        oob.handle_connection_request(&Request::create().set_parent_thread_id(&oob.get_id()))
+            .unwrap();
+        let serialized = oob.to_string().unwrap();
+        let deserialized = OutOfBandSender::from_string(&serialized).unwrap();
+        assert_eq!(oob, deserialized);
+        assert_eq!(deserialized.get_state(), OutOfBandSenderState::InUse);
+    }
+
+    #[test]
+    fn test_oob_sender_deserialize_legacy_invitation() {
+        let _setup = SetupMocks::init();
+
+        let oob = _create_oob();
+        let deserialized = OutOfBandSender::from_string(&oob.oob.to_string().unwrap()).unwrap();
+        assert_eq!(oob, deserialized);
+        assert_eq!(deserialized.get_state(), OutOfBandSenderState::Initial);
+    }
+
     #[test]
     fn test_oob_sender_to_a2a_message() {
         let _setup = SetupMocks::init();
@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, Stream, StreamExt};
+use indy_sys::WalletHandle;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use crate::did_doc::DidDoc;
+use crate::error::prelude::*;
+use crate::handlers::pickup::PickupRecipient;
+use crate::messages::a2a::A2AMessage;
+use crate::protocols::{InboundCallback, SendClosure};
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Persistent WebSocket session with a mediator. Messages are pushed by the mediator
+/// as soon as they arrive instead of being polled for.
+pub struct LivePickupSession {
+    wallet_handle: WalletHandle,
+    sender_vk: String,
+    mediator_did_doc: DidDoc,
+    sink: Arc<Mutex<SplitSink<WebSocket, Message>>>,
+    stream: SplitStream<WebSocket>,
+}
+
+impl LivePickupSession {
+    pub async fn connect(
+        wallet_handle: WalletHandle,
+        sender_vk: &str,
+        mediator_did_doc: &DidDoc,
+        ws_endpoint: &str,
+    ) -> VcxResult<Self> {
+        trace!("LivePickupSession::connect >>> ws_endpoint: {}", ws_endpoint);
+        let (socket, _) = connect_async(ws_endpoint).await.map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::IOError,
+                format!("Cannot open WebSocket connection to {}: {}", ws_endpoint, err),
+            )
+        })?;
+        let (sink, stream) = socket.split();
+        Ok(Self {
+            wallet_handle,
+            sender_vk: sender_vk.to_string(),
+            mediator_did_doc: mediator_did_doc.clone(),
+            sink: Arc::new(Mutex::new(sink)),
+            stream,
+        })
+    }
+
+    pub fn send_message_closure(&self) -> SendClosure {
+        let wallet_handle = self.wallet_handle;
+        let sender_vk = self.sender_vk.clone();
+        let did_doc = self.mediator_did_doc.clone();
+        let sink = self.sink.clone();
+        Box::new(move |message: A2AMessage| {
+            let sender_vk = sender_vk.clone();
+            let did_doc = did_doc.clone();
+            let sink = sink.clone();
+            Box::pin(async move {
+                let envelope = EncryptionEnvelope::create(wallet_handle, &message, Some(&sender_vk), &did_doc).await?;
+                sink.lock()
+                    .await
+                    .send(Message::Binary(envelope.0))
+                    .await
+                    .map_err(|err| {
+                        VcxError::from_msg(
+                            VcxErrorKind::IOError,
+                            format!("Cannot send message over WebSocket: {}", err),
+                        )
+                    })
+            })
+        })
+    }
+
+    /// Switches the mediator into live mode and feeds every received message to `on_message`
+    /// until the mediator closes the socket. Queued messages are delivered first.
+    pub async fn run(self, pickup: &mut PickupRecipient, on_message: InboundCallback) -> VcxResult<()> {
+        trace!("LivePickupSession::run >>>");
+        let send_message = Arc::new(self.send_message_closure());
+        _run(self.wallet_handle, pickup, self.stream, send_message, on_message).await
+    }
+}
+
+async fn _run<S>(
+    wallet_handle: WalletHandle,
+    pickup: &mut PickupRecipient,
+    mut stream: S,
+    send_message: Arc<SendClosure>,
+    on_message: InboundCallback,
+) -> VcxResult<()>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    let send_message = || -> SendClosure {
+        let send_message = send_message.clone();
+        Box::new(move |message: A2AMessage| send_message(message))
+    };
+    pickup.set_live_delivery(true, send_message()).await?;
+    pickup.request_status(send_message()).await?;
+    pickup.request_delivery(100, send_message()).await?;
+
+    while let Some(frame) = stream.next().await {
+        let payload = match frame {
+            Ok(Message::Binary(payload)) => payload,
+            Ok(Message::Text(payload)) => payload.into_bytes(),
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(err) => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::IOError,
+                    format!("WebSocket connection failed: {}", err),
+                ))
+            }
+        };
+        let message = match EncryptionEnvelope::anon_unpack(wallet_handle, payload).await {
+            Ok(message) => message,
+            Err(err) => {
+                warn!(
+                    "LivePickupSession::run >>> failed to unpack received message, err: {:?}",
+                    err
+                );
+                continue;
+            }
+        };
+        let messages = pickup.handle_message(wallet_handle, message, send_message()).await?;
+        for message in messages {
+            if let Err(err) = on_message(message).await {
+                warn!("LivePickupSession::run >>> message callback failed, err: {:?}", err);
+            }
+        }
+    }
+    trace!("LivePickupSession::run <<< socket closed");
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::Mutex as StdMutex;
+
+    use agency_client::testing::mocking::AgencyMockDecrypted;
+    use futures::stream;
+
+    use crate::messages::ack::test_utils::_ack;
+    use crate::messages::pickup::status::Status;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _send_message(sent: Arc<StdMutex<Vec<A2AMessage>>>) -> Arc<SendClosure> {
+        let send_message: SendClosure = Box::new(move |message: A2AMessage| {
+            sent.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        });
+        Arc::new(send_message)
+    }
+
+    fn _on_message(received: Arc<StdMutex<Vec<A2AMessage>>>) -> InboundCallback {
+        Box::new(move |message: A2AMessage| {
+            received.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_run_enables_live_delivery_and_stops_on_close() {
+        let _setup = SetupMocks::init();
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(_ack().to_a2a_message()).to_string());
+        let sent = Arc::new(StdMutex::new(vec![]));
+        let received = Arc::new(StdMutex::new(vec![]));
+        let frames = stream::iter(vec![
+            Ok(Message::Binary(vec![1])),
+            Ok(Message::Close(None)),
+            Ok(Message::Binary(vec![2])),
+        ]);
+
+        let mut pickup = PickupRecipient::create(None);
+        _run(
+            WalletHandle(0),
+            &mut pickup,
+            frames,
+            _send_message(sent.clone()),
+            _on_message(received.clone()),
+        )
+        .await
+        .unwrap();
+
+        assert!(pickup.is_live_delivery());
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 3);
+        match &sent[0] {
+            A2AMessage::PickupLiveDeliveryChange(change) => assert!(change.live_delivery),
+            other => panic!("Expected live-delivery-change, got {:?}", other),
+        }
+        assert!(matches!(sent[1], A2AMessage::PickupStatusRequest(_)));
+        assert!(matches!(sent[2], A2AMessage::PickupDeliveryRequest(_)));
+        assert_eq!(*received.lock().unwrap(), vec![_ack().to_a2a_message()]);
+    }
+
+    #[tokio::test]
+    async fn test_run_follows_live_delivery_status_of_mediator() {
+        let _setup = SetupMocks::init();
+        let status = Status {
+            live_delivery: Some(false),
+            ..Status::create()
+        };
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(status.to_a2a_message()).to_string());
+        let sent = Arc::new(StdMutex::new(vec![]));
+        let received = Arc::new(StdMutex::new(vec![]));
+        let frames = stream::iter(vec![Ok(Message::Binary(vec![1]))]);
+
+        let mut pickup = PickupRecipient::create(None);
+        _run(
+            WalletHandle(0),
+            &mut pickup,
+            frames,
+            _send_message(sent.clone()),
+            _on_message(received.clone()),
+        )
+        .await
+        .unwrap();
+
+        assert!(!pickup.is_live_delivery());
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_on_connection_error() {
+        let _setup = SetupMocks::init();
+        let sent = Arc::new(StdMutex::new(vec![]));
+        let received = Arc::new(StdMutex::new(vec![]));
+        let frames = stream::iter(vec![Err(WsError::ConnectionClosed)]);
+
+        let mut pickup = PickupRecipient::create(None);
+        let err = _run(
+            WalletHandle(0),
+            &mut pickup,
+            frames,
+            _send_message(sent.clone()),
+            _on_message(received.clone()),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.kind(), VcxErrorKind::IOError);
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
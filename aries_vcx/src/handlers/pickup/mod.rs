@@ -0,0 +1,245 @@
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::messages::a2a::A2AMessage;
+use crate::messages::pickup::delivery::Delivery;
+use crate::messages::pickup::status::Status;
+use crate::protocols::pickup::{
+    build_delivery_request, build_live_delivery_change, build_messages_received, build_status_request,
+};
+use crate::protocols::SendClosure;
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+
+#[cfg(feature = "pickup_websocket")]
+pub mod live;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PickupRecipient {
+    recipient_key: Option<String>,
+    last_status: Option<Status>,
+    #[serde(default)]
+    live_delivery: bool,
+}
+
+impl PickupRecipient {
+    pub fn create(recipient_key: Option<String>) -> Self {
+        Self {
+            recipient_key,
+            last_status: None,
+            live_delivery: false,
+        }
+    }
+
+    pub fn get_recipient_key(&self) -> Option<String> {
+        self.recipient_key.clone()
+    }
+
+    pub fn get_message_count(&self) -> Option<u32> {
+        self.last_status.as_ref().map(|status| status.message_count)
+    }
+
+    pub fn is_live_delivery(&self) -> bool {
+        self.live_delivery
+    }
+
+    pub async fn set_live_delivery(&mut self, live_delivery: bool, send_message: SendClosure) -> VcxResult<()> {
+        trace!(
+            "PickupRecipient::set_live_delivery >>> live_delivery: {}",
+            live_delivery
+        );
+        send_message(build_live_delivery_change(live_delivery).to_a2a_message()).await?;
+        self.live_delivery = live_delivery;
+        Ok(())
+    }
+
+    pub async fn request_status(&self, send_message: SendClosure) -> VcxResult<()> {
+        trace!("PickupRecipient::request_status >>>");
+        send_message(build_status_request(self.recipient_key.clone()).to_a2a_message()).await
+    }
+
+    pub fn handle_status(&mut self, status: Status) -> VcxResult<()> {
+        trace!("PickupRecipient::handle_status >>> status: {:?}", status);
+        if status.recipient_key != self.recipient_key {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidMessageFormat,
+                format!(
+                    "Status was sent for recipient key {:?}, expected {:?}",
+                    status.recipient_key, self.recipient_key
+                ),
+            ));
+        }
+        if let Some(live_delivery) = status.live_delivery {
+            self.live_delivery = live_delivery;
+        }
+        self.last_status = Some(status);
+        Ok(())
+    }
+
+    pub async fn request_delivery(&self, limit: u32, send_message: SendClosure) -> VcxResult<()> {
+        trace!("PickupRecipient::request_delivery >>> limit: {}", limit);
+        send_message(build_delivery_request(limit, self.recipient_key.clone()).to_a2a_message()).await
+    }
+
+    /// Unpacks delivered messages and acknowledges all of them, including the ones which can not be
+    /// decoded or unpacked, so that the mediator does not redeliver them.
+    pub async fn handle_delivery(
+        &self,
+        wallet_handle: WalletHandle,
+        delivery: Delivery,
+        send_message: SendClosure,
+    ) -> VcxResult<Vec<A2AMessage>> {
+        trace!("PickupRecipient::handle_delivery >>> delivery: {:?}", delivery.id);
+        let mut messages = Vec::new();
+        let mut received_ids = Vec::new();
+        for attachment in delivery.attachments {
+            let unpacked = match attachment.get_envelope() {
+                Ok(envelope) => EncryptionEnvelope::anon_unpack(wallet_handle, envelope).await,
+                Err(err) => Err(err),
+            };
+            match unpacked {
+                Ok(message) => messages.push(message),
+                Err(err) => {
+                    warn!(
+                        "Failed to unpack delivered message {}, dropping it, err: {:?}",
+                        attachment.id, err
+                    );
+                }
+            }
+            received_ids.push(attachment.id);
+        }
+        if !received_ids.is_empty() {
+            send_message(build_messages_received(received_ids).to_a2a_message()).await?;
+        }
+        Ok(messages)
+    }
+
+    pub async fn handle_message(
+        &mut self,
+        wallet_handle: WalletHandle,
+        message: A2AMessage,
+        send_message: SendClosure,
+    ) -> VcxResult<Vec<A2AMessage>> {
+        trace!("PickupRecipient::handle_message >>> message: {:?}", message);
+        match message {
+            A2AMessage::PickupStatus(status) => {
+                self.handle_status(status)?;
+                Ok(vec![])
+            }
+            A2AMessage::PickupDelivery(delivery) => self.handle_delivery(wallet_handle, delivery, send_message).await,
+            message => Ok(vec![message]),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::{Arc, Mutex};
+
+    use agency_client::testing::mocking::AgencyMockDecrypted;
+
+    use crate::messages::ack::test_utils::_ack;
+    use crate::messages::pickup::delivery::{DeliveryAttachment, DeliveryAttachmentData};
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _send_message(sent: Arc<Mutex<Vec<A2AMessage>>>) -> SendClosure {
+        Box::new(move |message: A2AMessage| {
+            sent.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_handle_delivery_acknowledges_unpacked_messages() {
+        let _setup = SetupMocks::init();
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(_ack().to_a2a_message()).to_string());
+        let sent = Arc::new(Mutex::new(vec![]));
+        let delivery = Delivery::create().add_attachment("msg-1", "{}".as_bytes());
+
+        let messages = PickupRecipient::create(None)
+            .handle_delivery(WalletHandle(0), delivery, _send_message(sent.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(messages.len(), 1);
+        let sent = sent.lock().unwrap();
+        match &sent[0] {
+            A2AMessage::PickupMessagesReceived(received) => {
+                assert_eq!(received.message_id_list, vec!["msg-1".to_string()])
+            }
+            other => panic!("Expected messages-received, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_delivery_acknowledges_undecodable_messages() {
+        let _setup = SetupMocks::init();
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(_ack().to_a2a_message()).to_string());
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut delivery = Delivery::create().add_attachment("msg-1", "{}".as_bytes());
+        delivery.attachments.insert(
+            0,
+            DeliveryAttachment {
+                id: "msg-0".into(),
+                data: DeliveryAttachmentData {
+                    base64: "not base64!".into(),
+                },
+            },
+        );
+
+        let messages = PickupRecipient::create(None)
+            .handle_delivery(WalletHandle(0), delivery, _send_message(sent.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(messages, vec![_ack().to_a2a_message()]);
+        let sent = sent.lock().unwrap();
+        match &sent[0] {
+            A2AMessage::PickupMessagesReceived(received) => {
+                assert_eq!(received.message_id_list, vec!["msg-0".to_string(), "msg-1".to_string()])
+            }
+            other => panic!("Expected messages-received, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_message_passes_through_live_messages() {
+        let _setup = SetupMocks::init();
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut pickup = PickupRecipient::create(None);
+
+        pickup
+            .set_live_delivery(true, _send_message(sent.clone()))
+            .await
+            .unwrap();
+        assert!(pickup.is_live_delivery());
+
+        let messages = pickup
+            .handle_message(WalletHandle(0), _ack().to_a2a_message(), _send_message(sent.clone()))
+            .await
+            .unwrap();
+        assert_eq!(messages, vec![_ack().to_a2a_message()]);
+
+        let messages = pickup
+            .handle_message(
+                WalletHandle(0),
+                Status::create().set_message_count(2).to_a2a_message(),
+                _send_message(sent.clone()),
+            )
+            .await
+            .unwrap();
+        assert!(messages.is_empty());
+        assert_eq!(pickup.get_message_count(), Some(2));
+        assert_eq!(sent.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_handle_status_for_other_recipient_key_fails() {
+        let _setup = SetupMocks::init();
+        let mut pickup = PickupRecipient::create(Some("key".into()));
+        pickup.handle_status(Status::create().set_message_count(3)).unwrap_err();
+        assert_eq!(pickup.get_message_count(), None);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::libindy::proofs::prover::credential_selection::SelectionStrategy;
+
+/// Steps the prover takes on its own while updating state, none by default.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ProverAutoPolicy {
+    #[serde(default)]
+    pub auto_present: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tails_dir: Option<String>,
+}
+
+impl ProverAutoPolicy {
+    pub fn create() -> Self {
+        ProverAutoPolicy::default()
+    }
+
+    /// Presents as soon as presentation request is received, provided the wallet holds exactly
+    /// one credential for each referent of the request. Otherwise the choice is left to the caller.
+    pub fn set_auto_present(mut self, auto_present: bool) -> Self {
+        self.auto_present = auto_present;
+        self
+    }
+
+    /// Directory with tails files, required to present revocable credentials.
+    pub fn set_tails_dir(mut self, tails_dir: &str) -> Self {
+        self.tails_dir = Some(tails_dir.to_string());
+        self
+    }
+
+    pub(crate) fn selection_strategy(&self) -> SelectionStrategy {
+        match &self.tails_dir {
+            Some(tails_dir) => SelectionStrategy::create().set_tails_dir(tails_dir),
+            None => SelectionStrategy::create(),
+        }
+    }
+}
+
+/// Steps the verifier takes on its own while updating state, none by default.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct VerifierAutoPolicy {
+    #[serde(default)]
+    pub auto_ack: bool,
+}
+
+impl VerifierAutoPolicy {
+    pub fn create() -> Self {
+        VerifierAutoPolicy::default()
+    }
+
+    /// Acknowledges presentation which was verified successfully, even if the prover did not ask
+    /// for acknowledgement.
+    pub fn set_auto_ack(mut self, auto_ack: bool) -> Self {
+        self.auto_ack = auto_ack;
+        self
+    }
+}
@@ -1,23 +1,63 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 use indy_sys::WalletHandle;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
 
 use agency_client::agency_client::AgencyClient;
 
 use crate::error::prelude::*;
+use crate::events::{self, ProtocolKind};
 use crate::handlers::connection::connection::Connection;
+use crate::handlers::proof_presentation::auto_policy::ProverAutoPolicy;
+use crate::handlers::util::deserialize_auto_policy;
+use crate::libindy::proofs::proof_request::ProofRequestData;
+use crate::libindy::proofs::prover::credential_selection::{self, CredentialSelection, SelectionStrategy};
 use crate::libindy::utils::anoncreds;
 use crate::messages::a2a::A2AMessage;
+use crate::messages::error::ProblemReport;
 use crate::messages::proof_presentation::presentation::Presentation;
 use crate::messages::proof_presentation::presentation_proposal::{PresentationPreview, PresentationProposalData};
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::protocols::proof_presentation::prover::messages::ProverMessages;
 use crate::protocols::proof_presentation::prover::state_machine::{ProverSM, ProverState};
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 use crate::protocols::SendClosure;
+use crate::utils::migrations::{PROVER_MIGRATIONS, SCHEMA_VERSION_KEY};
+use crate::utils::protocol_repository;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(try_from = "Value")]
 pub struct Prover {
     prover_sm: ProverSM,
+    auto_policy: ProverAutoPolicy,
+}
+
+impl Serialize for Prover {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Prover", 3)?;
+        state.serialize_field(SCHEMA_VERSION_KEY, &PROVER_MIGRATIONS.schema_version())?;
+        state.serialize_field("prover_sm", &self.prover_sm)?;
+        state.serialize_field("auto_policy", &self.auto_policy)?;
+        state.end()
+    }
+}
+
+impl TryFrom<Value> for Prover {
+    type Error = VcxError;
+
+    fn try_from(value: Value) -> VcxResult<Self> {
+        let auto_policy = deserialize_auto_policy(&value)?;
+        Ok(Self {
+            prover_sm: PROVER_MIGRATIONS.state_machine(value, "prover_sm")?,
+            auto_policy,
+        })
+    }
 }
 
 impl Prover {
@@ -25,6 +65,7 @@ impl Prover {
         trace!("Prover::create >>> source_id: {}", source_id);
         Ok(Prover {
             prover_sm: ProverSM::new(source_id.to_string()),
+            auto_policy: ProverAutoPolicy::default(),
         })
     }
 
@@ -36,6 +77,7 @@ impl Prover {
         );
         Ok(Prover {
             prover_sm: ProverSM::from_request(presentation_request, source_id.to_string()),
+            auto_policy: ProverAutoPolicy::default(),
         })
     }
 
@@ -43,6 +85,10 @@ impl Prover {
         self.prover_sm.get_state()
     }
 
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        self.prover_sm.get_problem_report()
+    }
+
     pub fn presentation_status(&self) -> u32 {
         trace!("Prover::presentation_state >>>");
         self.prover_sm.presentation_status()
@@ -54,6 +100,71 @@ impl Prover {
         anoncreds::libindy_prover_get_credentials_for_proof_req(wallet_handle, &presentation_request).await
     }
 
+    pub async fn select_credentials(
+        &self,
+        wallet_handle: WalletHandle,
+        strategy: &SelectionStrategy,
+    ) -> VcxResult<CredentialSelection> {
+        trace!("Prover::select_credentials >>> strategy: {:?}", strategy);
+        let proof_request = self._proof_request_data()?;
+        let retrieved_credentials = self.retrieve_credentials(wallet_handle).await?;
+        let selection = credential_selection::select_credentials(&retrieved_credentials, strategy).await?;
+        Ok(selection.match_proof_request(&proof_request))
+    }
+
+    /// Selects credentials by `strategy`, generates the presentation from them and sends it to the
+    /// verifier. Fails without progressing the protocol if some referent can not be satisfied.
+    pub async fn auto_present(
+        &mut self,
+        wallet_handle: WalletHandle,
+        strategy: &SelectionStrategy,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        trace!("Prover::auto_present >>> strategy: {:?}", strategy);
+        let selection = self.select_credentials(wallet_handle, strategy).await?;
+        if !selection.is_complete() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidProofCredentialData,
+                format!(
+                    "No credentials found for referents {:?} of presentation request",
+                    selection.unsatisfied_referents
+                ),
+            ));
+        }
+        self.generate_presentation(wallet_handle, selection.selected_credentials_json()?, "{}".to_string())
+            .await?;
+        self.send_presentation(wallet_handle, send_message).await
+    }
+
+    pub fn set_auto_policy(&mut self, auto_policy: ProverAutoPolicy) {
+        trace!("Prover::set_auto_policy >>> auto_policy: {:?}", auto_policy);
+        self.auto_policy = auto_policy;
+    }
+
+    pub fn get_auto_policy(&self) -> &ProverAutoPolicy {
+        &self.auto_policy
+    }
+
+    /// Takes the steps automated by the auto policy which are possible in the current state, that
+    /// is presenting when each referent of received request is satisfied by a single credential.
+    pub async fn apply_auto_policy(&mut self, wallet_handle: WalletHandle, send_message: SendClosure) -> VcxResult<()> {
+        trace!("Prover::apply_auto_policy >>> auto_policy: {:?}", self.auto_policy);
+        if !self.auto_policy.auto_present || self.get_state() != ProverState::PresentationRequestReceived {
+            return Ok(());
+        }
+        let strategy = self.auto_policy.selection_strategy();
+        let selection = self.select_credentials(wallet_handle, &strategy).await?;
+        if selection.is_unambiguous() {
+            self.auto_present(wallet_handle, &strategy, send_message).await?;
+        } else {
+            warn!(
+                "Credentials for presentation request are missing or ambiguous, presentation is not sent automatically"
+            );
+        }
+        Ok(())
+    }
+
+    /// Prepares the presentation from the credentials selected for each referent of the request.
     pub async fn generate_presentation(
         &mut self,
         wallet_handle: WalletHandle,
@@ -65,9 +176,43 @@ impl Prover {
             credentials,
             self_attested_attrs
         );
+        self.generate_presentation_with_reveal_choices(wallet_handle, credentials, self_attested_attrs, vec![])
+            .await
+    }
+
+    /// Same as `generate_presentation`, but the attributes of `unrevealed_attrs` referents are proven
+    /// without disclosing their values.
+    pub async fn generate_presentation_with_reveal_choices(
+        &mut self,
+        wallet_handle: WalletHandle,
+        credentials: String,
+        self_attested_attrs: String,
+        unrevealed_attrs: Vec<String>,
+    ) -> VcxResult<()> {
+        trace!(
+            "Prover::generate_presentation_with_reveal_choices >>> credentials: {}, self_attested_attrs: {:?}, unrevealed_attrs: {:?}",
+            credentials,
+            self_attested_attrs,
+            unrevealed_attrs
+        );
+        if !unrevealed_attrs.is_empty() {
+            let proof_request = self._proof_request_data()?;
+            if let Some(referent) = unrevealed_attrs
+                .iter()
+                .find(|referent| !proof_request.requested_attributes.contains_key(*referent))
+            {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidOption,
+                    format!(
+                        "Unrevealed attribute {} is not requested by presentation request",
+                        referent
+                    ),
+                ));
+            }
+        }
         self.step(
             wallet_handle,
-            ProverMessages::PreparePresentation((credentials, self_attested_attrs)),
+            ProverMessages::PreparePresentation((credentials, self_attested_attrs, unrevealed_attrs)),
             None,
         )
         .await
@@ -131,6 +276,19 @@ impl Prover {
             .content()
     }
 
+    fn _proof_request_data(&self) -> VcxResult<ProofRequestData> {
+        let presentation_request = self.presentation_request_data()?;
+        serde_json::from_str(&presentation_request).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!(
+                    "Cannot deserialize {:?} into ProofRequestData: {:?}",
+                    presentation_request, err
+                ),
+            )
+        })
+    }
+
     pub fn get_proof_request_attachment(&self) -> VcxResult<String> {
         let data = self
             .prover_sm
@@ -154,20 +312,73 @@ impl Prover {
         self.prover_sm.get_thread_id()
     }
 
+    pub fn get_negotiation_history(&self) -> Vec<PresentationNegotiationMessage> {
+        self.prover_sm.get_negotiation_history()
+    }
+
+    /// Fails the presentation in any state, notifying the verifier only if `send_message` is provided.
+    pub async fn abandon(&mut self, reason: Option<&str>, send_message: Option<SendClosure>) -> VcxResult<()> {
+        trace!("Prover::abandon >>> reason: {:?}", reason);
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
+        self.prover_sm = self
+            .prover_sm
+            .clone()
+            .abandon(reason.map(String::from), send_message)
+            .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, None).await;
+        Ok(())
+    }
+
     pub async fn step(
         &mut self,
         wallet_handle: WalletHandle,
         message: ProverMessages,
         send_message: Option<SendClosure>,
     ) -> VcxResult<()> {
+        self.step_on_connection(wallet_handle, message, send_message, None)
+            .await
+    }
+
+    /// Same as `step`, linking the stored record with `connection_id` if given.
+    async fn step_on_connection(
+        &mut self,
+        wallet_handle: WalletHandle,
+        message: ProverMessages,
+        send_message: Option<SendClosure>,
+        connection_id: Option<&str>,
+    ) -> VcxResult<()> {
+        if let Some(received) = message.received_message() {
+            events::publish_message_received(
+                ProtocolKind::Prover,
+                &self.prover_sm.source_id(),
+                &self.get_thread_id().unwrap_or_default(),
+                &received,
+            );
+        }
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
         self.prover_sm = self
             .prover_sm
             .clone()
             .step(wallet_handle, message, send_message)
             .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, connection_id).await;
         Ok(())
     }
 
+    fn publish_transition(&self, previous_state: ProverState, previous_problem_report: Option<ProblemReport>) {
+        events::publish_transition(
+            ProtocolKind::Prover,
+            &self.prover_sm.source_id(),
+            &self.get_thread_id().unwrap_or_default(),
+            (&previous_state, &self.get_state()),
+            (previous_problem_report, self.get_problem_report()),
+        )
+    }
+
     pub async fn decline_presentation_request(
         &mut self,
         wallet_handle: WalletHandle,
@@ -222,15 +433,26 @@ impl Prover {
         connection: &Connection,
     ) -> VcxResult<ProverState> {
         trace!("Prover::update_state >>> ");
-        if !self.progressable_by_message() {
-            return Ok(self.get_state());
-        }
-        let send_message = connection.send_message_closure(wallet_handle)?;
+        if self.progressable_by_message() {
+            let send_message = connection.send_message_closure(wallet_handle)?;
 
-        let messages = connection.get_messages(agency_client).await?;
-        if let Some((uid, msg)) = self.find_message_to_handle(messages) {
-            self.step(wallet_handle, msg.into(), Some(send_message)).await?;
-            connection.update_message_status(&uid, agency_client).await?;
+            let messages = connection.get_messages(agency_client).await?;
+            if let Some((uid, msg)) = self.find_message_to_handle(messages) {
+                self.step_on_connection(
+                    wallet_handle,
+                    msg.into(),
+                    Some(send_message),
+                    Some(&connection.get_thread_id()),
+                )
+                .await?;
+                connection.update_message_status(&uid, agency_client).await?;
+            }
+        }
+        if let Err(err) = self
+            .apply_auto_policy(wallet_handle, connection.send_message_closure(wallet_handle)?)
+            .await
+        {
+            warn!("Prover::update_state >>> failed to apply auto policy, err: {:?}", err);
         }
         Ok(self.get_state())
     }
@@ -265,11 +487,33 @@ pub mod test_utils {
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod tests {
+    use crate::libindy::proofs::prover::credential_selection::test_utils::_retrieved_credential;
+    use crate::messages::proof_presentation::presentation_request::test_utils::{
+        _presentation_request, _presentation_request_data,
+    };
     use crate::messages::proof_presentation::presentation_request::PresentationRequest;
     use crate::utils::devsetup::*;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
 
     use super::*;
 
+    fn _dummy_wallet_handle() -> WalletHandle {
+        WalletHandle(0)
+    }
+
+    pub fn _send_message() -> Option<SendClosure> {
+        Some(Box::new(|_: A2AMessage| Box::pin(async { VcxResult::Ok(()) })))
+    }
+
+    fn _presentation_request_with_predicate() -> PresentationRequest {
+        let request_data = _presentation_request_data()
+            .set_requested_predicates_as_string(json!([{"name": "age", "p_type": ">=", "p_value": 18}]).to_string())
+            .unwrap();
+        PresentationRequest::create()
+            .set_request_presentations_attach(&request_data)
+            .unwrap()
+    }
+
     #[tokio::test]
     async fn test_retrieve_credentials_fails_with_no_proof_req() {
         let setup = SetupLibraryWallet::init().await;
@@ -285,4 +529,133 @@ mod tests {
             VcxErrorKind::InvalidJson
         );
     }
+
+    #[tokio::test]
+    async fn test_auto_present_fails_for_unsatisfied_referents() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init()
+            .set_mock_creds_retrieved_for_proof_request(r#"{"attrs":{"attribute_0":[]},"predicates":{}}"#);
+
+        let mut prover = Prover::create_from_request("1", _presentation_request()).unwrap();
+        let selection = prover
+            .select_credentials(_dummy_wallet_handle(), &SelectionStrategy::create())
+            .await
+            .unwrap();
+        assert_eq!(selection.unsatisfied_referents, vec!["attribute_0".to_string()]);
+
+        let err = prover
+            .auto_present(
+                _dummy_wallet_handle(),
+                &SelectionStrategy::create(),
+                _send_message().unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::InvalidProofCredentialData);
+        assert_eq!(prover.get_state(), ProverState::PresentationRequestReceived);
+    }
+
+    #[tokio::test]
+    async fn test_generate_presentation_with_reveal_choices() {
+        let _setup = SetupMocks::init();
+
+        let credentials = json!({
+            "attrs": {"attribute_0": {"credential": _retrieved_credential("cred_1", "NcYxiDXkpYi6ov5FcYDi1e", None)}}
+        });
+
+        let mut prover = Prover::create_from_request("1", _presentation_request()).unwrap();
+        prover
+            .generate_presentation_with_reveal_choices(
+                _dummy_wallet_handle(),
+                credentials.to_string(),
+                "{}".to_string(),
+                vec!["attribute_0".to_string()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(prover.get_state(), ProverState::PresentationPrepared);
+    }
+
+    #[tokio::test]
+    async fn test_generate_presentation_with_reveal_choices_rejects_unrequested_attribute() {
+        let _setup = SetupMocks::init();
+
+        let mut prover = Prover::create_from_request("1", _presentation_request()).unwrap();
+        let err = prover
+            .generate_presentation_with_reveal_choices(
+                _dummy_wallet_handle(),
+                "{}".to_string(),
+                "{}".to_string(),
+                vec!["attribute_1".to_string()],
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::InvalidOption);
+        assert_eq!(prover.get_state(), ProverState::PresentationRequestReceived);
+    }
+
+    #[tokio::test]
+    async fn test_select_credentials_reports_unretrieved_predicate() {
+        let _setup = SetupMocks::init();
+        let retrieved =
+            json!({"attrs": {"attribute_0": [_retrieved_credential("cred_1", "NcYxiDXkpYi6ov5FcYDi1e", None)]}})
+                .to_string();
+        let _mock_builder = MockBuilder::init().set_mock_creds_retrieved_for_proof_request(&retrieved);
+
+        let prover = Prover::create_from_request("1", _presentation_request_with_predicate()).unwrap();
+        let selection = prover
+            .select_credentials(_dummy_wallet_handle(), &SelectionStrategy::create())
+            .await
+            .unwrap();
+        assert_eq!(selection.unsatisfied_referents, vec!["predicate_0".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_apply_auto_policy_presents_attributes_and_predicates() {
+        let _setup = SetupMocks::init();
+        let retrieved = json!({
+            "attrs": {
+                "attribute_0": [_retrieved_credential("cred_1", "NcYxiDXkpYi6ov5FcYDi1e", None)],
+                "predicate_0": [_retrieved_credential("cred_1", "NcYxiDXkpYi6ov5FcYDi1e", None)]
+            }
+        })
+        .to_string();
+        let _mock_builder = MockBuilder::init().set_mock_creds_retrieved_for_proof_request(&retrieved);
+
+        let mut prover = Prover::create_from_request("1", _presentation_request_with_predicate()).unwrap();
+        let selection = prover
+            .select_credentials(_dummy_wallet_handle(), &SelectionStrategy::create())
+            .await
+            .unwrap();
+        assert!(selection.is_unambiguous());
+        assert!(selection.selected_credentials.attrs.contains_key("attribute_0"));
+        assert!(selection.selected_credentials.predicates.contains_key("predicate_0"));
+
+        prover.set_auto_policy(ProverAutoPolicy::create().set_auto_present(true));
+        prover
+            .apply_auto_policy(_dummy_wallet_handle(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(prover.get_state(), ProverState::PresentationSent);
+    }
+
+    #[tokio::test]
+    async fn test_apply_auto_policy_skips_ambiguous_selection() {
+        let _setup = SetupMocks::init();
+        let candidate = _retrieved_credential("cred_1", "NcYxiDXkpYi6ov5FcYDi1e", None);
+        let retrieved = json!({"attrs": {"attribute_0": [candidate.clone(), candidate]}}).to_string();
+        let _mock_builder = MockBuilder::init().set_mock_creds_retrieved_for_proof_request(&retrieved);
+
+        let mut prover = Prover::create_from_request("1", _presentation_request()).unwrap();
+        prover.set_auto_policy(ProverAutoPolicy::create().set_auto_present(true));
+        prover
+            .apply_auto_policy(_dummy_wallet_handle(), _send_message().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(prover.get_state(), ProverState::PresentationRequestReceived);
+
+        let serialized = serde_json::to_string(&prover).unwrap();
+        let prover: Prover = serde_json::from_str(&serialized).unwrap();
+        assert!(prover.get_auto_policy().auto_present);
+    }
 }
@@ -1,22 +1,64 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
+use chrono::{Duration, Utc};
 use indy_sys::WalletHandle;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
 
 use agency_client::agency_client::AgencyClient;
 
 use crate::error::prelude::*;
+use crate::events::{self, ProtocolKind};
 use crate::handlers::connection::connection::Connection;
+use crate::handlers::proof_presentation::auto_policy::VerifierAutoPolicy;
+use crate::handlers::util::deserialize_auto_policy;
+use crate::libindy::proofs::proof_request_template::{ProofRequestOverrides, ProofRequestTemplate};
+use crate::libindy::proofs::verifier::requested_proof::RequestedProof;
 use crate::messages::a2a::A2AMessage;
+use crate::messages::error::ProblemReport;
 use crate::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::messages::proof_presentation::presentation_request::*;
+use crate::messages::status::Status;
 use crate::protocols::proof_presentation::verifier::messages::VerifierMessages;
 use crate::protocols::proof_presentation::verifier::state_machine::{VerifierSM, VerifierState};
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 use crate::protocols::SendClosure;
+use crate::utils::migrations::{SCHEMA_VERSION_KEY, VERIFIER_MIGRATIONS};
+use crate::utils::protocol_repository;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(try_from = "Value")]
 pub struct Verifier {
     verifier_sm: VerifierSM,
+    auto_policy: VerifierAutoPolicy,
+}
+
+impl Serialize for Verifier {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Verifier", 3)?;
+        state.serialize_field(SCHEMA_VERSION_KEY, &VERIFIER_MIGRATIONS.schema_version())?;
+        state.serialize_field("verifier_sm", &self.verifier_sm)?;
+        state.serialize_field("auto_policy", &self.auto_policy)?;
+        state.end()
+    }
+}
+
+impl TryFrom<Value> for Verifier {
+    type Error = VcxError;
+
+    fn try_from(value: Value) -> VcxResult<Self> {
+        let auto_policy = deserialize_auto_policy(&value)?;
+        Ok(Self {
+            verifier_sm: VERIFIER_MIGRATIONS.state_machine(value, "verifier_sm")?,
+            auto_policy,
+        })
+    }
 }
 
 impl Verifier {
@@ -25,6 +67,7 @@ impl Verifier {
 
         Ok(Self {
             verifier_sm: VerifierSM::new(source_id),
+            auto_policy: VerifierAutoPolicy::default(),
         })
     }
 
@@ -35,7 +78,30 @@ impl Verifier {
             presentation_request
         );
         let verifier_sm = VerifierSM::from_request(&source_id, presentation_request)?;
-        Ok(Self { verifier_sm })
+        Ok(Self {
+            verifier_sm,
+            auto_policy: VerifierAutoPolicy::default(),
+        })
+    }
+
+    /// Creates verifier with proof request instantiated from the template stored in the wallet, using
+    /// the latest version of the template unless `version` is given.
+    pub async fn create_from_template(
+        source_id: String,
+        wallet_handle: WalletHandle,
+        name: &str,
+        version: Option<&str>,
+        overrides: &ProofRequestOverrides,
+    ) -> VcxResult<Self> {
+        trace!(
+            "Verifier::create_from_template >>> source_id: {:?}, name: {}, version: {:?}",
+            source_id,
+            name,
+            version
+        );
+        let template = ProofRequestTemplate::get(wallet_handle, name, version).await?;
+        let presentation_request = template.instantiate(overrides).await?;
+        Self::create_from_request(source_id, &presentation_request)
     }
 
     pub fn create_from_proposal(source_id: &str, presentation_proposal: &PresentationProposal) -> VcxResult<Self> {
@@ -46,6 +112,7 @@ impl Verifier {
         );
         Ok(Self {
             verifier_sm: VerifierSM::from_proposal(source_id, presentation_proposal),
+            auto_policy: VerifierAutoPolicy::default(),
         })
     }
 
@@ -57,6 +124,10 @@ impl Verifier {
         self.verifier_sm.get_state()
     }
 
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        self.verifier_sm.get_problem_report()
+    }
+
     pub async fn handle_message(
         &mut self,
         wallet_handle: WalletHandle,
@@ -69,9 +140,14 @@ impl Verifier {
 
     pub async fn send_presentation_request(&mut self, send_message: SendClosure) -> VcxResult<()> {
         if self.verifier_sm.get_state() == VerifierState::PresentationRequestSet {
-            let offer = self.verifier_sm.presentation_request()?.to_a2a_message();
-            send_message(offer).await?;
-            self.verifier_sm = self.verifier_sm.clone().mark_presentation_request_msg_sent()?;
+            let presentation_request = self.verifier_sm.presentation_request()?;
+            presentation_request
+                .clone()
+                .get_presentation_request_data()?
+                .validate()?;
+            send_message(presentation_request.to_a2a_message()).await?;
+            self.mark_presentation_request_msg_sent()?;
+            protocol_repository::persist(self, None).await;
         }
         Ok(())
     }
@@ -86,6 +162,37 @@ impl Verifier {
         .await
     }
 
+    pub fn set_auto_policy(&mut self, auto_policy: VerifierAutoPolicy) {
+        trace!("Verifier::set_auto_policy >>> auto_policy: {:?}", auto_policy);
+        self.auto_policy = auto_policy;
+    }
+
+    pub fn get_auto_policy(&self) -> &VerifierAutoPolicy {
+        &self.auto_policy
+    }
+
+    /// Acknowledges the presentation verified successfully while transitioning from `previous_state`,
+    /// unless the state machine already did so on request of the prover.
+    async fn apply_auto_policy(
+        &mut self,
+        wallet_handle: WalletHandle,
+        previous_state: VerifierState,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        trace!("Verifier::apply_auto_policy >>> auto_policy: {:?}", self.auto_policy);
+        if !self.auto_policy.auto_ack
+            || previous_state != VerifierState::PresentationRequestSent
+            || self.get_state() != VerifierState::Finished
+            || self.get_presentation_status() != Status::Success.code()
+        {
+            return Ok(());
+        }
+        if self.verifier_sm.presentation()?.please_ack.is_none() {
+            self.send_ack(wallet_handle, send_message).await?;
+        }
+        Ok(())
+    }
+
     pub fn set_request(
         &mut self,
         presentation_request_data: PresentationRequestData,
@@ -96,16 +203,35 @@ impl Verifier {
             presentation_request_data,
             comment
         );
+        let previous_state = self.get_state();
         self.verifier_sm = self
             .verifier_sm
             .clone()
             .set_request(&presentation_request_data, comment)?;
+        self.publish_transition(previous_state, None);
         Ok(())
     }
 
+    /// Sets the time the prover has to respond to the presentation request which is not sent yet. The
+    /// verifier fails with a problem report if no presentation is received until then.
+    pub fn set_request_expiration(&mut self, expires_in: Duration) -> VcxResult<()> {
+        trace!("Verifier::set_request_expiration >>> expires_in: {:?}", expires_in);
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .set_request_expiration(Utc::now() + expires_in)?;
+        Ok(())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.verifier_sm.is_expired()
+    }
+
     pub fn mark_presentation_request_msg_sent(&mut self) -> VcxResult<()> {
         trace!("Verifier::mark_presentation_request_msg_sent >>>");
+        let previous_state = self.get_state();
         self.verifier_sm = self.verifier_sm.clone().mark_presentation_request_msg_sent()?;
+        self.publish_transition(previous_state, None);
         Ok(())
     }
 
@@ -133,6 +259,12 @@ impl Verifier {
         self.verifier_sm.presentation()?.presentations_attach.content()
     }
 
+    /// Attributes of the received presentation, telling apart the ones revealed by the prover from the
+    /// ones only proven to be held.
+    pub fn get_requested_proof(&self) -> VcxResult<RequestedProof> {
+        RequestedProof::from_proof(&self.get_presentation_attachment()?)
+    }
+
     pub fn get_presentation_proposal(&self) -> VcxResult<PresentationProposal> {
         trace!("Verifier::get_presentation_proposal >>>");
         self.verifier_sm.presentation_proposal()
@@ -142,20 +274,82 @@ impl Verifier {
         Ok(self.verifier_sm.thread_id())
     }
 
+    pub fn get_negotiation_history(&self) -> Vec<PresentationNegotiationMessage> {
+        self.verifier_sm.get_negotiation_history()
+    }
+
+    /// Fails the presentation in any state, notifying the prover only if `send_message` is provided.
+    pub async fn abandon(&mut self, reason: Option<&str>, send_message: Option<SendClosure>) -> VcxResult<()> {
+        trace!("Verifier::abandon >>> reason: {:?}", reason);
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .abandon(reason.map(String::from), send_message)
+            .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, None).await;
+        Ok(())
+    }
+
     pub async fn step(
         &mut self,
         wallet_handle: WalletHandle,
         message: VerifierMessages,
         send_message: Option<SendClosure>,
     ) -> VcxResult<()> {
+        self.step_on_connection(wallet_handle, message, send_message, None)
+            .await
+    }
+
+    /// Same as `step`, linking the stored record with `connection_id` if given.
+    async fn step_on_connection(
+        &mut self,
+        wallet_handle: WalletHandle,
+        message: VerifierMessages,
+        send_message: Option<SendClosure>,
+        connection_id: Option<&str>,
+    ) -> VcxResult<()> {
+        if let Some(received) = message.received_message() {
+            events::publish_message_received(
+                ProtocolKind::Verifier,
+                &self.verifier_sm.source_id(),
+                &self.verifier_sm.thread_id(),
+                &received,
+            );
+        }
+        let message = if self.is_expired() {
+            warn!(
+                "Verifier::step >>> presentation request has expired, failing instead of handling {:?}",
+                message
+            );
+            VerifierMessages::PresentationRequestExpired
+        } else {
+            message
+        };
+        let previous_state = self.get_state();
+        let previous_problem_report = self.get_problem_report();
         self.verifier_sm = self
             .verifier_sm
             .clone()
             .step(wallet_handle, message, send_message)
             .await?;
+        self.publish_transition(previous_state, previous_problem_report);
+        protocol_repository::persist(self, connection_id).await;
         Ok(())
     }
 
+    fn publish_transition(&self, previous_state: VerifierState, previous_problem_report: Option<ProblemReport>) {
+        events::publish_transition(
+            ProtocolKind::Verifier,
+            &self.verifier_sm.source_id(),
+            &self.verifier_sm.thread_id(),
+            (&previous_state, &self.get_state()),
+            (previous_problem_report, self.get_problem_report()),
+        )
+    }
+
     pub fn progressable_by_message(&self) -> bool {
         self.verifier_sm.progressable_by_message()
     }
@@ -190,11 +384,35 @@ impl Verifier {
             return Ok(self.get_state());
         }
         let send_message = connection.send_message_closure(wallet_handle)?;
+        let previous_state = self.get_state();
 
         let messages = connection.get_messages(agency_client).await?;
         if let Some((uid, msg)) = self.find_message_to_handle(messages) {
-            self.step(wallet_handle, msg.into(), Some(send_message)).await?;
+            self.step_on_connection(
+                wallet_handle,
+                msg.into(),
+                Some(send_message),
+                Some(&connection.get_thread_id()),
+            )
+            .await?;
             connection.update_message_status(&uid, agency_client).await?;
+        } else if self.is_expired() {
+            self.step(
+                wallet_handle,
+                VerifierMessages::PresentationRequestExpired,
+                Some(send_message),
+            )
+            .await?;
+        }
+        if let Err(err) = self
+            .apply_auto_policy(
+                wallet_handle,
+                previous_state,
+                connection.send_message_closure(wallet_handle)?,
+            )
+            .await
+        {
+            warn!("Verifier::update_state >>> failed to apply auto policy, err: {:?}", err);
         }
         Ok(self.get_state())
     }
@@ -203,10 +421,15 @@ impl Verifier {
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod unit_tests {
+    use std::sync::{Arc, Mutex};
+
     use indy_sys::WalletHandle;
 
     use crate::messages::a2a::A2AMessage;
+    use crate::messages::error::ProblemCode;
     use crate::messages::proof_presentation::presentation::test_utils::_presentation;
+    use crate::messages::proof_presentation::presentation::Presentation;
+    use crate::messages::proof_presentation::presentation_proposal::test_utils::_presentation_proposal;
     use crate::utils::constants::{REQUESTED_ATTRS, REQUESTED_PREDICATES};
     use crate::utils::devsetup::*;
     use crate::utils::mockdata::mock_settings::MockBuilder;
@@ -261,4 +484,90 @@ mod unit_tests {
         assert_eq!(presentation, json!(_presentation().to_a2a_message()).to_string());
         assert_eq!(verifier.get_state(), VerifierState::Finished);
     }
+
+    fn _recording_send_message(acked: Arc<Mutex<bool>>) -> SendClosure {
+        Box::new(move |message: A2AMessage| {
+            *acked.lock().unwrap() = matches!(message, A2AMessage::PresentationAck(_));
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_apply_auto_policy_acks_verified_presentation() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init().set_mock_result_for_validate_indy_proof(Ok(true));
+        let mut verifier = _verifier().await;
+        verifier.set_auto_policy(VerifierAutoPolicy::create().set_auto_ack(true));
+        verifier.to_presentation_request_sent_state().await;
+        let presentation = Presentation {
+            please_ack: None,
+            .._presentation()
+        };
+        verifier
+            .step(
+                _dummy_wallet_handle(),
+                VerifierMessages::VerifyPresentation(presentation),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        let acked = Arc::new(Mutex::new(false));
+        verifier
+            .apply_auto_policy(
+                _dummy_wallet_handle(),
+                VerifierState::Finished,
+                _recording_send_message(acked.clone()),
+            )
+            .await
+            .unwrap();
+        assert!(!*acked.lock().unwrap());
+
+        verifier
+            .apply_auto_policy(
+                _dummy_wallet_handle(),
+                VerifierState::PresentationRequestSent,
+                _recording_send_message(acked.clone()),
+            )
+            .await
+            .unwrap();
+        assert!(*acked.lock().unwrap());
+        assert_eq!(verifier.get_state(), VerifierState::Finished);
+    }
+
+    #[tokio::test]
+    async fn test_step_fails_expired_request() {
+        let _setup = SetupMocks::init();
+        let mut verifier = _verifier().await;
+        verifier.set_request_expiration(Duration::seconds(-1)).unwrap();
+        verifier.to_presentation_request_sent_state().await;
+        assert!(verifier.is_expired());
+
+        verifier
+            .step(
+                _dummy_wallet_handle(),
+                VerifierMessages::PresentationProposalReceived(_presentation_proposal()),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(verifier.get_state(), VerifierState::Failed);
+        assert_eq!(
+            verifier.get_problem_report().unwrap().get_code(),
+            Some(ProblemCode::RequestTime)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_presentation_request_fails_for_invalid_request() {
+        let _setup = SetupMocks::init();
+        let presentation_request_data = PresentationRequestData::create("1").await.unwrap();
+        let mut verifier = Verifier::create_from_request("1".to_string(), &presentation_request_data).unwrap();
+        let err = verifier
+            .send_presentation_request(_send_message().unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::InvalidProofRequest);
+        assert_eq!(verifier.get_state(), VerifierState::PresentationRequestSet);
+    }
 }
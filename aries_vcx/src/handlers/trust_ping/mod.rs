@@ -1,8 +1,10 @@
+use chrono::Utc;
+
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
 
 use crate::messages::trust_ping::ping::Ping;
 use crate::messages::trust_ping::ping_response::PingResponse;
-use crate::protocols::trustping::build_ping;
+use crate::protocols::trustping::{build_ping, build_ping_response};
 use crate::protocols::SendClosure;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -10,6 +12,10 @@ pub struct TrustPingSender {
     ping: Ping,
     ping_sent: bool,
     response_received: bool,
+    #[serde(default)]
+    sent_at: Option<i64>,
+    #[serde(default)]
+    rtt_ms: Option<u64>,
 }
 
 impl TrustPingSender {
@@ -19,6 +25,8 @@ impl TrustPingSender {
             ping,
             ping_sent: false,
             response_received: false,
+            sent_at: None,
+            rtt_ms: None,
         }
     }
 
@@ -30,6 +38,15 @@ impl TrustPingSender {
         self.ping.get_thread_id()
     }
 
+    pub fn is_response_received(&self) -> bool {
+        self.response_received
+    }
+
+    /// Round-trip time in milliseconds, available once the ping response has been handled.
+    pub fn get_rtt(&self) -> Option<u64> {
+        self.rtt_ms
+    }
+
     pub async fn send_ping(&mut self, send_message: SendClosure) -> VcxResult<()> {
         if self.ping_sent {
             return Err(VcxError::from_msg(
@@ -39,6 +56,7 @@ impl TrustPingSender {
         }
         send_message(self.ping.to_a2a_message()).await?;
         self.ping_sent = true;
+        self.sent_at = Some(Utc::now().timestamp_millis());
         Ok(())
     }
 
@@ -49,19 +67,31 @@ impl TrustPingSender {
         if !self.ping.response_requested {
             return Err(VcxError::from_msg(VcxErrorKind::NotReady, "Message was not expected"));
         } else {
-            self.response_received = true
+            self.response_received = true;
+            self.rtt_ms = self
+                .sent_at
+                .map(|sent_at| (Utc::now().timestamp_millis() - sent_at).max(0) as u64);
         }
         Ok(())
     }
 }
 
+/// Answers the ping if the sender requested a response. Returns whether response was sent.
+pub async fn respond_to_ping(ping: &Ping, send_message: SendClosure) -> VcxResult<bool> {
+    if !ping.response_requested {
+        return Ok(false);
+    }
+    send_message(build_ping_response(ping).to_a2a_message()).await?;
+    Ok(true)
+}
+
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod unit_tests {
     use crate::error::VcxResult;
-    use crate::handlers::trust_ping::TrustPingSender;
+    use crate::handlers::trust_ping::{respond_to_ping, TrustPingSender};
     use crate::messages::a2a::A2AMessage;
-    use crate::protocols::trustping::build_ping_response;
+    use crate::protocols::trustping::{build_ping, build_ping_response};
     use crate::protocols::SendClosure;
     use crate::utils::devsetup::SetupMocks;
 
@@ -76,6 +106,17 @@ mod unit_tests {
         sender.send_ping(_send_message()).await.unwrap();
         let ping_response = build_ping_response(&sender.ping);
         sender.handle_ping_response(&ping_response).unwrap();
+        assert!(sender.is_response_received());
+        assert!(sender.get_rtt().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_respond_to_ping_only_if_requested() {
+        let _setup = SetupMocks::init();
+        assert!(respond_to_ping(&build_ping(true, None), _send_message()).await.unwrap());
+        assert!(!respond_to_ping(&build_ping(false, None), _send_message())
+            .await
+            .unwrap());
     }
 
     #[tokio::test]
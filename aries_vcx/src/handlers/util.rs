@@ -1,3 +1,6 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
 
 use crate::messages::a2a::A2AMessage;
@@ -14,3 +17,17 @@ pub fn verify_thread_id(thread_id: &str, message: &A2AMessage) -> VcxResult<()>
     };
     Ok(())
 }
+
+/// Reads auto policy serialized along the state machine of a handler. Handlers serialized without
+/// it get the default policy, which automates nothing.
+pub(crate) fn deserialize_auto_policy<T: DeserializeOwned + Default>(value: &Value) -> VcxResult<T> {
+    match value.get("auto_policy") {
+        Some(auto_policy) => serde_json::from_value(auto_policy.clone()).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Cannot deserialize auto policy: {}", err),
+            )
+        }),
+        None => Ok(T::default()),
+    }
+}
@@ -39,6 +39,7 @@ pub mod handlers;
 pub mod actors;
 pub mod did_doc;
 pub mod error;
+pub mod events;
 pub mod global;
 pub mod libindy;
 pub mod messages;
@@ -0,0 +1,210 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::libindy::proofs::proof_request_internal::Filter;
+use crate::libindy::utils::anoncreds::{
+    libindy_prover_close_credentials_search, libindy_prover_delete_credential, libindy_prover_fetch_credentials,
+    libindy_prover_get_credential, libindy_prover_search_credentials,
+};
+use crate::utils::qualifier;
+
+const FETCH_BATCH_SIZE: usize = 100;
+
+/// Credential held in the wallet with its raw attribute values.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CredentialInfo {
+    pub referent: String,
+    #[serde(default)]
+    pub attrs: HashMap<String, String>,
+    pub schema_id: String,
+    pub cred_def_id: String,
+    pub rev_reg_id: Option<String>,
+    pub cred_rev_id: Option<String>,
+}
+
+impl CredentialInfo {
+    pub fn issuer_did(&self) -> Option<&str> {
+        qualifier::cred_def_issuer_did(&self.cred_def_id)
+    }
+
+    /// Schema name parsed from unqualified schema id `<did>:2:<name>:<version>`.
+    pub fn schema_name(&self) -> Option<&str> {
+        self.schema_id.split(':').nth(2)
+    }
+
+    pub fn schema_version(&self) -> Option<&str> {
+        self.schema_id.split(':').nth(3)
+    }
+
+    pub fn is_revocable(&self) -> bool {
+        self.rev_reg_id.is_some() && self.cred_rev_id.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CredentialPage {
+    pub credentials: Vec<CredentialInfo>,
+    pub total_count: usize,
+}
+
+pub async fn get_credential(wallet_handle: WalletHandle, cred_id: &str) -> VcxResult<CredentialInfo> {
+    trace!("get_credential >>> cred_id: {}", cred_id);
+    let credential = libindy_prover_get_credential(wallet_handle, cred_id).await?;
+    serde_json::from_str(&credential).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!("Cannot deserialize credential {}: {}", cred_id, err),
+        )
+    })
+}
+
+/// Returns page of credentials held in the wallet which match all conditions of the filter, in the
+/// order returned by the wallet.
+pub async fn get_credentials(
+    wallet_handle: WalletHandle,
+    filter: &Filter,
+    offset: usize,
+    limit: usize,
+) -> VcxResult<CredentialPage> {
+    trace!(
+        "get_credentials >>> filter: {:?}, offset: {}, limit: {}",
+        filter,
+        offset,
+        limit
+    );
+    let query = json!(filter).to_string();
+    let (search_handle, total_count) = libindy_prover_search_credentials(wallet_handle, &query).await?;
+    let credentials = _fetch_credentials(search_handle, offset.saturating_add(limit)).await;
+    libindy_prover_close_credentials_search(search_handle).await?;
+    Ok(CredentialPage {
+        credentials: credentials?.into_iter().skip(offset).collect(),
+        total_count,
+    })
+}
+
+pub async fn delete_credential(wallet_handle: WalletHandle, cred_id: &str) -> VcxResult<()> {
+    trace!("delete_credential >>> cred_id: {}", cred_id);
+    libindy_prover_delete_credential(wallet_handle, cred_id).await
+}
+
+async fn _fetch_credentials(search_handle: i32, count: usize) -> VcxResult<Vec<CredentialInfo>> {
+    let mut credentials = Vec::new();
+    while credentials.len() < count {
+        let batch_size = cmp::min(FETCH_BATCH_SIZE, count - credentials.len());
+        let batch = _parse_credentials(&libindy_prover_fetch_credentials(search_handle, batch_size).await?)?;
+        if batch.is_empty() {
+            break;
+        }
+        credentials.extend(batch);
+    }
+    Ok(credentials)
+}
+
+fn _parse_credentials(credentials: &str) -> VcxResult<Vec<CredentialInfo>> {
+    serde_json::from_str(credentials).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!("Cannot deserialize credentials fetched from wallet: {}", err),
+        )
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use serde_json::Value;
+
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+
+    use super::*;
+
+    fn _credentials(count: usize) -> String {
+        let credentials: Vec<Value> = (0..count)
+            .map(|i| {
+                json!({
+                    "referent": format!("cred_{}", i),
+                    "attrs": {"name": "Alice"},
+                    "schema_id": "2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4",
+                    "cred_def_id": "2hoqvcwupRTUNkXn6ArYzs:3:CL:1766:tag1",
+                    "rev_reg_id": null,
+                    "cred_rev_id": null
+                })
+            })
+            .collect();
+        json!(credentials).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_get_credential_returns_typed_record() {
+        let _setup = SetupMocks::init();
+
+        let credential = get_credential(WalletHandle(0), "cred_id").await.unwrap();
+        assert_eq!(credential.referent, "cred_id");
+        assert_eq!(credential.attrs["name"], "Alice");
+        assert_eq!(credential.issuer_did(), Some("2hoqvcwupRTUNkXn6ArYzs"));
+        assert_eq!(credential.schema_name(), Some("test-licence"));
+        assert_eq!(credential.schema_version(), Some("4.4.4"));
+        assert!(!credential.is_revocable());
+    }
+
+    #[tokio::test]
+    async fn test_get_credentials_builds_page() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init().set_mock_creds_found_by_search(&_credentials(250));
+
+        let filter = Filter::create()
+            .set_issuer_did("2hoqvcwupRTUNkXn6ArYzs")
+            .set_attr_value("name", "Alice");
+        let page = get_credentials(WalletHandle(0), &filter, 195, 10).await.unwrap();
+        assert_eq!(page.total_count, 250);
+        let referents: Vec<String> = page.credentials.into_iter().map(|cred| cred.referent).collect();
+        assert_eq!(
+            referents,
+            (195..205).map(|i| format!("cred_{}", i)).collect::<Vec<String>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_credentials_returns_partial_last_page() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init().set_mock_creds_found_by_search(&_credentials(12));
+
+        let page = get_credentials(WalletHandle(0), &Filter::create(), 10, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.total_count, 12);
+        let referents: Vec<String> = page.credentials.into_iter().map(|cred| cred.referent).collect();
+        assert_eq!(referents, vec!["cred_10", "cred_11"]);
+
+        let _mock_builder = MockBuilder::init().set_mock_creds_found_by_search(&_credentials(12));
+        let page = get_credentials(WalletHandle(0), &Filter::create(), 20, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.total_count, 12);
+        assert!(page.credentials.is_empty());
+    }
+
+    #[test]
+    fn test_parse_credentials() {
+        let credentials = json!([
+            {
+                "referent": "cred_1",
+                "attrs": {"name": "Alice"},
+                "schema_id": "2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4",
+                "cred_def_id": "2hoqvcwupRTUNkXn6ArYzs:3:CL:1766:tag1",
+                "rev_reg_id": "2hoqvcwupRTUNkXn6ArYzs:4:2hoqvcwupRTUNkXn6ArYzs:3:CL:1766:tag1:CL_ACCUM:tag1",
+                "cred_rev_id": "1"
+            }
+        ])
+        .to_string();
+
+        let credentials = _parse_credentials(&credentials).unwrap();
+        assert_eq!(credentials.len(), 1);
+        assert!(credentials[0].is_revocable());
+        assert_eq!(_parse_credentials("{}").unwrap_err().kind(), VcxErrorKind::InvalidJson);
+    }
+}
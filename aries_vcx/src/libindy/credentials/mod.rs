@@ -1,6 +1,9 @@
+pub mod credential_store;
+
 use std::collections::HashMap;
 
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
+use crate::utils::encoding::{normalize_raw_value, parse_mime_type};
 use crate::utils::error;
 use crate::utils::openssl::encode;
 
@@ -72,12 +75,16 @@ pub fn encode_attributes(attributes: &str) -> VcxResult<String> {
                             VcxErrorKind::InvalidAttributesStructure,
                             format!("No 'value' field in cred_value: {:?}", cred_value),
                         ))?;
-                        let encoded = encode(value.as_str().ok_or(VcxError::from_msg(
-                            VcxErrorKind::InvalidAttributesStructure,
-                            format!("Failed to convert value {:?} to string", value),
-                        ))?)?;
+                        let raw = normalize_raw_value(
+                            value.as_str().ok_or(VcxError::from_msg(
+                                VcxErrorKind::InvalidAttributesStructure,
+                                format!("Failed to convert value {:?} to string", value),
+                            ))?,
+                            &parse_mime_type(cred_value)?,
+                        )?;
+                        let encoded = encode(&raw)?;
                         let attrib_values = json!({
-                            "raw": value,
+                            "raw": raw,
                             "encoded": encoded
                         });
                         let name = name
@@ -220,6 +227,32 @@ pub mod unit_tests {
         assert_eq!(expected, results);
     }
 
+    #[test]
+    fn test_encode_with_aries_format_mime_types() {
+        let _setup = SetupDefaults::init();
+
+        let image = base64::encode(&[0xff, 0xd8, 0xff]);
+        let credential_data = json!([
+            {"name": "name", "value": "Alice"},
+            {"name": "birthdate", "value": "1990-04-21", "mime-type": "text/date"},
+            {"name": "photo", "value": image, "mime-type": "image/jpeg"}
+        ])
+        .to_string();
+
+        let results: Value = serde_json::from_str(&encode_attributes(&credential_data).unwrap()).unwrap();
+        assert_eq!(results["birthdate"], json!({"raw": "19900421", "encoded": "19900421"}));
+        assert_eq!(results["photo"]["raw"], json!(image));
+        assert_eq!(results["photo"]["encoded"], json!(encode(&image).unwrap()));
+        assert_eq!(results["name"]["encoded"], json!(encode("Alice").unwrap()));
+
+        let credential_data =
+            json!([{"name": "birthdate", "value": "21.4.1990", "mime-type": "text/date"}]).to_string();
+        assert_eq!(
+            encode_attributes(&credential_data).unwrap_err().kind(),
+            VcxErrorKind::EncodeError
+        );
+    }
+
     #[test]
     fn test_encode_with_new_format_several_attributes_success() {
         let _setup = SetupDefaults::init();
@@ -1,4 +1,5 @@
 pub mod proof_request;
 pub mod proof_request_internal;
+pub mod proof_request_template;
 pub mod prover;
 pub mod verifier; // TODO: Should stay private
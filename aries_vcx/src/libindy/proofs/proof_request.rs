@@ -86,6 +86,24 @@ impl ProofRequestData {
         Ok(self)
     }
 
+    pub fn add_requested_attribute(mut self, referent: &str, attribute: AttrInfo) -> Self {
+        self.requested_attributes.insert(referent.to_string(), attribute);
+        self
+    }
+
+    pub fn add_requested_predicate(mut self, referent: &str, predicate: PredicateInfo) -> Self {
+        self.requested_predicates.insert(referent.to_string(), predicate);
+        self
+    }
+
+    pub fn set_non_revoked(mut self, from: Option<u64>, to: Option<u64>) -> Self {
+        self.non_revoked = match (from, to) {
+            (None, None) => None,
+            (from, to) => Some(NonRevokedInterval { from, to }),
+        };
+        self
+    }
+
     pub fn set_not_revoked_interval(mut self, non_revoc_interval: String) -> VcxResult<Self> {
         let non_revoc_interval: NonRevokedInterval = ::serde_json::from_str(&non_revoc_interval).map_err(|_| {
             VcxError::from_msg(
@@ -101,6 +119,30 @@ impl ProofRequestData {
 
         Ok(self)
     }
+
+    /// Checks the request is well formed, so that it is not rejected by the prover's libindy.
+    pub fn validate(&self) -> VcxResult<()> {
+        if self.requested_attributes.is_empty() && self.requested_predicates.is_empty() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidProofRequest,
+                "Proof request contains neither requested attributes nor requested predicates",
+            ));
+        }
+        for (referent, attribute) in self.requested_attributes.iter() {
+            attribute
+                .validate()
+                .map_err(|err| err.extend(format!("Invalid requested attribute {}", referent)))?;
+        }
+        for (referent, predicate) in self.requested_predicates.iter() {
+            predicate
+                .validate()
+                .map_err(|err| err.extend(format!("Invalid requested predicate {}", referent)))?;
+        }
+        match &self.non_revoked {
+            Some(interval) => interval.validate(),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Default for ProofRequestData {
@@ -121,6 +163,7 @@ impl Default for ProofRequestData {
 mod unit_tests {
     use serde_json::Value;
 
+    use crate::libindy::proofs::proof_request_internal::{Filter, PredicateType, Restriction};
     use crate::utils;
     use crate::utils::constants::{REQUESTED_ATTRS, REQUESTED_PREDICATES};
     use crate::utils::devsetup::SetupDefaults;
@@ -281,6 +324,142 @@ mod unit_tests {
         assert_eq!(VcxErrorKind::InvalidProofRequest, err.kind());
     }
 
+    #[tokio::test]
+    async fn test_typed_proof_request_builder() {
+        let _setup = SetupDefaults::init();
+
+        let schema_id = "6XFh8yBzrpJQmNyZzgoTqB:2:schema_name:0.0.11";
+        let restriction = Restriction::from(
+            Filter::create()
+                .set_schema_id(schema_id)
+                .set_attr_value("name", "Alice"),
+        )
+        .or(Filter::create()
+            .set_issuer_did("8XFh8yBzrpJQmNyZzgoTqB")
+            .set_attr_marker("name")
+            .into())
+        .and(Filter::create().set_schema_name("Faber Student Info").into());
+        let request = ProofRequestData::create("Test")
+            .await
+            .unwrap()
+            .add_requested_attribute(
+                "name_1",
+                AttrInfo::create_with_name("name")
+                    .set_restrictions(restriction)
+                    .set_non_revoked(None, Some(1600000000)),
+            )
+            .add_requested_predicate(
+                "age_1",
+                PredicateInfo::create("age", PredicateType::Less, 65)
+                    .set_restrictions(Filter::create().set_schema_id(schema_id).into()),
+            )
+            .set_non_revoked(Some(1100000000), Some(1600000000));
+        request.validate().unwrap();
+
+        let msg_as_value = json!(request);
+        assert_eq!(
+            msg_as_value["requested_attributes"]["name_1"]["restrictions"],
+            json!({
+                "$and": [
+                    {"$or": [
+                        {"schema_id": schema_id, "attr::name::value": "Alice"},
+                        {"issuer_did": "8XFh8yBzrpJQmNyZzgoTqB", "attr::name::marker": "1"}
+                    ]},
+                    {"schema_name": "Faber Student Info"}
+                ]
+            })
+        );
+        assert_eq!(
+            msg_as_value["requested_attributes"]["name_1"]["non_revoked"],
+            json!({"from": null, "to": 1600000000})
+        );
+        assert_eq!(msg_as_value["requested_predicates"]["age_1"]["p_type"], "<");
+        assert_eq!(
+            msg_as_value["requested_predicates"]["age_1"]["restrictions"],
+            json!([{ "schema_id": schema_id }])
+        );
+        assert_eq!(
+            serde_json::from_value::<ProofRequestData>(msg_as_value).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn test_predicate_type_accepts_legacy_names() {
+        let _setup = SetupDefaults::init();
+
+        for (legacy, expected) in [
+            ("GE", PredicateType::GreaterOrEqual),
+            ("GT", PredicateType::Greater),
+            ("LE", PredicateType::LessOrEqual),
+            ("LT", PredicateType::Less),
+        ]
+        .iter()
+        {
+            let p_type: PredicateType = serde_json::from_value(json!(legacy)).unwrap();
+            assert_eq!(p_type, *expected);
+        }
+        assert_eq!(json!(PredicateType::GreaterOrEqual), json!(">="));
+        assert!(serde_json::from_value::<PredicateType>(json!("EQ")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_malformed_requests() {
+        let _setup = SetupDefaults::init();
+
+        let request = ProofRequestData::create("Test").await.unwrap();
+        assert_eq!(
+            request.validate().unwrap_err().kind(),
+            VcxErrorKind::InvalidProofRequest
+        );
+
+        let invalid_attributes = vec![
+            AttrInfo::default(),
+            AttrInfo::create_with_names(&[]),
+            AttrInfo::create_with_name("name").set_non_revoked(Some(20), Some(10)),
+            AttrInfo::create_with_name("name")
+                .set_restrictions(Filter::create().set_schema_name("Faber Student Info").into())
+                .set_self_attest_allowed(true),
+            AttrInfo::create_with_name("name").set_restrictions(Restriction::Or(vec![])),
+            AttrInfo::create_with_name("name").set_restrictions(Filter::create().into()),
+        ];
+        for attribute in invalid_attributes {
+            let request = request.clone().add_requested_attribute("attribute_0", attribute);
+            assert_eq!(
+                request.validate().unwrap_err().kind(),
+                VcxErrorKind::InvalidProofRequest
+            );
+        }
+
+        let request = request
+            .add_requested_predicate(
+                "predicate_0",
+                PredicateInfo::create("age", PredicateType::GreaterOrEqual, 18),
+            )
+            .set_non_revoked(Some(20), Some(10));
+        assert_eq!(
+            request.validate().unwrap_err().kind(),
+            VcxErrorKind::InvalidProofRequest
+        );
+        request.set_non_revoked(Some(10), Some(20)).validate().unwrap();
+    }
+
+    #[test]
+    fn test_predicate_for_date() {
+        let predicate = PredicateInfo::create_for_date("birthdate", PredicateType::LessOrEqual, "2004-10-18").unwrap();
+        assert_eq!(predicate.p_value, 20041018);
+        assert_eq!(
+            json!(predicate),
+            json!({"name": "birthdate", "p_type": "<=", "p_value": 20041018})
+        );
+        assert_eq!(
+            PredicateInfo::create_for_date("birthdate", PredicateType::LessOrEqual, "18.10.2004")
+                .unwrap_err()
+                .kind(),
+            VcxErrorKind::EncodeError
+        );
+    }
+
     #[test]
     fn test_indy_proof_req_parses_correctly() {
         let _setup = SetupDefaults::init();
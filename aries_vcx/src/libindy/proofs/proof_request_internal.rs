@@ -1,4 +1,11 @@
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::error::prelude::*;
+use crate::utils::encoding::date_to_integer;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct Filter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_id: Option<String>,
@@ -12,6 +19,150 @@ pub struct Filter {
     pub issuer_did: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cred_def_id: Option<String>,
+    /// Attribute value and marker restrictions, keyed as `attr::<name>::value` and `attr::<name>::marker`.
+    #[serde(flatten)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl Filter {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_schema_id(mut self, schema_id: &str) -> Self {
+        self.schema_id = Some(schema_id.to_string());
+        self
+    }
+
+    pub fn set_schema_issuer_did(mut self, schema_issuer_did: &str) -> Self {
+        self.schema_issuer_did = Some(schema_issuer_did.to_string());
+        self
+    }
+
+    pub fn set_schema_name(mut self, schema_name: &str) -> Self {
+        self.schema_name = Some(schema_name.to_string());
+        self
+    }
+
+    pub fn set_schema_version(mut self, schema_version: &str) -> Self {
+        self.schema_version = Some(schema_version.to_string());
+        self
+    }
+
+    pub fn set_issuer_did(mut self, issuer_did: &str) -> Self {
+        self.issuer_did = Some(issuer_did.to_string());
+        self
+    }
+
+    pub fn set_cred_def_id(mut self, cred_def_id: &str) -> Self {
+        self.cred_def_id = Some(cred_def_id.to_string());
+        self
+    }
+
+    /// Requires the credential to hold `value` as raw value of the attribute `name`.
+    pub fn set_attr_value(mut self, name: &str, value: &str) -> Self {
+        self.attributes
+            .insert(format!("attr::{}::value", name), value.to_string());
+        self
+    }
+
+    /// Requires the credential to contain the attribute `name`, whatever its value.
+    pub fn set_attr_marker(mut self, name: &str) -> Self {
+        self.attributes
+            .insert(format!("attr::{}::marker", name), "1".to_string());
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Typed restriction query, combining filters with `$and` and `$or` operators.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Restriction {
+    Filter(Filter),
+    And(Vec<Restriction>),
+    Or(Vec<Restriction>),
+}
+
+impl Restriction {
+    pub fn and(self, other: Restriction) -> Self {
+        match self {
+            Restriction::And(mut restrictions) => {
+                restrictions.push(other);
+                Restriction::And(restrictions)
+            }
+            restriction => Restriction::And(vec![restriction, other]),
+        }
+    }
+
+    pub fn or(self, other: Restriction) -> Self {
+        match self {
+            Restriction::Or(mut restrictions) => {
+                restrictions.push(other);
+                Restriction::Or(restrictions)
+            }
+            restriction => Restriction::Or(vec![restriction, other]),
+        }
+    }
+
+    pub fn validate(&self) -> VcxResult<()> {
+        match self {
+            Restriction::Filter(filter) if filter.is_empty() => Err(VcxError::from_msg(
+                VcxErrorKind::InvalidProofRequest,
+                "Restriction filter does not restrict anything",
+            )),
+            Restriction::Filter(_) => Ok(()),
+            Restriction::And(restrictions) | Restriction::Or(restrictions) => {
+                if restrictions.is_empty() {
+                    return Err(VcxError::from_msg(
+                        VcxErrorKind::InvalidProofRequest,
+                        "Restriction operator has no operands",
+                    ));
+                }
+                restrictions.iter().try_for_each(Restriction::validate)
+            }
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Restriction::Filter(filter) => json!(filter),
+            Restriction::And(restrictions) => {
+                json!({ "$and": restrictions.iter().map(Restriction::to_value).collect::<Vec<Value>>() })
+            }
+            Restriction::Or(restrictions) => {
+                json!({ "$or": restrictions.iter().map(Restriction::to_value).collect::<Vec<Value>>() })
+            }
+        }
+    }
+}
+
+impl From<Filter> for Restriction {
+    fn from(filter: Filter) -> Self {
+        Restriction::Filter(filter)
+    }
+}
+
+impl From<Restriction> for Restrictions {
+    fn from(restriction: Restriction) -> Self {
+        match restriction {
+            Restriction::Filter(filter) => Restrictions::V1(vec![filter]),
+            Restriction::Or(restrictions) if restrictions.iter().all(|r| matches!(r, Restriction::Filter(_))) => {
+                Restrictions::V1(
+                    restrictions
+                        .into_iter()
+                        .filter_map(|restriction| match restriction {
+                            Restriction::Filter(filter) => Some(filter),
+                            _ => None,
+                        })
+                        .collect(),
+                )
+            }
+            restriction => Restrictions::V2(restriction.to_value()),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -21,11 +172,22 @@ pub enum Restrictions {
     V2(serde_json::Value),
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PredicateType {
+    #[serde(rename = ">=", alias = "GE")]
+    GreaterOrEqual,
+    #[serde(rename = ">", alias = "GT")]
+    Greater,
+    #[serde(rename = "<=", alias = "LE")]
+    LessOrEqual,
+    #[serde(rename = "<", alias = "LT")]
+    Less,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct PredicateInfo {
     pub name: String,
-    //Todo: Update p_type to use Enum
-    pub p_type: String,
+    pub p_type: PredicateType,
     pub p_value: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub restrictions: Option<Restrictions>,
@@ -33,6 +195,45 @@ pub struct PredicateInfo {
     pub non_revoked: Option<NonRevokedInterval>,
 }
 
+impl PredicateInfo {
+    pub fn create(name: &str, p_type: PredicateType, p_value: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            p_type,
+            p_value,
+            restrictions: None,
+            non_revoked: None,
+        }
+    }
+
+    /// Predicate over attribute issued with `MimeType::Date`, comparing it with `date` given as
+    /// `YYYY-MM-DD`.
+    pub fn create_for_date(name: &str, p_type: PredicateType, date: &str) -> VcxResult<Self> {
+        Ok(Self::create(name, p_type, date_to_integer(date)?))
+    }
+
+    pub fn set_restrictions(mut self, restriction: Restriction) -> Self {
+        self.restrictions = Some(restriction.into());
+        self
+    }
+
+    pub fn set_non_revoked(mut self, from: Option<u64>, to: Option<u64>) -> Self {
+        self.non_revoked = Some(NonRevokedInterval { from, to });
+        self
+    }
+
+    pub fn validate(&self) -> VcxResult<()> {
+        if self.name.is_empty() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidProofRequest,
+                "Requested predicate has empty attribute name",
+            ));
+        }
+        _validate_restrictions(&self.restrictions)?;
+        _validate_non_revoked(&self.non_revoked)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct AttrInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -47,8 +248,109 @@ pub struct AttrInfo {
     pub self_attest_allowed: Option<bool>,
 }
 
+impl AttrInfo {
+    pub fn create_with_name(name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn create_with_names(names: &[&str]) -> Self {
+        Self {
+            names: Some(names.iter().map(|name| name.to_string()).collect()),
+            ..Self::default()
+        }
+    }
+
+    pub fn set_restrictions(mut self, restriction: Restriction) -> Self {
+        self.restrictions = Some(restriction.into());
+        self
+    }
+
+    pub fn set_non_revoked(mut self, from: Option<u64>, to: Option<u64>) -> Self {
+        self.non_revoked = Some(NonRevokedInterval { from, to });
+        self
+    }
+
+    pub fn set_self_attest_allowed(mut self, self_attest_allowed: bool) -> Self {
+        self.self_attest_allowed = Some(self_attest_allowed);
+        self
+    }
+
+    pub fn validate(&self) -> VcxResult<()> {
+        match (&self.name, &self.names) {
+            (Some(_), Some(_)) => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidProofRequest,
+                    "Requested attribute can contain either 'name' or 'names'. Not both.",
+                ))
+            }
+            (None, None) => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidProofRequest,
+                    "Requested attribute must contain either 'name' or 'names'",
+                ))
+            }
+            (Some(name), None) if name.is_empty() => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidProofRequest,
+                    "Requested attribute has empty name",
+                ))
+            }
+            (None, Some(names)) if names.is_empty() || names.iter().any(String::is_empty) => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidProofRequest,
+                    "Requested attribute has empty or missing names",
+                ))
+            }
+            _ => {}
+        };
+        if self.self_attest_allowed == Some(true) && self.restrictions.is_some() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidProofRequest,
+                "Requested attribute with restrictions cannot be self attested",
+            ));
+        }
+        _validate_restrictions(&self.restrictions)?;
+        _validate_non_revoked(&self.non_revoked)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct NonRevokedInterval {
     pub from: Option<u64>,
     pub to: Option<u64>,
 }
+
+impl NonRevokedInterval {
+    pub fn validate(&self) -> VcxResult<()> {
+        match (self.from, self.to) {
+            (Some(from), Some(to)) if from > to => Err(VcxError::from_msg(
+                VcxErrorKind::InvalidProofRequest,
+                format!("Non-revoked interval starts after it ends, from: {}, to: {}", from, to),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn _validate_restrictions(restrictions: &Option<Restrictions>) -> VcxResult<()> {
+    match restrictions {
+        Some(Restrictions::V1(filters)) if filters.is_empty() => Err(VcxError::from_msg(
+            VcxErrorKind::InvalidProofRequest,
+            "Restrictions list is empty",
+        )),
+        Some(Restrictions::V1(filters)) => filters
+            .iter()
+            .try_for_each(|filter| Restriction::Filter(filter.clone()).validate()),
+        _ => Ok(()),
+    }
+}
+
+fn _validate_non_revoked(non_revoked: &Option<NonRevokedInterval>) -> VcxResult<()> {
+    match non_revoked {
+        Some(interval) => interval.validate(),
+        None => Ok(()),
+    }
+}
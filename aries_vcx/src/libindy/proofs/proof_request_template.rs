@@ -0,0 +1,449 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::libindy::proofs::proof_request::ProofRequestData;
+use crate::libindy::proofs::proof_request_internal::{AttrInfo, NonRevokedInterval, PredicateInfo};
+use crate::libindy::utils::wallet::{
+    add_wallet_record, delete_wallet_record, search_wallet_records, update_wallet_record_value,
+};
+
+pub const PROOF_REQUEST_TEMPLATE_RECORD_TYPE: &str = "proof_request_template";
+
+/// Named and versioned proof request stored in the wallet, so that verifiers can repeatedly send the
+/// same request. Each instantiation gets its own nonce.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProofRequestTemplate {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub requested_attributes: HashMap<String, AttrInfo>,
+    #[serde(default)]
+    pub requested_predicates: HashMap<String, PredicateInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub non_revoked: Option<NonRevokedInterval>,
+}
+
+/// Parameters replacing parts of the template when instantiating a proof request from it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ProofRequestOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub non_revoked: Option<NonRevokedInterval>,
+    #[serde(default)]
+    pub requested_attributes: HashMap<String, AttrInfo>,
+    #[serde(default)]
+    pub requested_predicates: HashMap<String, PredicateInfo>,
+    /// Predicate values by referent, e.g. to change the age threshold of an existing predicate.
+    #[serde(default)]
+    pub predicate_values: HashMap<String, i32>,
+}
+
+impl ProofRequestOverrides {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn set_non_revoked(mut self, from: Option<u64>, to: Option<u64>) -> Self {
+        self.non_revoked = Some(NonRevokedInterval { from, to });
+        self
+    }
+
+    pub fn set_requested_attribute(mut self, referent: &str, attribute: AttrInfo) -> Self {
+        self.requested_attributes.insert(referent.to_string(), attribute);
+        self
+    }
+
+    pub fn set_requested_predicate(mut self, referent: &str, predicate: PredicateInfo) -> Self {
+        self.requested_predicates.insert(referent.to_string(), predicate);
+        self
+    }
+
+    pub fn set_predicate_value(mut self, referent: &str, p_value: i32) -> Self {
+        self.predicate_values.insert(referent.to_string(), p_value);
+        self
+    }
+
+    pub fn from_string(overrides: &str) -> VcxResult<Self> {
+        serde_json::from_str(overrides).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!(
+                    "Cannot deserialize proof request overrides: {}, error: {}",
+                    overrides, err
+                ),
+            )
+        })
+    }
+}
+
+impl ProofRequestTemplate {
+    pub fn create(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            requested_attributes: HashMap::new(),
+            requested_predicates: HashMap::new(),
+            non_revoked: None,
+        }
+    }
+
+    /// Creates template from existing request data, its nonce is not kept.
+    pub fn from_request(version: &str, request: ProofRequestData) -> Self {
+        Self {
+            name: request.name,
+            version: version.to_string(),
+            requested_attributes: request.requested_attributes,
+            requested_predicates: request.requested_predicates,
+            non_revoked: request.non_revoked,
+        }
+    }
+
+    pub fn set_requested_attribute(mut self, referent: &str, attribute: AttrInfo) -> Self {
+        self.requested_attributes.insert(referent.to_string(), attribute);
+        self
+    }
+
+    pub fn set_requested_predicate(mut self, referent: &str, predicate: PredicateInfo) -> Self {
+        self.requested_predicates.insert(referent.to_string(), predicate);
+        self
+    }
+
+    pub fn set_non_revoked(mut self, from: Option<u64>, to: Option<u64>) -> Self {
+        self.non_revoked = Some(NonRevokedInterval { from, to });
+        self
+    }
+
+    /// Checks the template would make a valid proof request. Name and version must not contain `:`,
+    /// which separates them in the id of the stored record.
+    pub fn validate(&self) -> VcxResult<()> {
+        for (field, value) in [("name", &self.name), ("version", &self.version)] {
+            if value.is_empty() || value.contains(':') {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidProofRequest,
+                    format!(
+                        "Template {} must be non-empty and must not contain ':', found {:?}",
+                        field, value
+                    ),
+                ));
+            }
+        }
+        ProofRequestData {
+            name: self.name.clone(),
+            requested_attributes: self.requested_attributes.clone(),
+            requested_predicates: self.requested_predicates.clone(),
+            non_revoked: self.non_revoked.clone(),
+            ..ProofRequestData::default()
+        }
+        .validate()
+    }
+
+    /// Builds proof request with fresh nonce, applying the overrides on top of the template.
+    pub async fn instantiate(&self, overrides: &ProofRequestOverrides) -> VcxResult<ProofRequestData> {
+        trace!(
+            "ProofRequestTemplate::instantiate >>> name: {}, version: {}, overrides: {:?}",
+            self.name,
+            self.version,
+            overrides
+        );
+        let name = overrides.name.as_deref().unwrap_or(&self.name);
+        let mut request = ProofRequestData::create(name).await?;
+        request.requested_attributes = self.requested_attributes.clone();
+        request.requested_predicates = self.requested_predicates.clone();
+        request.non_revoked = overrides.non_revoked.clone().or_else(|| self.non_revoked.clone());
+        request
+            .requested_attributes
+            .extend(overrides.requested_attributes.clone());
+        request
+            .requested_predicates
+            .extend(overrides.requested_predicates.clone());
+        for (referent, p_value) in overrides.predicate_values.iter() {
+            match request.requested_predicates.get_mut(referent) {
+                Some(predicate) => predicate.p_value = *p_value,
+                None => {
+                    return Err(VcxError::from_msg(
+                        VcxErrorKind::InvalidProofRequest,
+                        format!("Template {} has no predicate {} to override", self.name, referent),
+                    ))
+                }
+            }
+        }
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Stores the template, replacing previously stored template of the same name and version.
+    pub async fn store(&self, wallet_handle: WalletHandle) -> VcxResult<()> {
+        trace!(
+            "ProofRequestTemplate::store >>> name: {}, version: {}",
+            self.name,
+            self.version
+        );
+        self.validate()?;
+        let value = serde_json::to_string(self).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::SerializationError,
+                format!("Cannot serialize proof request template: {}", err),
+            )
+        })?;
+        let record_id = _record_id(&self.name, &self.version);
+        let stored = _search(wallet_handle, &self.name, Some(&self.version)).await?;
+        if stored.is_empty() {
+            let tags = json!({ "name": self.name, "version": self.version }).to_string();
+            add_wallet_record(
+                wallet_handle,
+                PROOF_REQUEST_TEMPLATE_RECORD_TYPE,
+                &record_id,
+                &value,
+                Some(&tags),
+            )
+            .await
+        } else {
+            update_wallet_record_value(wallet_handle, PROOF_REQUEST_TEMPLATE_RECORD_TYPE, &record_id, &value).await
+        }
+    }
+
+    /// Retrieves the template of given version, or its latest version if none is given.
+    pub async fn get(wallet_handle: WalletHandle, name: &str, version: Option<&str>) -> VcxResult<Self> {
+        trace!("ProofRequestTemplate::get >>> name: {}, version: {:?}", name, version);
+        _latest(_search(wallet_handle, name, version).await?).ok_or_else(|| {
+            VcxError::from_msg(
+                VcxErrorKind::WalletRecordNotFound,
+                format!("Proof request template {} of version {:?} not found", name, version),
+            )
+        })
+    }
+
+    /// Lists all stored templates, all versions of `name` only if provided.
+    pub async fn list(wallet_handle: WalletHandle, name: Option<&str>) -> VcxResult<Vec<Self>> {
+        trace!("ProofRequestTemplate::list >>> name: {:?}", name);
+        match name {
+            Some(name) => _search(wallet_handle, name, None).await,
+            None => _parse_records(wallet_handle, "{}").await,
+        }
+    }
+
+    pub async fn delete(wallet_handle: WalletHandle, name: &str, version: &str) -> VcxResult<()> {
+        trace!("ProofRequestTemplate::delete >>> name: {}, version: {}", name, version);
+        delete_wallet_record(
+            wallet_handle,
+            PROOF_REQUEST_TEMPLATE_RECORD_TYPE,
+            &_record_id(name, version),
+        )
+        .await
+    }
+}
+
+fn _record_id(name: &str, version: &str) -> String {
+    format!("{}:{}", name, version)
+}
+
+async fn _search(
+    wallet_handle: WalletHandle,
+    name: &str,
+    version: Option<&str>,
+) -> VcxResult<Vec<ProofRequestTemplate>> {
+    let query = match version {
+        Some(version) => json!({ "name": name, "version": version }),
+        None => json!({ "name": name }),
+    };
+    _parse_records(wallet_handle, &query.to_string()).await
+}
+
+async fn _parse_records(wallet_handle: WalletHandle, query: &str) -> VcxResult<Vec<ProofRequestTemplate>> {
+    Ok(
+        search_wallet_records(wallet_handle, PROOF_REQUEST_TEMPLATE_RECORD_TYPE, query)
+            .await?
+            .iter()
+            .filter_map(|record| {
+                serde_json::from_str::<ProofRequestTemplate>(record.value.as_deref().unwrap_or_default())
+                    .map_err(|err| warn!("ProofRequestTemplate >>> skipping malformed record, err: {:?}", err))
+                    .ok()
+            })
+            .collect(),
+    )
+}
+
+fn _latest(templates: Vec<ProofRequestTemplate>) -> Option<ProofRequestTemplate> {
+    templates
+        .into_iter()
+        .max_by(|a, b| _compare_versions(&a.version, &b.version))
+}
+
+/// Compares dot separated versions numerically by components, falling back to string comparison.
+fn _compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| {
+        version
+            .split('.')
+            .map(|component| component.parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>()
+    };
+    match (parse(a), parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use crate::handlers::proof_presentation::verifier::Verifier;
+    use crate::libindy::proofs::proof_request_internal::{Filter, PredicateType};
+    use crate::utils::devsetup::{SetupDefaults, SetupLibraryWallet};
+
+    use super::*;
+
+    fn _template(version: &str) -> ProofRequestTemplate {
+        ProofRequestTemplate::create("Adult check", version)
+            .set_requested_attribute(
+                "name_1",
+                AttrInfo::create_with_name("name")
+                    .set_restrictions(Filter::create().set_schema_name("Faber Student Info").into()),
+            )
+            .set_requested_predicate("age_1", PredicateInfo::create("age", PredicateType::GreaterOrEqual, 18))
+            .set_non_revoked(None, Some(1600000000))
+    }
+
+    #[tokio::test]
+    async fn test_instantiate_generates_fresh_nonce() {
+        let _setup = SetupDefaults::init();
+
+        let template = _template("1.0");
+        let request_1 = template.instantiate(&ProofRequestOverrides::create()).await.unwrap();
+        let request_2 = template.instantiate(&ProofRequestOverrides::create()).await.unwrap();
+
+        assert_ne!(request_1.nonce, request_2.nonce);
+        assert_eq!(request_1.name, "Adult check");
+        assert_eq!(request_1.requested_attributes, template.requested_attributes);
+        assert_eq!(request_1.requested_predicates, template.requested_predicates);
+        assert_eq!(request_1.non_revoked, template.non_revoked);
+    }
+
+    #[tokio::test]
+    async fn test_instantiate_applies_overrides() {
+        let _setup = SetupDefaults::init();
+
+        let overrides = ProofRequestOverrides::create()
+            .set_name("Senior check")
+            .set_predicate_value("age_1", 65)
+            .set_requested_attribute("email_1", AttrInfo::create_with_name("email"))
+            .set_non_revoked(Some(1500000000), None);
+        let request = _template("1.0").instantiate(&overrides).await.unwrap();
+
+        assert_eq!(request.name, "Senior check");
+        assert_eq!(request.requested_predicates["age_1"].p_value, 65);
+        assert_eq!(request.requested_attributes.len(), 2);
+        assert_eq!(request.requested_attributes["email_1"].name, Some("email".to_string()));
+        assert_eq!(
+            request.non_revoked,
+            Some(NonRevokedInterval {
+                from: Some(1500000000),
+                to: None
+            })
+        );
+
+        let overrides = ProofRequestOverrides::create().set_predicate_value("age_2", 65);
+        assert_eq!(
+            _template("1.0").instantiate(&overrides).await.unwrap_err().kind(),
+            VcxErrorKind::InvalidProofRequest
+        );
+    }
+
+    #[tokio::test]
+    async fn test_store_get_and_create_verifier_from_template() {
+        let setup = SetupLibraryWallet::init().await;
+
+        _template("1.0").store(setup.wallet_handle).await.unwrap();
+        _template("1.2").store(setup.wallet_handle).await.unwrap();
+        _template("1.2")
+            .set_requested_attribute("email_1", AttrInfo::create_with_name("email"))
+            .store(setup.wallet_handle)
+            .await
+            .unwrap();
+
+        let latest = ProofRequestTemplate::get(setup.wallet_handle, "Adult check", None)
+            .await
+            .unwrap();
+        assert_eq!(latest.version, "1.2");
+        assert_eq!(latest.requested_attributes.len(), 2);
+        assert_eq!(
+            ProofRequestTemplate::list(setup.wallet_handle, Some("Adult check"))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let verifier = Verifier::create_from_template(
+            "1".to_string(),
+            setup.wallet_handle,
+            "Adult check",
+            Some("1.0"),
+            &ProofRequestOverrides::create(),
+        )
+        .await
+        .unwrap();
+        let attachment = verifier
+            .get_presentation_request()
+            .unwrap()
+            .request_presentations_attach
+            .content()
+            .unwrap();
+        let request: ProofRequestData = serde_json::from_str(&attachment).unwrap();
+        assert_eq!(request.requested_attributes, _template("1.0").requested_attributes);
+        assert_eq!(request.requested_predicates, _template("1.0").requested_predicates);
+
+        ProofRequestTemplate::delete(setup.wallet_handle, "Adult check", "1.0")
+            .await
+            .unwrap();
+        assert_eq!(
+            ProofRequestTemplate::get(setup.wallet_handle, "Adult check", Some("1.0"))
+                .await
+                .unwrap_err()
+                .kind(),
+            VcxErrorKind::WalletRecordNotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_store_rejects_invalid_template() {
+        let setup = SetupLibraryWallet::init().await;
+
+        let invalid_templates = vec![
+            ProofRequestTemplate::create("Adult check", "1.0"),
+            _template("1:0"),
+            ProofRequestTemplate {
+                name: "Adult:check".to_string(),
+                .._template("1.0")
+            },
+        ];
+        for template in invalid_templates {
+            assert_eq!(
+                template.store(setup.wallet_handle).await.unwrap_err().kind(),
+                VcxErrorKind::InvalidProofRequest
+            );
+        }
+        assert!(ProofRequestTemplate::list(setup.wallet_handle, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_latest_template_compares_versions_numerically() {
+        let _setup = SetupDefaults::init();
+
+        let templates = vec![_template("1.2"), _template("1.10"), _template("1.9")];
+        assert_eq!(_latest(templates).unwrap().version, "1.10");
+        assert_eq!(_latest(vec![]), None);
+        assert_eq!(_compare_versions("2.0", "beta"), Ordering::Less);
+    }
+}
@@ -0,0 +1,471 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde_json::Value;
+
+use crate::error::prelude::*;
+use crate::libindy::credentials::credential_store::CredentialInfo;
+use crate::libindy::proofs::proof_request::ProofRequestData;
+use crate::libindy::proofs::proof_request_internal::NonRevokedInterval;
+use crate::libindy::utils::anoncreds::get_rev_reg_delta_json;
+
+/// Preferences used to pick a single credential for each referent of a presentation request out of the
+/// credentials retrieved for it. Preferences are applied in order: non-revoked credentials first, then
+/// credentials of preferred issuers, then credentials satisfying most referents. Credentials which are
+/// equal in all enabled preferences keep the order they were retrieved in.
+///
+/// There is no preference for the newest credentials: wallet credential records carry no time of
+/// issuance or storage, and the order libindy retrieves them in is not chronological.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SelectionStrategy {
+    #[serde(default)]
+    pub prefer_non_revoked: bool,
+    #[serde(default)]
+    pub preferred_issuers: Vec<String>,
+    #[serde(default)]
+    pub prefer_same_credential: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tails_dir: Option<String>,
+}
+
+impl SelectionStrategy {
+    pub fn create() -> Self {
+        SelectionStrategy::default()
+    }
+
+    /// Checks the ledger for revocation of revocable credentials and picks revoked ones only if there is
+    /// no other credential for the referent.
+    pub fn set_prefer_non_revoked(mut self, prefer_non_revoked: bool) -> Self {
+        self.prefer_non_revoked = prefer_non_revoked;
+        self
+    }
+
+    /// Issuer DIDs ordered from the most preferred one. Credentials of other issuers come last.
+    pub fn set_preferred_issuers(mut self, preferred_issuers: Vec<String>) -> Self {
+        self.preferred_issuers = preferred_issuers;
+        self
+    }
+
+    /// Prefers the credentials which can answer most referents of the request, so that grouped attributes
+    /// are presented from the same credential.
+    pub fn set_prefer_same_credential(mut self, prefer_same_credential: bool) -> Self {
+        self.prefer_same_credential = prefer_same_credential;
+        self
+    }
+
+    /// Directory with tails files, required to present revocable credentials.
+    pub fn set_tails_dir(mut self, tails_dir: &str) -> Self {
+        self.tails_dir = Some(tails_dir.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RetrievedCredential {
+    pub cred_info: CredentialInfo,
+    pub interval: Option<NonRevokedInterval>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct RetrievedCredentials {
+    #[serde(default)]
+    pub attrs: BTreeMap<String, Vec<RetrievedCredential>>,
+    #[serde(default)]
+    pub predicates: BTreeMap<String, Vec<RetrievedCredential>>,
+}
+
+impl RetrievedCredentials {
+    fn candidates(&self) -> impl Iterator<Item = &Vec<RetrievedCredential>> {
+        self.attrs.values().chain(self.predicates.values())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SelectedCredential {
+    pub credential: RetrievedCredential,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tails_file: Option<String>,
+}
+
+/// Credentials selected for the referents of a presentation request, serialized in the format
+/// expected by `Prover::generate_presentation`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SelectedCredentials {
+    pub attrs: BTreeMap<String, SelectedCredential>,
+    #[serde(default)]
+    pub predicates: BTreeMap<String, SelectedCredential>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CredentialSelection {
+    pub selected_credentials: SelectedCredentials,
+    pub unsatisfied_referents: Vec<String>,
+    /// Referents satisfied by more than one held credential, for which the strategy made the choice.
+    pub ambiguous_referents: Vec<String>,
+}
+
+impl CredentialSelection {
+    pub fn is_complete(&self) -> bool {
+        self.unsatisfied_referents.is_empty()
+    }
+
+    /// Whether each referent is satisfied by exactly one held credential.
+    pub fn is_unambiguous(&self) -> bool {
+        self.is_complete() && self.ambiguous_referents.is_empty()
+    }
+
+    /// Moves credentials selected for predicate referents, which libindy retrieves together with the
+    /// attributes, under predicates and reports referents of `proof_request` without any retrieved
+    /// credential as unsatisfied.
+    pub fn match_proof_request(mut self, proof_request: &ProofRequestData) -> Self {
+        for referent in proof_request.requested_predicates.keys() {
+            if let Some(selected) = self.selected_credentials.attrs.remove(referent) {
+                self.selected_credentials
+                    .predicates
+                    .insert(referent.to_string(), selected);
+            }
+        }
+        let selected = &self.selected_credentials;
+        let mut missing: Vec<String> = proof_request
+            .requested_attributes
+            .keys()
+            .filter(|referent| !selected.attrs.contains_key(*referent))
+            .chain(
+                proof_request
+                    .requested_predicates
+                    .keys()
+                    .filter(|referent| !selected.predicates.contains_key(*referent)),
+            )
+            .filter(|referent| !self.unsatisfied_referents.contains(*referent))
+            .cloned()
+            .collect();
+        missing.sort();
+        self.unsatisfied_referents.extend(missing);
+        self
+    }
+
+    pub fn selected_credentials_json(&self) -> VcxResult<String> {
+        serde_json::to_string(&self.selected_credentials).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::SerializationError,
+                format!("Cannot serialize selected credentials: {}", err),
+            )
+        })
+    }
+}
+
+/// Picks a credential for each referent of credentials retrieved by `Prover::retrieve_credentials`,
+/// reporting referents which no held credential satisfies.
+pub async fn select_credentials(
+    retrieved_credentials: &str,
+    strategy: &SelectionStrategy,
+) -> VcxResult<CredentialSelection> {
+    trace!(
+        "select_credentials >>> retrieved_credentials: {}, strategy: {:?}",
+        secret!(retrieved_credentials),
+        strategy
+    );
+    let retrieved_credentials: RetrievedCredentials = match retrieved_credentials.trim() {
+        "" | "{}" => RetrievedCredentials::default(),
+        retrieved_credentials => serde_json::from_str(retrieved_credentials).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Cannot deserialize retrieved credentials: {}", err),
+            )
+        })?,
+    };
+
+    let mut coverage: HashMap<&str, usize> = HashMap::new();
+    for candidates in retrieved_credentials.candidates() {
+        let referents: HashSet<&str> = candidates
+            .iter()
+            .map(|candidate| candidate.cred_info.referent.as_str())
+            .collect();
+        for referent in referents {
+            *coverage.entry(referent).or_default() += 1;
+        }
+    }
+
+    let revoked = if strategy.prefer_non_revoked {
+        _find_revoked_credentials(&retrieved_credentials).await?
+    } else {
+        HashSet::new()
+    };
+
+    let mut selection = CredentialSelection::default();
+    for (attr_referent, candidates) in retrieved_credentials.attrs.iter() {
+        if let Some(selected) =
+            _select_credential(attr_referent, candidates, strategy, &coverage, &revoked, &mut selection)
+        {
+            selection
+                .selected_credentials
+                .attrs
+                .insert(attr_referent.to_string(), selected);
+        }
+    }
+    for (predicate_referent, candidates) in retrieved_credentials.predicates.iter() {
+        if let Some(selected) = _select_credential(
+            predicate_referent,
+            candidates,
+            strategy,
+            &coverage,
+            &revoked,
+            &mut selection,
+        ) {
+            selection
+                .selected_credentials
+                .predicates
+                .insert(predicate_referent.to_string(), selected);
+        }
+    }
+    Ok(selection)
+}
+
+fn _select_credential(
+    referent: &str,
+    candidates: &[RetrievedCredential],
+    strategy: &SelectionStrategy,
+    coverage: &HashMap<&str, usize>,
+    revoked: &HashSet<String>,
+    selection: &mut CredentialSelection,
+) -> Option<SelectedCredential> {
+    if candidates.len() > 1 {
+        selection.ambiguous_referents.push(referent.to_string());
+    }
+    let selected = candidates.iter().enumerate().min_by_key(|(index, candidate)| {
+        let info = &candidate.cred_info;
+        let issuer_rank = strategy
+            .preferred_issuers
+            .iter()
+            .position(|issuer_did| Some(issuer_did.as_str()) == info.issuer_did())
+            .unwrap_or(strategy.preferred_issuers.len());
+        let coverage = if strategy.prefer_same_credential {
+            coverage.get(info.referent.as_str()).cloned().unwrap_or_default()
+        } else {
+            0
+        };
+        (revoked.contains(&info.referent), issuer_rank, Reverse(coverage), *index)
+    });
+    match selected {
+        Some((_, credential)) => Some(SelectedCredential {
+            credential: credential.clone(),
+            tails_file: credential.cred_info.rev_reg_id.as_ref().and(strategy.tails_dir.clone()),
+        }),
+        None => {
+            selection.unsatisfied_referents.push(referent.to_string());
+            None
+        }
+    }
+}
+
+async fn _find_revoked_credentials(retrieved_credentials: &RetrievedCredentials) -> VcxResult<HashSet<String>> {
+    let mut revoked_indices: HashMap<(String, Option<u64>), HashSet<u64>> = HashMap::new();
+    let mut revoked = HashSet::new();
+    for candidate in retrieved_credentials.candidates().flatten() {
+        let info = &candidate.cred_info;
+        if let (Some(rev_reg_id), Some(cred_rev_id)) = (&info.rev_reg_id, &info.cred_rev_id) {
+            let to = candidate.interval.as_ref().and_then(|interval| interval.to);
+            let key = (rev_reg_id.to_string(), to);
+            if !revoked_indices.contains_key(&key) {
+                let (_, rev_reg_delta_json, _) = get_rev_reg_delta_json(rev_reg_id, None, to).await?;
+                revoked_indices.insert(key.clone(), _parse_revoked_indices(&rev_reg_delta_json)?);
+            }
+            let is_revoked = cred_rev_id
+                .parse::<u64>()
+                .map_or(false, |cred_rev_id| revoked_indices[&key].contains(&cred_rev_id));
+            if is_revoked {
+                revoked.insert(info.referent.to_string());
+            }
+        }
+    }
+    Ok(revoked)
+}
+
+fn _parse_revoked_indices(rev_reg_delta_json: &str) -> VcxResult<HashSet<u64>> {
+    let rev_reg_delta: Value = serde_json::from_str(rev_reg_delta_json).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!("Cannot deserialize revocation registry delta: {}", err),
+        )
+    })?;
+    Ok(rev_reg_delta["value"]["revoked"]
+        .as_array()
+        .map(|revoked| revoked.iter().filter_map(Value::as_u64).collect())
+        .unwrap_or_default())
+}
+
+#[cfg(feature = "test_utils")]
+pub mod test_utils {
+    use serde_json::Value;
+
+    pub fn _retrieved_credential(referent: &str, issuer_did: &str, rev_reg_id: Option<&str>) -> Value {
+        json!({
+            "cred_info": {
+                "referent": referent,
+                "attrs": {"name": "alice", "age": "25"},
+                "schema_id": format!("{}:2:schema:1.0", issuer_did),
+                "cred_def_id": format!("{}:3:CL:12:tag1", issuer_did),
+                "rev_reg_id": rev_reg_id,
+                "cred_rev_id": rev_reg_id.map(|_| "1")
+            },
+            "interval": null
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use crate::libindy::proofs::prover::credential_selection::test_utils::_retrieved_credential;
+    use crate::utils::constants::CREDS_FROM_PROOF_REQ;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _selected_referent(selection: &CredentialSelection, attr_referent: &str) -> String {
+        selection.selected_credentials.attrs[attr_referent]
+            .credential
+            .cred_info
+            .referent
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn test_select_credentials_picks_first_candidate_by_default() {
+        let _setup = SetupMocks::init();
+
+        let selection = select_credentials(CREDS_FROM_PROOF_REQ, &SelectionStrategy::create())
+            .await
+            .unwrap();
+        assert!(selection.is_complete());
+        assert_eq!(
+            _selected_referent(&selection, "height_1"),
+            "92556f60-d290-4b58-9a43-05c25aac214e"
+        );
+        assert_eq!(
+            _selected_referent(&selection, "zip_2"),
+            "2dea21e2-1404-4f85-966f-d03f403aac71"
+        );
+
+        let selected: Value = serde_json::from_str(&selection.selected_credentials_json().unwrap()).unwrap();
+        let retrieved: Value = serde_json::from_str(CREDS_FROM_PROOF_REQ).unwrap();
+        assert_eq!(selected["attrs"]["zip_2"]["credential"], retrieved["attrs"]["zip_2"][0]);
+        assert!(selected["attrs"]["zip_2"].get("tails_file").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_select_credentials_reports_unsatisfied_referents() {
+        let _setup = SetupMocks::init();
+
+        let retrieved = json!({
+            "attrs": {
+                "name_1": [_retrieved_credential("cred_1", "issuer1", None)],
+                "degree_2": []
+            }
+        });
+        let selection = select_credentials(&retrieved.to_string(), &SelectionStrategy::create())
+            .await
+            .unwrap();
+        assert!(!selection.is_complete());
+        assert_eq!(selection.unsatisfied_referents, vec!["degree_2".to_string()]);
+        assert!(selection.ambiguous_referents.is_empty());
+        assert_eq!(_selected_referent(&selection, "name_1"), "cred_1");
+
+        let selection = select_credentials("{}", &SelectionStrategy::create()).await.unwrap();
+        assert!(selection.is_complete());
+        assert!(selection.selected_credentials.attrs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_select_credentials_by_strategy() {
+        let _setup = SetupMocks::init();
+
+        let retrieved = json!({
+            "attrs": {
+                "name_1": [
+                    _retrieved_credential("cred_1", "issuer1", None),
+                    _retrieved_credential("cred_2", "issuer2", None),
+                    _retrieved_credential("cred_3", "issuer2", Some("issuer2:4:issuer2:3:CL:12:tag1:CL_ACCUM:tag1")),
+                ],
+                "age_2": [
+                    _retrieved_credential("cred_1", "issuer1", None),
+                    _retrieved_credential("cred_3", "issuer2", Some("issuer2:4:issuer2:3:CL:12:tag1:CL_ACCUM:tag1")),
+                ]
+            }
+        })
+        .to_string();
+
+        let selection = select_credentials(&retrieved, &SelectionStrategy::create())
+            .await
+            .unwrap();
+        assert_eq!(_selected_referent(&selection, "name_1"), "cred_1");
+        assert!(!selection.is_unambiguous());
+        assert_eq!(
+            selection.ambiguous_referents,
+            vec!["age_2".to_string(), "name_1".to_string()]
+        );
+
+        let strategy = SelectionStrategy::create().set_preferred_issuers(vec!["issuer2".to_string()]);
+        let selection = select_credentials(&retrieved, &strategy).await.unwrap();
+        assert_eq!(_selected_referent(&selection, "name_1"), "cred_2");
+        assert_eq!(_selected_referent(&selection, "age_2"), "cred_3");
+
+        let strategy = SelectionStrategy::create()
+            .set_preferred_issuers(vec!["issuer2".to_string()])
+            .set_prefer_same_credential(true)
+            .set_tails_dir("/tmp/tails");
+        let selection = select_credentials(&retrieved, &strategy).await.unwrap();
+        assert_eq!(_selected_referent(&selection, "name_1"), "cred_3");
+        assert_eq!(
+            selection.selected_credentials.attrs["name_1"].tails_file,
+            Some("/tmp/tails".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_credentials_for_predicates() {
+        let _setup = SetupMocks::init();
+
+        let retrieved = json!({
+            "attrs": {
+                "name_1": [_retrieved_credential("cred_2", "issuer2", None)]
+            },
+            "predicates": {
+                "age_2": [
+                    _retrieved_credential("cred_1", "issuer1", None),
+                    _retrieved_credential("cred_2", "issuer2", None),
+                ],
+                "height_3": []
+            }
+        })
+        .to_string();
+
+        let strategy = SelectionStrategy::create().set_prefer_same_credential(true);
+        let selection = select_credentials(&retrieved, &strategy).await.unwrap();
+        assert_eq!(
+            selection.selected_credentials.predicates["age_2"]
+                .credential
+                .cred_info
+                .referent,
+            "cred_2"
+        );
+        assert_eq!(selection.ambiguous_referents, vec!["age_2".to_string()]);
+        assert_eq!(selection.unsatisfied_referents, vec!["height_3".to_string()]);
+
+        let selected: Value = serde_json::from_str(&selection.selected_credentials_json().unwrap()).unwrap();
+        assert_eq!(
+            selected["predicates"]["age_2"]["credential"]["cred_info"]["referent"],
+            json!("cred_2")
+        );
+    }
+
+    #[test]
+    fn test_parse_revoked_indices() {
+        let revoked = _parse_revoked_indices(r#"{"ver":"1.0","value":{"accum":"1","revoked":[1,3]}}"#).unwrap();
+        assert!(revoked.contains(&1));
+        assert!(!revoked.contains(&2));
+        assert!(_parse_revoked_indices(r#"{"ver":"1.0","value":{}}"#)
+            .unwrap()
+            .is_empty());
+    }
+}
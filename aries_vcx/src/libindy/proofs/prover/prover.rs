@@ -15,11 +15,13 @@ pub async fn generate_indy_proof(
     credentials: &str,
     self_attested_attrs: &str,
     proof_req_data_json: &str,
+    unrevealed_attrs: &[String],
 ) -> VcxResult<String> {
     trace!(
-        "generate_indy_proof >>> credentials: {}, self_attested_attrs: {}",
+        "generate_indy_proof >>> credentials: {}, self_attested_attrs: {}, unrevealed_attrs: {:?}",
         secret!(&credentials),
-        secret!(&self_attested_attrs)
+        secret!(&self_attested_attrs),
+        unrevealed_attrs
     );
 
     match get_mock_generate_indy_proof() {
@@ -40,8 +42,12 @@ pub async fn generate_indy_proof(
     let mut credentials_identifiers = credential_def_identifiers(credentials, &proof_request)?;
 
     let revoc_states_json = build_rev_states_json(&mut credentials_identifiers).await?;
-    let requested_credentials =
-        build_requested_credentials_json(&credentials_identifiers, self_attested_attrs, &proof_request)?;
+    let requested_credentials = build_requested_credentials_json(
+        &credentials_identifiers,
+        self_attested_attrs,
+        &proof_request,
+        unrevealed_attrs,
+    )?;
 
     let schemas_json = build_schemas_json_prover(wallet_handle, &credentials_identifiers).await?;
     let credential_defs_json = build_cred_defs_json_prover(wallet_handle, &credentials_identifiers).await?;
@@ -100,40 +100,43 @@ pub fn credential_def_identifiers(credentials: &str, proof_req: &ProofRequestDat
         )
     })?;
 
-    if let Value::Object(ref attrs) = credentials["attrs"] {
-        for (requested_attr, value) in attrs {
-            if let (Some(referent), Some(schema_id), Some(cred_def_id)) = (
-                value["credential"]["cred_info"]["referent"].as_str(),
-                value["credential"]["cred_info"]["schema_id"].as_str(),
-                value["credential"]["cred_info"]["cred_def_id"].as_str(),
-            ) {
-                let rev_reg_id = value["credential"]["cred_info"]["rev_reg_id"]
-                    .as_str()
-                    .map(|x| x.to_string());
-
-                let cred_rev_id = value["credential"]["cred_info"]["cred_rev_id"]
-                    .as_str()
-                    .map(|x| x.to_string());
-
-                let tails_file = value["tails_file"].as_str().map(|x| x.to_string());
-
-                rtn.push(CredInfoProver {
-                    requested_attr: requested_attr.to_string(),
-                    referent: referent.to_string(),
-                    schema_id: schema_id.to_string(),
-                    cred_def_id: cred_def_id.to_string(),
-                    revocation_interval: _get_revocation_interval(requested_attr, proof_req)?,
-                    timestamp: None,
-                    rev_reg_id,
-                    cred_rev_id,
-                    tails_file,
-                });
-            } else {
-                return Err(VcxError::from_msg(
-                    VcxErrorKind::InvalidProofCredentialData,
-                    "Cannot get identifiers",
-                ));
-            }
+    let referents = credentials["attrs"]
+        .as_object()
+        .into_iter()
+        .chain(credentials["predicates"].as_object())
+        .flatten();
+    for (requested_attr, value) in referents {
+        if let (Some(referent), Some(schema_id), Some(cred_def_id)) = (
+            value["credential"]["cred_info"]["referent"].as_str(),
+            value["credential"]["cred_info"]["schema_id"].as_str(),
+            value["credential"]["cred_info"]["cred_def_id"].as_str(),
+        ) {
+            let rev_reg_id = value["credential"]["cred_info"]["rev_reg_id"]
+                .as_str()
+                .map(|x| x.to_string());
+
+            let cred_rev_id = value["credential"]["cred_info"]["cred_rev_id"]
+                .as_str()
+                .map(|x| x.to_string());
+
+            let tails_file = value["tails_file"].as_str().map(|x| x.to_string());
+
+            rtn.push(CredInfoProver {
+                requested_attr: requested_attr.to_string(),
+                referent: referent.to_string(),
+                schema_id: schema_id.to_string(),
+                cred_def_id: cred_def_id.to_string(),
+                revocation_interval: _get_revocation_interval(requested_attr, proof_req)?,
+                timestamp: None,
+                rev_reg_id,
+                cred_rev_id,
+                tails_file,
+            });
+        } else {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidProofCredentialData,
+                "Cannot get identifiers",
+            ));
         }
     }
 
@@ -213,16 +216,19 @@ pub async fn build_rev_states_json(credentials_identifiers: &mut Vec<CredInfoPro
     Ok(rtn.to_string())
 }
 
+/// Attributes of `unrevealed_attrs` referents are proven without disclosing their values.
 pub fn build_requested_credentials_json(
     credentials_identifiers: &Vec<CredInfoProver>,
     self_attested_attrs: &str,
     proof_req: &ProofRequestData,
+    unrevealed_attrs: &[String],
 ) -> VcxResult<String> {
     trace!(
-        "build_requested_credentials_json >> credentials_identifiers: {:?}, self_attested_attrs: {:?}, proof_req: {:?}",
+        "build_requested_credentials_json >> credentials_identifiers: {:?}, self_attested_attrs: {:?}, proof_req: {:?}, unrevealed_attrs: {:?}",
         credentials_identifiers,
         self_attested_attrs,
-        proof_req
+        proof_req,
+        unrevealed_attrs
     );
     let mut rtn: Value = json!({
           "self_attested_attributes":{},
@@ -233,8 +239,9 @@ pub fn build_requested_credentials_json(
     if let Value::Object(ref mut map) = rtn["requested_attributes"] {
         for cred_info in credentials_identifiers {
             if let Some(_) = proof_req.requested_attributes.get(&cred_info.requested_attr) {
+                let revealed = !unrevealed_attrs.contains(&cred_info.requested_attr);
                 let insert_val =
-                    json!({"cred_id": cred_info.referent, "revealed": true, "timestamp": cred_info.timestamp});
+                    json!({"cred_id": cred_info.referent, "revealed": revealed, "timestamp": cred_info.timestamp});
                 map.insert(cred_info.requested_attr.to_owned(), insert_val);
             }
         }
@@ -542,6 +549,36 @@ pub mod unit_tests {
         assert_eq!(creds, vec![cred1, cred2]);
     }
 
+    #[test]
+    fn test_credential_def_identifiers_for_predicates() {
+        let _setup = SetupDefaults::init();
+
+        let selected_credentials: Value = json!({
+           "attrs": {},
+           "predicates": {
+              "height_3": {
+                "credential": {
+                    "cred_info": {
+                       "referent": LICENCE_CRED_ID,
+                       "attrs": {"height": "4'11"},
+                       "schema_id": SCHEMA_ID,
+                       "cred_def_id": CRED_DEF_ID,
+                       "rev_reg_id": null,
+                       "cred_rev_id": null
+                    },
+                    "interval": null
+                }
+              }
+           }
+        });
+
+        let creds = credential_def_identifiers(&selected_credentials.to_string(), &proof_req_no_interval()).unwrap();
+        assert_eq!(creds.len(), 1);
+        assert_eq!(creds[0].requested_attr, "height_3");
+        assert_eq!(creds[0].referent, LICENCE_CRED_ID);
+        assert_eq!(creds[0].rev_reg_id, None);
+    }
+
     #[test]
     fn test_credential_def_identifiers_failure() {
         let _setup = SetupDefaults::init();
@@ -723,10 +760,62 @@ pub mod unit_tests {
             "non_revoked": {"from": 098, "to": 123}
         });
         let proof_req: ProofRequestData = serde_json::from_value(proof_req).unwrap();
-        let requested_credential = build_requested_credentials_json(&creds, &self_attested_attrs, &proof_req).unwrap();
+        let requested_credential =
+            build_requested_credentials_json(&creds, &self_attested_attrs, &proof_req, &[]).unwrap();
         assert_eq!(test.to_string(), requested_credential);
     }
 
+    #[test]
+    fn test_build_requested_credentials_with_unrevealed_attrs() {
+        let _setup = SetupMocks::init();
+
+        let cred1 = CredInfoProver {
+            requested_attr: "height_1".to_string(),
+            referent: LICENCE_CRED_ID.to_string(),
+            schema_id: SCHEMA_ID.to_string(),
+            cred_def_id: CRED_DEF_ID.to_string(),
+            rev_reg_id: None,
+            cred_rev_id: None,
+            revocation_interval: None,
+            tails_file: None,
+            timestamp: None,
+        };
+        let cred2 = CredInfoProver {
+            requested_attr: "zip_2".to_string(),
+            referent: ADDRESS_CRED_ID.to_string(),
+            schema_id: ADDRESS_SCHEMA_ID.to_string(),
+            cred_def_id: ADDRESS_CRED_DEF_ID.to_string(),
+            rev_reg_id: None,
+            cred_rev_id: None,
+            revocation_interval: None,
+            tails_file: None,
+            timestamp: None,
+        };
+        let proof_req: ProofRequestData = serde_json::from_value(json!({
+            "nonce": "123432421212",
+            "name": "proof_req_1",
+            "version": "0.1",
+            "requested_attributes": {
+                "height_1": { "name": "height_1" },
+                "zip_2": { "name": "zip_2" }
+            },
+            "requested_predicates": {}
+        }))
+        .unwrap();
+
+        let requested_credentials =
+            build_requested_credentials_json(&vec![cred1, cred2], "{}", &proof_req, &["zip_2".to_string()]).unwrap();
+        let requested_credentials: Value = serde_json::from_str(&requested_credentials).unwrap();
+        assert_eq!(
+            requested_credentials["requested_attributes"]["height_1"]["revealed"],
+            json!(true)
+        );
+        assert_eq!(
+            requested_credentials["requested_attributes"]["zip_2"]["revealed"],
+            json!(false)
+        );
+    }
+
     #[tokio::test]
     async fn test_build_rev_states_json() {
         let _setup = SetupMocks::init();
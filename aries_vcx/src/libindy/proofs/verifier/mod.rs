@@ -1,2 +1,3 @@
+pub mod requested_proof;
 pub mod verifier;
 mod verifier_internal;
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::prelude::*;
+
+/// Requested proof part of a libindy proof, telling how the prover answered each referent of the
+/// presentation request. Attributes the prover chose not to reveal are proven to be held without
+/// disclosing their values.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct RequestedProof {
+    #[serde(rename = "revealed_attrs", default)]
+    pub revealed_attributes: HashMap<String, RevealedAttribute>,
+    #[serde(rename = "revealed_attr_groups", default)]
+    pub revealed_attribute_groups: HashMap<String, RevealedAttributeGroup>,
+    #[serde(rename = "unrevealed_attrs", default)]
+    pub unrevealed_attributes: HashMap<String, SubProofReferent>,
+    #[serde(rename = "self_attested_attrs", default)]
+    pub self_attested_attributes: HashMap<String, String>,
+    #[serde(default)]
+    pub predicates: HashMap<String, SubProofReferent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RevealedAttribute {
+    pub sub_proof_index: u32,
+    pub raw: String,
+    pub encoded: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RevealedAttributeGroup {
+    pub sub_proof_index: u32,
+    pub values: HashMap<String, AttributeValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttributeValue {
+    pub raw: String,
+    pub encoded: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubProofReferent {
+    pub sub_proof_index: u32,
+}
+
+impl RequestedProof {
+    pub fn from_proof(proof_json: &str) -> VcxResult<Self> {
+        let proof: Value = serde_json::from_str(proof_json).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Cannot deserialize libindy proof: {}", err),
+            )
+        })?;
+        serde_json::from_value(proof["requested_proof"].clone()).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidProof,
+                format!("Cannot deserialize requested proof: {}", err),
+            )
+        })
+    }
+
+    pub fn is_revealed(&self, referent: &str) -> bool {
+        self.revealed_attributes.contains_key(referent) || self.revealed_attribute_groups.contains_key(referent)
+    }
+
+    pub fn is_unrevealed(&self, referent: &str) -> bool {
+        self.unrevealed_attributes.contains_key(referent)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_requested_proof_distinguishes_unrevealed_attributes() {
+        let proof = json!({
+            "proof": {},
+            "requested_proof": {
+                "revealed_attrs": {
+                    "name_1": {"sub_proof_index": 0, "raw": "Alice", "encoded": "123"}
+                },
+                "unrevealed_attrs": {
+                    "age_2": {"sub_proof_index": 0}
+                },
+                "self_attested_attrs": {},
+                "predicates": {}
+            },
+            "identifiers": []
+        });
+
+        let requested_proof = RequestedProof::from_proof(&proof.to_string()).unwrap();
+        assert!(requested_proof.is_revealed("name_1"));
+        assert!(!requested_proof.is_unrevealed("name_1"));
+        assert!(requested_proof.is_unrevealed("age_2"));
+        assert!(!requested_proof.is_revealed("age_2"));
+        assert_eq!(requested_proof.revealed_attributes["name_1"].raw, "Alice");
+        assert!(requested_proof.revealed_attribute_groups.is_empty());
+    }
+
+    #[test]
+    fn test_requested_proof_fails_for_invalid_proof() {
+        assert_eq!(
+            RequestedProof::from_proof("{}").unwrap_err().kind(),
+            VcxErrorKind::InvalidProof
+        );
+        assert_eq!(
+            RequestedProof::from_proof("invalid").unwrap_err().kind(),
+            VcxErrorKind::InvalidJson
+        );
+    }
+}
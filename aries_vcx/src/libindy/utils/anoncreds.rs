@@ -17,9 +17,11 @@ use crate::utils::constants::{
     REV_REG_JSON, SCHEMA_ID, SCHEMA_JSON, SCHEMA_TXN,
 };
 use crate::utils::constants::{
-    ATTRS, LIBINDY_CRED_OFFER, PROOF_REQUESTED_PREDICATES, REQUESTED_ATTRIBUTES, REV_STATE_JSON,
+    ATTRS, LIBINDY_CRED_OFFER, PROOF_REQUESTED_PREDICATES, PROVER_CREDENTIAL_INFO, REQUESTED_ATTRIBUTES, REV_STATE_JSON,
+};
+use crate::utils::mockdata::mock_settings::{
+    get_mock_creds_found_by_search, get_mock_creds_retrieved_for_proof_request, take_mock_creds_found_by_search,
 };
-use crate::utils::mockdata::mock_settings::get_mock_creds_retrieved_for_proof_request;
 
 const BLOB_STORAGE_TYPE: &str = "default";
 const REVOCATION_REGISTRY_TYPE: &str = "ISSUANCE_BY_DEFAULT";
@@ -389,6 +391,63 @@ pub async fn libindy_prover_delete_credential(wallet_handle: WalletHandle, cred_
         .map_err(VcxError::from)
 }
 
+pub async fn libindy_prover_get_credential(wallet_handle: WalletHandle, cred_id: &str) -> VcxResult<String> {
+    trace!("libindy_prover_get_credential >>> cred_id: {}", cred_id);
+    if settings::indy_mocks_enabled() {
+        return Ok(PROVER_CREDENTIAL_INFO.to_string());
+    }
+
+    anoncreds::prover_get_credential(wallet_handle, cred_id)
+        .await
+        .map_err(VcxError::from)
+}
+
+/// Opens search for credentials matching the WQL query, returns search handle and total count of matches.
+pub async fn libindy_prover_search_credentials(
+    wallet_handle: WalletHandle,
+    query_json: &str,
+) -> VcxResult<(i32, usize)> {
+    trace!("libindy_prover_search_credentials >>> query_json: {}", query_json);
+    if settings::indy_mocks_enabled() {
+        let total_count = get_mock_creds_found_by_search().map_or(0, |found_creds| found_creds.len());
+        return Ok((1, total_count));
+    }
+
+    anoncreds::prover_search_credentials(wallet_handle, Some(query_json))
+        .await
+        .map_err(VcxError::from)
+}
+
+pub async fn libindy_prover_fetch_credentials(search_handle: i32, count: usize) -> VcxResult<String> {
+    trace!(
+        "libindy_prover_fetch_credentials >>> search_handle: {}, count: {}",
+        search_handle,
+        count
+    );
+    if settings::indy_mocks_enabled() {
+        let batch = take_mock_creds_found_by_search(count).unwrap_or_default();
+        return Ok(json!(batch).to_string());
+    }
+
+    anoncreds::prover_fetch_credentials(search_handle, count)
+        .await
+        .map_err(VcxError::from)
+}
+
+pub async fn libindy_prover_close_credentials_search(search_handle: i32) -> VcxResult<()> {
+    trace!(
+        "libindy_prover_close_credentials_search >>> search_handle: {}",
+        search_handle
+    );
+    if settings::indy_mocks_enabled() {
+        return Ok(());
+    }
+
+    anoncreds::prover_close_credentials_search(search_handle)
+        .await
+        .map_err(VcxError::from)
+}
+
 pub async fn libindy_prover_create_master_secret(
     wallet_handle: WalletHandle,
     master_secret_id: &str,
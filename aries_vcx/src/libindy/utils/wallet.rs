@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use indy::{wallet, ErrorCode};
 use indy::{SearchHandle, WalletHandle};
 
@@ -401,6 +403,51 @@ pub async fn close_search_wallet(search_handle: SearchHandle) -> VcxResult<()> {
         .map_err(VcxError::from)
 }
 
+const SEARCH_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct WalletSearchRecord {
+    pub id: String,
+    pub value: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalletSearchBatch {
+    records: Option<Vec<WalletSearchRecord>>,
+}
+
+/// Fetches all records of the type matching the WQL query, with values and tags.
+pub async fn search_wallet_records(
+    wallet_handle: WalletHandle,
+    xtype: &str,
+    query: &str,
+) -> VcxResult<Vec<WalletSearchRecord>> {
+    trace!("search_wallet_records >>> xtype: {}, query: {}", secret!(&xtype), query);
+    let options = json!({"retrieveRecords": true, "retrieveValue": true, "retrieveTags": true}).to_string();
+    let search_handle = open_search_wallet(wallet_handle, xtype, query, &options).await?;
+    let mut records = Vec::new();
+    let result = loop {
+        let batch = fetch_next_records_wallet(wallet_handle, search_handle, SEARCH_BATCH_SIZE)
+            .await
+            .and_then(|batch| {
+                serde_json::from_str::<WalletSearchBatch>(&batch).map_err(|err| {
+                    VcxError::from_msg(
+                        VcxErrorKind::InvalidJson,
+                        format!("Cannot parse wallet search result: {}", err),
+                    )
+                })
+            });
+        match batch {
+            Ok(WalletSearchBatch { records: Some(batch) }) if !batch.is_empty() => records.extend(batch),
+            Ok(_) => break Ok(records),
+            Err(err) => break Err(err),
+        }
+    };
+    close_search_wallet(search_handle).await?;
+    result
+}
+
 pub async fn wallet_configure_issuer(wallet_handle: WalletHandle, enterprise_seed: &str) -> VcxResult<IssuerConfig> {
     let (institution_did, _institution_verkey) =
         signus::create_and_store_my_did(wallet_handle, Some(enterprise_seed), None).await?;
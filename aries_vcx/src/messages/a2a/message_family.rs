@@ -11,8 +11,12 @@ pub enum MessageFamilies {
     PresentProof,
     TrustPing,
     DiscoveryFeatures,
+    DiscoveryFeaturesV2,
     Basicmessage,
     OutOfBand,
+    CoordinateMediation,
+    Pickup,
+    DidRotate,
     Unknown(String),
 }
 
@@ -31,8 +35,12 @@ impl MessageFamilies {
             MessageFamilies::PresentProof => "1.0",
             MessageFamilies::TrustPing => "1.0",
             MessageFamilies::DiscoveryFeatures => "1.0",
+            MessageFamilies::DiscoveryFeaturesV2 => "2.0",
             MessageFamilies::Basicmessage => "1.0",
             MessageFamilies::OutOfBand => "1.1",
+            MessageFamilies::CoordinateMediation => "1.0",
+            MessageFamilies::Pickup => "2.0",
+            MessageFamilies::DidRotate => "1.0",
             MessageFamilies::Unknown(_) => "1.0",
         }
     }
@@ -52,11 +60,24 @@ impl MessageFamilies {
             MessageFamilies::PresentProof => Some((Actors::Prover, Actors::Verifier)),
             MessageFamilies::TrustPing => Some((Actors::Sender, Actors::Receiver)),
             MessageFamilies::DiscoveryFeatures => Some((Actors::Sender, Actors::Receiver)),
+            MessageFamilies::DiscoveryFeaturesV2 => Some((Actors::Sender, Actors::Receiver)),
             MessageFamilies::Basicmessage => Some((Actors::Sender, Actors::Receiver)),
             MessageFamilies::OutOfBand => Some((Actors::Sender, Actors::Receiver)),
+            MessageFamilies::CoordinateMediation => Some((Actors::Mediator, Actors::Recipient)),
+            MessageFamilies::Pickup => Some((Actors::Mediator, Actors::Recipient)),
+            MessageFamilies::DidRotate => Some((Actors::RotatingParty, Actors::ObservingParty)),
             MessageFamilies::Unknown(_) => None,
         }
     }
+
+    /// Resolves the family from the `@type` family name and version, so that families which share the
+    /// name across major versions are told apart.
+    pub fn from_family_and_version(family: &str, version: &str) -> Self {
+        match MessageFamilies::from(family.to_string()) {
+            MessageFamilies::DiscoveryFeatures if version.starts_with("2.") => MessageFamilies::DiscoveryFeaturesV2,
+            family => family,
+        }
+    }
 }
 
 impl From<String> for MessageFamilies {
@@ -73,6 +94,9 @@ impl From<String> for MessageFamilies {
             "discover-features" => MessageFamilies::DiscoveryFeatures,
             "basicmessage" => MessageFamilies::Basicmessage,
             "out-of-band" => MessageFamilies::OutOfBand,
+            "coordinate-mediation" => MessageFamilies::CoordinateMediation,
+            "messagepickup" => MessageFamilies::Pickup,
+            "did-rotate" => MessageFamilies::DidRotate,
             family => MessageFamilies::Unknown(family.to_string()),
         }
     }
@@ -90,8 +114,12 @@ impl ::std::string::ToString for MessageFamilies {
             MessageFamilies::PresentProof => "present-proof".to_string(),
             MessageFamilies::TrustPing => "trust_ping".to_string(),
             MessageFamilies::DiscoveryFeatures => "discover-features".to_string(),
+            MessageFamilies::DiscoveryFeaturesV2 => "discover-features".to_string(),
             MessageFamilies::Basicmessage => "basicmessage".to_string(),
             MessageFamilies::OutOfBand => "out-of-band".to_string(),
+            MessageFamilies::CoordinateMediation => "coordinate-mediation".to_string(),
+            MessageFamilies::Pickup => "messagepickup".to_string(),
+            MessageFamilies::DidRotate => "did-rotate".to_string(),
             MessageFamilies::Unknown(family) => family.to_string(),
         }
     }
@@ -102,3 +130,21 @@ impl Default for MessageFamilies {
         MessageFamilies::Unknown(String::new())
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_message_families_round_trip_through_name_and_version() {
+        for family in MessageFamilies::iter() {
+            assert_eq!(
+                MessageFamilies::from_family_and_version(&family.to_string(), family.version()),
+                family
+            );
+        }
+    }
+}
@@ -100,7 +100,7 @@ pub fn parse_message_type_legacy(message_type: &str) -> Option<MessageType> {
         match (did, family, version, msg_type) {
             (Some(did), Some(family), Some(version), Some(msg_type)) => Some(MessageType {
                 prefix: did.to_string(),
-                family: MessageFamilies::from(family.to_string()),
+                family: MessageFamilies::from_family_and_version(family, version),
                 version: version.to_string(),
                 msg_type: msg_type.to_string(),
             }),
@@ -133,7 +133,7 @@ pub fn parse_message_type(message_type: &str) -> Option<MessageType> {
         match (prefix, family, version, msg_type) {
             (Some(prefix), Some(family), Some(version), Some(msg_type)) => Some(MessageType {
                 prefix: prefix.to_string(),
-                family: MessageFamilies::from(family.to_string()),
+                family: MessageFamilies::from_family_and_version(family, version),
                 version: version.to_string(),
                 msg_type: msg_type.to_string(),
             }),
@@ -10,7 +10,18 @@ use crate::messages::connection::invite::{PairwiseInvitation, PublicInvitation};
 use crate::messages::connection::problem_report::ProblemReport as ConnectionProblemReport;
 use crate::messages::connection::request::Request;
 use crate::messages::connection::response::SignedResponse;
+use crate::messages::coordinate_mediation::keylist::Keylist;
+use crate::messages::coordinate_mediation::keylist_query::KeylistQuery;
+use crate::messages::coordinate_mediation::keylist_update::KeylistUpdate;
+use crate::messages::coordinate_mediation::keylist_update_response::KeylistUpdateResponse;
+use crate::messages::coordinate_mediation::mediate_deny::MediateDeny;
+use crate::messages::coordinate_mediation::mediate_grant::MediateGrant;
+use crate::messages::coordinate_mediation::mediate_request::MediateRequest;
+use crate::messages::did_rotate::ack::RotateAck;
+use crate::messages::did_rotate::rotate::Rotate;
 use crate::messages::discovery::disclose::Disclose;
+use crate::messages::discovery::disclosures::FeatureDisclosures;
+use crate::messages::discovery::queries::FeatureQueries;
 use crate::messages::discovery::query::Query;
 use crate::messages::error::ProblemReport as CommonProblemReport;
 use crate::messages::forward::Forward;
@@ -20,6 +31,12 @@ use crate::messages::issuance::credential_proposal::CredentialProposal;
 use crate::messages::issuance::credential_request::CredentialRequest;
 use crate::messages::out_of_band::handshake_reuse::OutOfBandHandshakeReuse;
 use crate::messages::out_of_band::handshake_reuse_accepted::OutOfBandHandshakeReuseAccepted;
+use crate::messages::pickup::delivery::Delivery as PickupDelivery;
+use crate::messages::pickup::delivery_request::DeliveryRequest as PickupDeliveryRequest;
+use crate::messages::pickup::live_delivery_change::LiveDeliveryChange as PickupLiveDeliveryChange;
+use crate::messages::pickup::messages_received::MessagesReceived as PickupMessagesReceived;
+use crate::messages::pickup::status::Status as PickupStatus;
+use crate::messages::pickup::status_request::StatusRequest as PickupStatusRequest;
 use crate::messages::proof_presentation::presentation::Presentation;
 use crate::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
@@ -69,6 +86,8 @@ pub enum A2AMessage {
     /// discovery features
     Query(Query),
     Disclose(Disclose),
+    FeatureQueries(FeatureQueries),
+    FeatureDisclosures(FeatureDisclosures),
 
     /// basic message
     BasicMessage(BasicMessage),
@@ -78,6 +97,27 @@ pub enum A2AMessage {
     OutOfBandHandshakeReuse(OutOfBandHandshakeReuse),
     OutOfBandHandshakeReuseAccepted(OutOfBandHandshakeReuseAccepted),
 
+    /// coordinate mediation
+    MediateRequest(MediateRequest),
+    MediateGrant(MediateGrant),
+    MediateDeny(MediateDeny),
+    KeylistUpdate(KeylistUpdate),
+    KeylistUpdateResponse(KeylistUpdateResponse),
+    KeylistQuery(KeylistQuery),
+    Keylist(Keylist),
+
+    /// message pickup
+    PickupStatusRequest(PickupStatusRequest),
+    PickupStatus(PickupStatus),
+    PickupDeliveryRequest(PickupDeliveryRequest),
+    PickupDelivery(PickupDelivery),
+    PickupMessagesReceived(PickupMessagesReceived),
+    PickupLiveDeliveryChange(PickupLiveDeliveryChange),
+
+    /// did rotate
+    DidRotate(Rotate),
+    DidRotateAck(RotateAck),
+
     /// Any Raw Message
     Generic(Value),
 }
@@ -103,12 +143,29 @@ impl A2AMessage {
             Self::CredentialRequest(m) => m.from_thread(thread_id),
             Self::PresentationRequest(m) => m.from_thread(thread_id),
             Self::Disclose(m) => m.from_thread(thread_id),
+            Self::FeatureDisclosures(m) => m.from_thread(thread_id),
             Self::OutOfBandHandshakeReuse(m) => m.from_thread(thread_id),
             Self::OutOfBandHandshakeReuseAccepted(m) => m.from_thread(thread_id),
+            Self::MediateGrant(m) => m.from_thread(thread_id),
+            Self::MediateDeny(m) => m.from_thread(thread_id),
+            Self::KeylistUpdateResponse(m) => m.from_thread(thread_id),
+            Self::Keylist(m) => m.from_thread(thread_id),
+            Self::PickupStatus(m) => m.from_thread(thread_id),
+            Self::PickupDelivery(m) => m.from_thread(thread_id),
+            Self::MediateRequest(_) => false,
+            Self::KeylistUpdate(_) => false,
+            Self::KeylistQuery(_) => false,
+            Self::PickupStatusRequest(_) => false,
+            Self::PickupDeliveryRequest(_) => false,
+            Self::PickupMessagesReceived(_) => false,
+            Self::PickupLiveDeliveryChange(_) => false,
+            Self::DidRotateAck(m) => m.from_thread(thread_id),
+            Self::DidRotate(_) => false,
             Self::Forward(_) => false,
             Self::ConnectionInvitationPairwise(_) => false,
             Self::ConnectionInvitationPublic(_) => false,
             Self::Query(_) => false,
+            Self::FeatureQueries(_) => false,
             Self::OutOfBandInvitation(_) => false,
             Self::BasicMessage(m) => m.from_thread(thread_id),
             Self::Generic(m) => {
@@ -223,6 +280,14 @@ impl<'de> Deserialize<'de> for A2AMessage {
             (MessageFamilies::DiscoveryFeatures, A2AMessage::DISCLOSE) => Disclose::deserialize(value)
                 .map(A2AMessage::Disclose)
                 .map_err(de::Error::custom),
+            (MessageFamilies::DiscoveryFeaturesV2, A2AMessage::FEATURE_QUERIES) => FeatureQueries::deserialize(value)
+                .map(A2AMessage::FeatureQueries)
+                .map_err(de::Error::custom),
+            (MessageFamilies::DiscoveryFeaturesV2, A2AMessage::FEATURE_DISCLOSURES) => {
+                FeatureDisclosures::deserialize(value)
+                    .map(A2AMessage::FeatureDisclosures)
+                    .map_err(de::Error::custom)
+            }
             (MessageFamilies::Basicmessage, A2AMessage::BASIC_MESSAGE) => BasicMessage::deserialize(value)
                 .map(A2AMessage::BasicMessage)
                 .map_err(de::Error::custom),
@@ -239,6 +304,57 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(A2AMessage::OutOfBandHandshakeReuseAccepted)
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_REQUEST) => MediateRequest::deserialize(value)
+                .map(A2AMessage::MediateRequest)
+                .map_err(de::Error::custom),
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_GRANT) => MediateGrant::deserialize(value)
+                .map(A2AMessage::MediateGrant)
+                .map_err(de::Error::custom),
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_DENY) => MediateDeny::deserialize(value)
+                .map(A2AMessage::MediateDeny)
+                .map_err(de::Error::custom),
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE) => KeylistUpdate::deserialize(value)
+                .map(A2AMessage::KeylistUpdate)
+                .map_err(de::Error::custom),
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE_RESPONSE) => {
+                KeylistUpdateResponse::deserialize(value)
+                    .map(A2AMessage::KeylistUpdateResponse)
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_QUERY) => KeylistQuery::deserialize(value)
+                .map(A2AMessage::KeylistQuery)
+                .map_err(de::Error::custom),
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST) => Keylist::deserialize(value)
+                .map(A2AMessage::Keylist)
+                .map_err(de::Error::custom),
+            (MessageFamilies::Pickup, A2AMessage::PICKUP_STATUS_REQUEST) => PickupStatusRequest::deserialize(value)
+                .map(A2AMessage::PickupStatusRequest)
+                .map_err(de::Error::custom),
+            (MessageFamilies::Pickup, A2AMessage::PICKUP_STATUS) => PickupStatus::deserialize(value)
+                .map(A2AMessage::PickupStatus)
+                .map_err(de::Error::custom),
+            (MessageFamilies::Pickup, A2AMessage::PICKUP_DELIVERY_REQUEST) => PickupDeliveryRequest::deserialize(value)
+                .map(A2AMessage::PickupDeliveryRequest)
+                .map_err(de::Error::custom),
+            (MessageFamilies::Pickup, A2AMessage::PICKUP_DELIVERY) => PickupDelivery::deserialize(value)
+                .map(A2AMessage::PickupDelivery)
+                .map_err(de::Error::custom),
+            (MessageFamilies::Pickup, A2AMessage::PICKUP_MESSAGES_RECEIVED) => {
+                PickupMessagesReceived::deserialize(value)
+                    .map(A2AMessage::PickupMessagesReceived)
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::Pickup, A2AMessage::PICKUP_LIVE_DELIVERY_CHANGE) => {
+                PickupLiveDeliveryChange::deserialize(value)
+                    .map(A2AMessage::PickupLiveDeliveryChange)
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::DidRotate, A2AMessage::DID_ROTATE) => Rotate::deserialize(value)
+                .map(A2AMessage::DidRotate)
+                .map_err(de::Error::custom),
+            (MessageFamilies::DidRotate, A2AMessage::ACK) => RotateAck::deserialize(value)
+                .map(A2AMessage::DidRotateAck)
+                .map_err(de::Error::custom),
             (_, other_type) => {
                 warn!("Unexpected @type field structure: {}", other_type);
                 Ok(A2AMessage::Generic(value))
@@ -318,6 +434,14 @@ impl Serialize for A2AMessage {
             A2AMessage::Disclose(msg) => {
                 set_a2a_message_type(msg, MessageFamilies::DiscoveryFeatures, A2AMessage::DISCLOSE)
             }
+            A2AMessage::FeatureQueries(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::DiscoveryFeaturesV2, A2AMessage::FEATURE_QUERIES)
+            }
+            A2AMessage::FeatureDisclosures(msg) => set_a2a_message_type(
+                msg,
+                MessageFamilies::DiscoveryFeaturesV2,
+                A2AMessage::FEATURE_DISCLOSURES,
+            ),
             A2AMessage::BasicMessage(msg) => {
                 set_a2a_message_type(msg, MessageFamilies::Basicmessage, A2AMessage::BASIC_MESSAGE)
             }
@@ -332,6 +456,49 @@ impl Serialize for A2AMessage {
                 MessageFamilies::OutOfBand,
                 A2AMessage::OUT_OF_BAND_HANDSHAKE_REUSE_ACCEPTED,
             ),
+            A2AMessage::MediateRequest(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_REQUEST)
+            }
+            A2AMessage::MediateGrant(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_GRANT)
+            }
+            A2AMessage::MediateDeny(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_DENY)
+            }
+            A2AMessage::KeylistUpdate(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE)
+            }
+            A2AMessage::KeylistUpdateResponse(msg) => set_a2a_message_type(
+                msg,
+                MessageFamilies::CoordinateMediation,
+                A2AMessage::KEYLIST_UPDATE_RESPONSE,
+            ),
+            A2AMessage::KeylistQuery(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_QUERY)
+            }
+            A2AMessage::Keylist(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST)
+            }
+            A2AMessage::PickupStatusRequest(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::Pickup, A2AMessage::PICKUP_STATUS_REQUEST)
+            }
+            A2AMessage::PickupStatus(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::Pickup, A2AMessage::PICKUP_STATUS)
+            }
+            A2AMessage::PickupDeliveryRequest(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::Pickup, A2AMessage::PICKUP_DELIVERY_REQUEST)
+            }
+            A2AMessage::PickupDelivery(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::Pickup, A2AMessage::PICKUP_DELIVERY)
+            }
+            A2AMessage::PickupMessagesReceived(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::Pickup, A2AMessage::PICKUP_MESSAGES_RECEIVED)
+            }
+            A2AMessage::PickupLiveDeliveryChange(msg) => {
+                set_a2a_message_type(msg, MessageFamilies::Pickup, A2AMessage::PICKUP_LIVE_DELIVERY_CHANGE)
+            }
+            A2AMessage::DidRotate(msg) => set_a2a_message_type(msg, MessageFamilies::DidRotate, A2AMessage::DID_ROTATE),
+            A2AMessage::DidRotateAck(msg) => set_a2a_message_type(msg, MessageFamilies::DidRotate, A2AMessage::ACK),
             A2AMessage::Generic(msg) => Ok(msg.clone()),
         }
         .map_err(ser::Error::custom)?;
@@ -385,10 +552,26 @@ impl A2AMessage {
     const PRESENTATION: &'static str = "presentation";
     const QUERY: &'static str = "query";
     const DISCLOSE: &'static str = "disclose";
+    const FEATURE_QUERIES: &'static str = "queries";
+    const FEATURE_DISCLOSURES: &'static str = "disclosures";
     const BASIC_MESSAGE: &'static str = "message";
     const OUT_OF_BAND_INVITATION: &'static str = "invitation";
     const OUT_OF_BAND_HANDSHAKE_REUSE: &'static str = "handshake-reuse";
     const OUT_OF_BAND_HANDSHAKE_REUSE_ACCEPTED: &'static str = "handshake-reuse-accepted";
+    const MEDIATE_REQUEST: &'static str = "mediate-request";
+    const MEDIATE_GRANT: &'static str = "mediate-grant";
+    const MEDIATE_DENY: &'static str = "mediate-deny";
+    const KEYLIST_UPDATE: &'static str = "keylist-update";
+    const KEYLIST_UPDATE_RESPONSE: &'static str = "keylist-update-response";
+    const KEYLIST_QUERY: &'static str = "keylist-query";
+    const KEYLIST: &'static str = "keylist";
+    const PICKUP_STATUS_REQUEST: &'static str = "status-request";
+    const PICKUP_STATUS: &'static str = "status";
+    const PICKUP_DELIVERY_REQUEST: &'static str = "delivery-request";
+    const PICKUP_DELIVERY: &'static str = "delivery";
+    const PICKUP_MESSAGES_RECEIVED: &'static str = "messages-received";
+    const PICKUP_LIVE_DELIVERY_CHANGE: &'static str = "live-delivery-change";
+    const DID_ROTATE: &'static str = "rotate";
 }
 
 #[cfg(test)]
@@ -399,6 +582,7 @@ pub mod test_a2a_serialization {
     use crate::messages::a2a::{A2AMessage, MessageId};
     use crate::messages::ack::{Ack, AckStatus};
     use crate::messages::connection::request::Request;
+    use crate::messages::discovery::queries::{FeatureQueries, FeatureQuery, FeatureType};
     use crate::messages::forward::Forward;
     use crate::utils::devsetup::SetupDefaults;
 
@@ -438,6 +622,24 @@ pub mod test_a2a_serialization {
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_feature_queries() {
+        let _setup = SetupDefaults::init();
+        let a2a_msg = A2AMessage::FeatureQueries(
+            FeatureQueries::create().set_queries(vec![FeatureQuery::new(FeatureType::GoalCode, "aries.*")]),
+        );
+        let serialized = serde_json::to_string(&a2a_msg).unwrap();
+
+        let val: Value = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(
+            val["@type"],
+            Value::String("https://didcomm.org/discover-features/2.0/queries".into())
+        );
+
+        let a2a_msg: A2AMessage = serde_json::from_str(&serialized).unwrap();
+        assert_match!(A2AMessage::FeatureQueries(_), a2a_msg);
+    }
+
     #[test]
     fn test_serialize_deserialize_connection_ack() {
         let _setup = SetupDefaults::init();
@@ -3,16 +3,36 @@ use strum::IntoEnumIterator;
 
 use crate::actors;
 use crate::actors::Actors;
+use crate::global::settings;
 use crate::messages::a2a::message_family::MessageFamilies;
 use crate::messages::discovery::disclose::ProtocolDescriptor;
+use crate::messages::discovery::disclosures::Disclosure;
+use crate::messages::discovery::queries::{FeatureQuery, FeatureType};
+
+const GOAL_CODES: [&str; 3] = ["aries.vc.issue", "aries.vc.verify", "aries.rel.build"];
 
 pub struct ProtocolRegistry {
     protocols: Vec<ProtocolDescriptor>,
+    goal_codes: Vec<String>,
+    attachment_formats: Vec<String>,
 }
 
 impl ProtocolRegistry {
+    /// Goal codes and attachment formats set in `CONFIG_GOAL_CODES` and `CONFIG_ATTACHMENT_FORMATS` are
+    /// disclosed as well. No attachment format is disclosed by default, as issue-credential and
+    /// present-proof 1.0 do not identify formats of their attachments.
     pub fn init() -> ProtocolRegistry {
-        let mut registry = ProtocolRegistry { protocols: Vec::new() };
+        let mut goal_codes: Vec<String> = GOAL_CODES.iter().map(|code| code.to_string()).collect();
+        for goal_code in _configured_features(settings::CONFIG_GOAL_CODES) {
+            if !goal_codes.contains(&goal_code) {
+                goal_codes.push(goal_code);
+            }
+        }
+        let mut registry = ProtocolRegistry {
+            protocols: Vec::new(),
+            goal_codes,
+            attachment_formats: _configured_features(settings::CONFIG_ATTACHMENT_FORMATS),
+        };
         let actors = actors::get_actors();
 
         for family in MessageFamilies::iter() {
@@ -26,7 +46,11 @@ impl ProtocolRegistry {
                 | family @ MessageFamilies::TrustPing
                 | family @ MessageFamilies::Basicmessage
                 | family @ MessageFamilies::DiscoveryFeatures
-                | family @ MessageFamilies::OutOfBand => registry.add_protocol(&actors, family),
+                | family @ MessageFamilies::DiscoveryFeaturesV2
+                | family @ MessageFamilies::OutOfBand
+                | family @ MessageFamilies::CoordinateMediation
+                | family @ MessageFamilies::Pickup
+                | family @ MessageFamilies::DidRotate => registry.add_protocol(&actors, family),
                 MessageFamilies::Signature => {}
                 MessageFamilies::Unknown(_) => {}
             }
@@ -78,12 +102,69 @@ impl ProtocolRegistry {
     pub fn protocols(&self) -> Vec<ProtocolDescriptor> {
         self.protocols.clone()
     }
+
+    pub fn goal_codes(&self) -> Vec<String> {
+        self.goal_codes.clone()
+    }
+
+    pub fn attachment_formats(&self) -> Vec<String> {
+        self.attachment_formats.clone()
+    }
+
+    /// Resolves discover-features 2.0 queries. The `match` of a query may contain `*` wildcards,
+    /// every other character is matched literally.
+    pub fn get_features_for_queries(&self, queries: &[FeatureQuery]) -> Vec<Disclosure> {
+        let mut disclosures: Vec<Disclosure> = Vec::new();
+        for query in queries {
+            let pattern = format!("^{}$", regex::escape(&query.match_).replace("\\*", ".*"));
+            let re = match Regex::new(&pattern) {
+                Ok(re) => re,
+                Err(_) => continue,
+            };
+            let matching: Vec<Disclosure> = match query.feature_type {
+                FeatureType::Protocol => self
+                    .protocols
+                    .iter()
+                    .filter(|protocol| re.is_match(&protocol.pid))
+                    .cloned()
+                    .map(Disclosure::from)
+                    .collect(),
+                FeatureType::GoalCode => Self::_matching_features(&re, &self.goal_codes, FeatureType::GoalCode),
+                FeatureType::AttachmentFormat => {
+                    Self::_matching_features(&re, &self.attachment_formats, FeatureType::AttachmentFormat)
+                }
+            };
+            for disclosure in matching {
+                if !disclosures.contains(&disclosure) {
+                    disclosures.push(disclosure);
+                }
+            }
+        }
+        disclosures
+    }
+
+    fn _matching_features(re: &Regex, features: &[String], feature_type: FeatureType) -> Vec<Disclosure> {
+        features
+            .iter()
+            .filter(|feature| re.is_match(feature))
+            .map(|feature| Disclosure::new(feature_type, feature))
+            .collect()
+    }
+}
+
+fn _configured_features(config_name: &str) -> Vec<String> {
+    match settings::get_config_value(config_name) {
+        Ok(features) => serde_json::from_str(&features).unwrap_or_else(|err| {
+            warn!("Ignoring invalid {} setting {}, err: {:?}", config_name, features, err);
+            vec![]
+        }),
+        Err(_) => vec![],
+    }
 }
 
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 pub mod unit_tests {
-    use crate::global::settings;
     use crate::utils::devsetup::SetupEmpty;
 
     use super::*;
@@ -108,6 +189,8 @@ pub mod unit_tests {
     fn _protocol_registry() -> ProtocolRegistry {
         ProtocolRegistry {
             protocols: _protocols(),
+            goal_codes: vec!["aries.vc.issue".to_string(), "aries.rel.build".to_string()],
+            attachment_formats: vec!["hlindy/proof-req@v2.0".to_string()],
         }
     }
 
@@ -225,4 +308,72 @@ pub mod unit_tests {
         }];
         assert_eq!(expected_protocols, protocols);
     }
+
+    #[test]
+    fn test_get_features_for_queries_works() {
+        let _setup = SetupEmpty::init();
+
+        let registry: ProtocolRegistry = _protocol_registry();
+
+        let features = registry.get_features_for_queries(&[
+            FeatureQuery::new(FeatureType::Protocol, "protocol_1.0*"),
+            FeatureQuery::new(FeatureType::GoalCode, "aries.vc.*"),
+            FeatureQuery::new(FeatureType::AttachmentFormat, "*"),
+        ]);
+
+        let expected_features = vec![
+            Disclosure::new(FeatureType::Protocol, "protocol_1.0_test"),
+            Disclosure::new(FeatureType::Protocol, "protocol_1.0_some"),
+            Disclosure::new(FeatureType::GoalCode, "aries.vc.issue"),
+            Disclosure::new(FeatureType::AttachmentFormat, "hlindy/proof-req@v2.0"),
+        ];
+        assert_eq!(expected_features, features);
+    }
+
+    #[test]
+    fn test_protocol_registry_init_discloses_configured_features() {
+        let _setup = SetupEmpty::init();
+
+        let registry: ProtocolRegistry = ProtocolRegistry::init();
+        assert!(registry.attachment_formats().is_empty());
+
+        settings::set_config_value(
+            settings::CONFIG_GOAL_CODES,
+            &json!(["aries.vc.issue", "acme.order"]).to_string(),
+        )
+        .unwrap();
+        settings::set_config_value(
+            settings::CONFIG_ATTACHMENT_FORMATS,
+            &json!(["dif/pres-exch@v1.0"]).to_string(),
+        )
+        .unwrap();
+        let registry: ProtocolRegistry = ProtocolRegistry::init();
+
+        let features = registry.get_features_for_queries(&[
+            FeatureQuery::new(FeatureType::GoalCode, "acme.*"),
+            FeatureQuery::new(FeatureType::AttachmentFormat, "*"),
+        ]);
+        let expected_features = vec![
+            Disclosure::new(FeatureType::GoalCode, "acme.order"),
+            Disclosure::new(FeatureType::AttachmentFormat, "dif/pres-exch@v1.0"),
+        ];
+        assert_eq!(expected_features, features);
+        assert_eq!(
+            registry.goal_codes(),
+            vec!["aries.vc.issue", "aries.vc.verify", "aries.rel.build", "acme.order"]
+        );
+    }
+
+    #[test]
+    fn test_get_features_for_queries_matches_literally() {
+        let _setup = SetupEmpty::init();
+
+        let registry: ProtocolRegistry = _protocol_registry();
+
+        let features = registry.get_features_for_queries(&[FeatureQuery::new(FeatureType::GoalCode, "aries.vc")]);
+        assert!(features.is_empty());
+
+        let features = registry.get_features_for_queries(&[FeatureQuery::new(FeatureType::Protocol, "0.0.test")]);
+        assert!(features.is_empty());
+    }
 }
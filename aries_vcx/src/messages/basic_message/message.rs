@@ -45,6 +45,15 @@ impl BasicMessage {
         self
     }
 
+    pub fn set_localization(mut self, locale: &str) -> Self {
+        self.l10n = Some(Localization::new(locale));
+        self
+    }
+
+    pub fn get_locale(&self) -> Option<String> {
+        self.l10n.as_ref().map(|l10n| l10n.locale.clone())
+    }
+
     pub fn to_a2a_message(&self) -> A2AMessage {
         A2AMessage::BasicMessage(self.clone()) // TODO: THINK how to avoid clone
     }
@@ -67,4 +76,16 @@ pub mod unit_tests {
             .set_default_localization();
         assert_eq!(_content(), basic_message.content);
     }
+
+    #[test]
+    fn test_basic_message_localization() {
+        let basic_message: BasicMessage = BasicMessage::default().set_content(_content());
+        assert_eq!(None, basic_message.get_locale());
+        let basic_message = basic_message.set_localization("de");
+        assert_eq!(Some("de".to_string()), basic_message.get_locale());
+        assert_eq!(
+            Some("en".to_string()),
+            basic_message.set_default_localization().get_locale()
+        );
+    }
 }
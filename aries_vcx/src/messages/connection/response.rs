@@ -91,7 +91,37 @@ impl Response {
     }
 
     pub async fn encode(&self, wallet_handle: WalletHandle, key: &str) -> VcxResult<SignedResponse> {
-        let connection_data = json!(self.connection).to_string();
+        let connection_sig = ConnectionSignature::sign(wallet_handle, key, &self.connection).await?;
+
+        let signed_response = SignedResponse {
+            id: self.id.clone(),
+            thread: self.thread.clone(),
+            connection_sig,
+            please_ack: self.please_ack.clone(),
+            timing: self.timing.clone(),
+        };
+
+        Ok(signed_response)
+    }
+}
+
+impl SignedResponse {
+    pub async fn decode(self, their_vk: &str) -> VcxResult<Response> {
+        let connection = self.connection_sig.verify(their_vk).await?;
+
+        Ok(Response {
+            id: self.id,
+            thread: self.thread,
+            connection,
+            please_ack: self.please_ack,
+            timing: self.timing,
+        })
+    }
+}
+
+impl ConnectionSignature {
+    pub async fn sign(wallet_handle: WalletHandle, key: &str, connection: &ConnectionData) -> VcxResult<Self> {
+        let connection_data = json!(connection).to_string();
 
         let now: u64 = time::get_time().sec as u64;
 
@@ -105,69 +135,46 @@ impl Response {
 
         let signature = base64::encode_config(&signature, base64::URL_SAFE);
 
-        let connection_sig = ConnectionSignature {
+        Ok(ConnectionSignature {
             signature,
             sig_data,
             signer: key.to_string(),
             ..Default::default()
-        };
-
-        let signed_response = SignedResponse {
-            id: self.id.clone(),
-            thread: self.thread.clone(),
-            connection_sig,
-            please_ack: self.please_ack.clone(),
-            timing: self.timing.clone(),
-        };
-
-        Ok(signed_response)
+        })
     }
-}
 
-impl SignedResponse {
-    pub async fn decode(self, their_vk: &str) -> VcxResult<Response> {
-        let signature =
-            base64::decode_config(&self.connection_sig.signature.as_bytes(), base64::URL_SAFE).map_err(|err| {
-                VcxError::from_msg(
-                    VcxErrorKind::InvalidJson,
-                    format!("Cannot decode ConnectionResponse: {:?}", err),
-                )
-            })?;
-
-        let sig_data =
-            base64::decode_config(&self.connection_sig.sig_data.as_bytes(), base64::URL_SAFE).map_err(|err| {
-                VcxError::from_msg(
-                    VcxErrorKind::InvalidJson,
-                    format!("Cannot decode ConnectionResponse: {:?}", err),
-                )
-            })?;
+    pub async fn verify(&self, their_vk: &str) -> VcxResult<ConnectionData> {
+        let signature = base64::decode_config(&self.signature.as_bytes(), base64::URL_SAFE).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Cannot decode connection signature: {:?}", err),
+            )
+        })?;
+
+        let sig_data = base64::decode_config(&self.sig_data.as_bytes(), base64::URL_SAFE).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Cannot decode connection signature: {:?}", err),
+            )
+        })?;
 
         if !crypto::verify(their_vk, &sig_data, &signature).await? {
             return Err(VcxError::from_msg(
                 VcxErrorKind::InvalidJson,
-                "ConnectionResponse signature is invalid for original Invite recipient key",
+                "Connection signature is invalid for the expected signer key",
             ));
         }
 
-        if self.connection_sig.signer != their_vk {
+        if self.signer != their_vk {
             return Err(VcxError::from_msg(
                 VcxErrorKind::InvalidJson,
-                "Signer declared in ConnectionResponse signed response is not matching the actual signer. Connection ",
+                "Signer declared in connection signature is not matching the actual signer",
             ));
         }
 
         let sig_data = &sig_data[8..];
 
-        let connection: ConnectionData = serde_json::from_slice(sig_data)
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, err.to_string()))?;
-
-        Ok(Response {
-            id: self.id,
-            thread: self.thread,
-            connection,
-            please_ack: self.please_ack,
-            timing: self.timing,
-        })
+        serde_json::from_slice(sig_data).map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, err.to_string()))
     }
 }
 
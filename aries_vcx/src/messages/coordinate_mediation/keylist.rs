@@ -0,0 +1,52 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::thread::Thread;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct KeylistKey {
+    pub recipient_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct KeylistPagination {
+    pub count: u32,
+    pub offset: u32,
+    pub remaining: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct Keylist {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub keys: Vec<KeylistKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<KeylistPagination>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl Keylist {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_keys(mut self, keys: Vec<String>) -> Self {
+        self.keys = keys
+            .into_iter()
+            .map(|recipient_key| KeylistKey { recipient_key })
+            .collect();
+        self
+    }
+
+    pub fn get_keys(&self) -> Vec<String> {
+        self.keys.iter().map(|key| key.recipient_key.clone()).collect()
+    }
+}
+
+threadlike!(Keylist);
+a2a_message!(Keylist);
+timing_optional!(Keylist);
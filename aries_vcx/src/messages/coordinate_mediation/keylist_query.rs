@@ -0,0 +1,34 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct KeylistQueryPaginate {
+    pub limit: u32,
+    pub offset: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct KeylistQuery {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paginate: Option<KeylistQueryPaginate>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl KeylistQuery {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_paginate(mut self, limit: u32, offset: u32) -> Self {
+        self.paginate = Some(KeylistQueryPaginate { limit, offset });
+        self
+    }
+}
+
+a2a_message!(KeylistQuery);
+timing_optional!(KeylistQuery);
@@ -0,0 +1,52 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum KeylistUpdateAction {
+    #[serde(rename = "add")]
+    Add,
+    #[serde(rename = "remove")]
+    Remove,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct KeylistUpdateItem {
+    pub recipient_key: String,
+    pub action: KeylistUpdateAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct KeylistUpdate {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub updates: Vec<KeylistUpdateItem>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl KeylistUpdate {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn add_key(mut self, recipient_key: &str) -> Self {
+        self.updates.push(KeylistUpdateItem {
+            recipient_key: recipient_key.to_string(),
+            action: KeylistUpdateAction::Add,
+        });
+        self
+    }
+
+    pub fn remove_key(mut self, recipient_key: &str) -> Self {
+        self.updates.push(KeylistUpdateItem {
+            recipient_key: recipient_key.to_string(),
+            action: KeylistUpdateAction::Remove,
+        });
+        self
+    }
+}
+
+a2a_message!(KeylistUpdate);
+timing_optional!(KeylistUpdate);
@@ -0,0 +1,51 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::coordinate_mediation::keylist_update::KeylistUpdateAction;
+use crate::messages::thread::Thread;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum KeylistUpdateResult {
+    #[serde(rename = "client_error")]
+    ClientError,
+    #[serde(rename = "server_error")]
+    ServerError,
+    #[serde(rename = "no_change")]
+    NoChange,
+    #[serde(rename = "success")]
+    Success,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct KeylistUpdateResponseItem {
+    pub recipient_key: String,
+    pub action: KeylistUpdateAction,
+    pub result: KeylistUpdateResult,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct KeylistUpdateResponse {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub updated: Vec<KeylistUpdateResponseItem>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl KeylistUpdateResponse {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_updated(mut self, updated: Vec<KeylistUpdateResponseItem>) -> Self {
+        self.updated = updated;
+        self
+    }
+}
+
+threadlike!(KeylistUpdateResponse);
+a2a_message!(KeylistUpdateResponse);
+timing_optional!(KeylistUpdateResponse);
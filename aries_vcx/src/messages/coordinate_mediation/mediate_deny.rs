@@ -0,0 +1,25 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::thread::Thread;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct MediateDeny {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl MediateDeny {
+    pub fn create() -> Self {
+        Self::default()
+    }
+}
+
+threadlike!(MediateDeny);
+a2a_message!(MediateDeny);
+timing_optional!(MediateDeny);
@@ -0,0 +1,37 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::thread::Thread;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct MediateGrant {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub endpoint: String,
+    pub routing_keys: Vec<String>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl MediateGrant {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    pub fn set_routing_keys(mut self, routing_keys: Vec<String>) -> Self {
+        self.routing_keys = routing_keys;
+        self
+    }
+}
+
+threadlike!(MediateGrant);
+a2a_message!(MediateGrant);
+timing_optional!(MediateGrant);
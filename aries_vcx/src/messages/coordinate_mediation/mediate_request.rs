@@ -0,0 +1,21 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct MediateRequest {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl MediateRequest {
+    pub fn create() -> Self {
+        Self::default()
+    }
+}
+
+a2a_message!(MediateRequest);
+timing_optional!(MediateRequest);
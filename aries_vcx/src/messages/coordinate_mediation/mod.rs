@@ -0,0 +1,7 @@
+pub mod keylist;
+pub mod keylist_query;
+pub mod keylist_update;
+pub mod keylist_update_response;
+pub mod mediate_deny;
+pub mod mediate_grant;
+pub mod mediate_request;
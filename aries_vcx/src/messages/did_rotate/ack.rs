@@ -0,0 +1,25 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::thread::Thread;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct RotateAck {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl RotateAck {
+    pub fn create() -> Self {
+        Self::default()
+    }
+}
+
+a2a_message!(RotateAck, DidRotateAck);
+threadlike!(RotateAck);
+timing_optional!(RotateAck);
@@ -0,0 +1,2 @@
+pub mod ack;
+pub mod rotate;
@@ -0,0 +1,30 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::connection::response::ConnectionSignature;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Rotate {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub to_did: String,
+    #[serde(rename = "to_did_doc~sig")]
+    pub to_did_doc_sig: ConnectionSignature,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl Rotate {
+    pub fn create(to_did: &str, to_did_doc_sig: ConnectionSignature) -> Self {
+        Self {
+            id: MessageId::new(),
+            to_did: to_did.to_string(),
+            to_did_doc_sig,
+            timing: None,
+        }
+    }
+}
+
+a2a_message!(Rotate, DidRotate);
+timing_optional!(Rotate);
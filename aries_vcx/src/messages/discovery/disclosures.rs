@@ -0,0 +1,97 @@
+use crate::actors::Actors;
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::discovery::disclose::ProtocolDescriptor;
+use crate::messages::discovery::queries::FeatureType;
+use crate::messages::thread::Thread;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Disclosure {
+    #[serde(rename = "feature-type")]
+    pub feature_type: FeatureType,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<Actors>>,
+}
+
+impl Disclosure {
+    pub fn new(feature_type: FeatureType, id: &str) -> Self {
+        Self {
+            feature_type,
+            id: id.to_string(),
+            roles: None,
+        }
+    }
+}
+
+impl From<ProtocolDescriptor> for Disclosure {
+    fn from(protocol: ProtocolDescriptor) -> Self {
+        Self {
+            feature_type: FeatureType::Protocol,
+            id: protocol.pid,
+            roles: protocol.roles,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct FeatureDisclosures {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub disclosures: Vec<Disclosure>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl FeatureDisclosures {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_disclosures(mut self, disclosures: Vec<Disclosure>) -> Self {
+        self.disclosures = disclosures;
+        self
+    }
+
+    pub fn get_protocols(&self) -> Vec<ProtocolDescriptor> {
+        self.disclosures
+            .iter()
+            .filter(|disclosure| disclosure.feature_type == FeatureType::Protocol)
+            .map(|disclosure| ProtocolDescriptor {
+                pid: disclosure.id.clone(),
+                roles: disclosure.roles.clone(),
+            })
+            .collect()
+    }
+}
+
+a2a_message!(FeatureDisclosures);
+threadlike_optional!(FeatureDisclosures);
+timing_optional!(FeatureDisclosures);
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_disclosures_get_protocols() {
+        let disclosures = FeatureDisclosures::create().set_disclosures(vec![
+            Disclosure::new(FeatureType::Protocol, "https://didcomm.org/trust_ping/1.0"),
+            Disclosure::new(FeatureType::GoalCode, "aries.vc.issue"),
+        ]);
+        assert_eq!(
+            disclosures.get_protocols(),
+            vec![ProtocolDescriptor {
+                pid: "https://didcomm.org/trust_ping/1.0".to_string(),
+                roles: None,
+            }]
+        );
+        assert_eq!(json!(disclosures)["disclosures"][1]["feature-type"], "goal-code");
+    }
+}
@@ -1,2 +1,4 @@
 pub mod disclose;
+pub mod disclosures;
+pub mod queries;
 pub mod query;
@@ -0,0 +1,71 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeatureType {
+    Protocol,
+    GoalCode,
+    AttachmentFormat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeatureQuery {
+    #[serde(rename = "feature-type")]
+    pub feature_type: FeatureType,
+    #[serde(rename = "match")]
+    pub match_: String,
+}
+
+impl FeatureQuery {
+    pub fn new(feature_type: FeatureType, match_: &str) -> Self {
+        Self {
+            feature_type,
+            match_: match_.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct FeatureQueries {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub queries: Vec<FeatureQuery>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl FeatureQueries {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_queries(mut self, queries: Vec<FeatureQuery>) -> Self {
+        self.queries = queries;
+        self
+    }
+}
+
+a2a_message!(FeatureQueries);
+timing_optional!(FeatureQueries);
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_queries_serialization() {
+        let queries = FeatureQueries::create().set_queries(vec![
+            FeatureQuery::new(FeatureType::Protocol, "https://didcomm.org/issue-credential/*"),
+            FeatureQuery::new(FeatureType::GoalCode, "aries.*"),
+        ]);
+        let value = json!(queries);
+        assert_eq!(value["queries"][0]["feature-type"], "protocol");
+        assert_eq!(value["queries"][1]["feature-type"], "goal-code");
+        assert_eq!(value["queries"][1]["match"], "aries.*");
+        assert_eq!(queries, serde_json::from_value(value).unwrap());
+    }
+}
@@ -1,6 +1,12 @@
 use std::collections::HashMap;
 
+use chrono::Utc;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::error::{VcxError, VcxErrorKind};
 use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::localization::Localization;
 use crate::messages::thread::Thread;
 use crate::messages::timing::Timing;
 use crate::timing_optional;
@@ -36,6 +42,10 @@ pub struct ProblemReport {
     pub problem_items: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
+    #[serde(rename = "~l10n")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub localization: Option<Localization>,
     #[serde(rename = "~timing")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing: Option<Timing>,
@@ -46,8 +56,43 @@ impl ProblemReport {
         ProblemReport::default()
     }
 
-    pub fn set_description(mut self, code: u32) -> Self {
-        self.description = Some(Description { en: None, code });
+    /// Builds problem report describing failure on our side of the protocol. Only the problem code and
+    /// its generic explanation are shared with the peer, details of the error are logged locally.
+    pub fn from_error(err: &VcxError) -> Self {
+        let code = ProblemCode::from(err.kind());
+        warn!(
+            "ProblemReport::from_error >>> reporting {} to the peer, err: {}",
+            code.to_string(),
+            err
+        );
+        ProblemReport::create()
+            .set_description_text(code.explanation())
+            .set_description(code)
+            .set_impact(Impact::Thread)
+            .set_noticed_time()
+    }
+
+    /// Builds problem report notifying the peer that we abandoned the protocol, optionally with the reason.
+    pub fn from_abandonment(code: ProblemCode, reason: Option<String>) -> Self {
+        ProblemReport::create()
+            .set_description(code)
+            .set_comment(reason)
+            .set_impact(Impact::Thread)
+            .set_noticed_time()
+    }
+
+    pub fn set_description(mut self, code: ProblemCode) -> Self {
+        let en = self.description.and_then(|description| description.en);
+        self.description = Some(Description { en, code });
+        self
+    }
+
+    pub fn set_description_text(mut self, en: &str) -> Self {
+        let code = self.description.map(|description| description.code).unwrap_or_default();
+        self.description = Some(Description {
+            en: Some(en.to_string()),
+            code,
+        });
         self
     }
 
@@ -55,6 +100,61 @@ impl ProblemReport {
         self.comment = comment;
         self
     }
+
+    pub fn set_who_retries(mut self, who_retries: WhoRetries) -> Self {
+        self.who_retries = Some(who_retries);
+        self
+    }
+
+    pub fn set_impact(mut self, impact: Impact) -> Self {
+        self.impact = Some(impact);
+        self
+    }
+
+    pub fn set_fix_hint(mut self, fix_hint: &str) -> Self {
+        self.fix_hint = Some(FixHint::new(fix_hint));
+        self
+    }
+
+    pub fn set_location(mut self, location: &str) -> Self {
+        self.location = Some(location.to_string());
+        self
+    }
+
+    pub fn set_noticed_time(mut self) -> Self {
+        self.noticed_time = Some(Utc::now().to_rfc3339());
+        self
+    }
+
+    pub fn add_problem_item(mut self, name: &str, value: &str) -> Self {
+        self.problem_items
+            .get_or_insert_with(HashMap::new)
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn set_localization(mut self, locale: &str) -> Self {
+        self.localization = Some(Localization::new(locale));
+        self
+    }
+
+    pub fn get_code(&self) -> Option<ProblemCode> {
+        self.description.as_ref().map(|description| description.code.clone())
+    }
+
+    /// Human readable explanation, taken from the description or the comment of the report.
+    pub fn get_explanation(&self) -> Option<String> {
+        self.description
+            .as_ref()
+            .and_then(|description| description.en.clone())
+            .or_else(|| self.comment.clone())
+    }
+
+    pub fn get_locale(&self) -> Option<String> {
+        self.localization
+            .as_ref()
+            .map(|localization| localization.locale.clone())
+    }
 }
 
 threadlike_optional!(ProblemReport);
@@ -65,19 +165,173 @@ a2a_message!(ProblemReport, CommonProblemReport);
 pub struct Description {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub en: Option<String>,
-    pub code: u32,
+    pub code: ProblemCode,
+}
+
+/// Problem codes of the RFC 0035 catalog shared by all protocols, along with the codes used by the
+/// issue-credential and present-proof protocols when they are abandoned. Other codes are kept as received.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProblemCode {
+    RequestNotAccepted,
+    RequestProcessingError,
+    MessageParseFailure,
+    Trust,
+    TrustCrypto,
+    Transfer,
+    Did,
+    Message,
+    Me,
+    MeResource,
+    Request,
+    RequestTime,
+    Legal,
+    IssuanceAbandoned,
+    PresentationAbandoned,
+    Other(String),
+}
+
+impl ProblemCode {
+    /// Generic human readable explanation of the code, safe to be shared with the peer.
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            ProblemCode::RequestNotAccepted => "The request was not accepted",
+            ProblemCode::RequestProcessingError => "The request could not be processed",
+            ProblemCode::MessageParseFailure => "The message could not be parsed",
+            ProblemCode::Trust => "The message failed a trust check",
+            ProblemCode::TrustCrypto => "The message failed a cryptographic check",
+            ProblemCode::Transfer => "The message could not be delivered",
+            ProblemCode::Did => "The DID could not be resolved or is invalid",
+            ProblemCode::Message => "The message is invalid",
+            ProblemCode::Me => "An internal error occurred",
+            ProblemCode::MeResource => "A resource required to process the message is unavailable",
+            ProblemCode::Request => "The request is invalid",
+            ProblemCode::RequestTime => "The message arrived after it expired",
+            ProblemCode::Legal => "The request can not be processed for legal reasons",
+            ProblemCode::IssuanceAbandoned => "The credential issuance was abandoned",
+            ProblemCode::PresentationAbandoned => "The presentation was abandoned",
+            ProblemCode::Other(_) => "The request could not be processed",
+        }
+    }
+}
+
+impl Default for ProblemCode {
+    fn default() -> ProblemCode {
+        ProblemCode::RequestProcessingError
+    }
+}
+
+impl From<String> for ProblemCode {
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            "request_not_accepted" => ProblemCode::RequestNotAccepted,
+            "request_processing_error" => ProblemCode::RequestProcessingError,
+            "message-parse-failure" => ProblemCode::MessageParseFailure,
+            "trust" => ProblemCode::Trust,
+            "trust.crypto" => ProblemCode::TrustCrypto,
+            "xfer" => ProblemCode::Transfer,
+            "did" => ProblemCode::Did,
+            "msg" => ProblemCode::Message,
+            "me" => ProblemCode::Me,
+            "me.res" => ProblemCode::MeResource,
+            "req" => ProblemCode::Request,
+            "req.time" => ProblemCode::RequestTime,
+            "legal" => ProblemCode::Legal,
+            "issuance-abandoned" => ProblemCode::IssuanceAbandoned,
+            "abandoned" => ProblemCode::PresentationAbandoned,
+            _ => ProblemCode::Other(code),
+        }
+    }
+}
+
+impl ::std::string::ToString for ProblemCode {
+    fn to_string(&self) -> String {
+        match self {
+            ProblemCode::RequestNotAccepted => "request_not_accepted".to_string(),
+            ProblemCode::RequestProcessingError => "request_processing_error".to_string(),
+            ProblemCode::MessageParseFailure => "message-parse-failure".to_string(),
+            ProblemCode::Trust => "trust".to_string(),
+            ProblemCode::TrustCrypto => "trust.crypto".to_string(),
+            ProblemCode::Transfer => "xfer".to_string(),
+            ProblemCode::Did => "did".to_string(),
+            ProblemCode::Message => "msg".to_string(),
+            ProblemCode::Me => "me".to_string(),
+            ProblemCode::MeResource => "me.res".to_string(),
+            ProblemCode::Request => "req".to_string(),
+            ProblemCode::RequestTime => "req.time".to_string(),
+            ProblemCode::Legal => "legal".to_string(),
+            ProblemCode::IssuanceAbandoned => "issuance-abandoned".to_string(),
+            ProblemCode::PresentationAbandoned => "abandoned".to_string(),
+            ProblemCode::Other(code) => code.to_string(),
+        }
+    }
+}
+
+impl From<VcxErrorKind> for ProblemCode {
+    fn from(kind: VcxErrorKind) -> Self {
+        match kind {
+            VcxErrorKind::InvalidJson
+            | VcxErrorKind::InvalidMessagePack
+            | VcxErrorKind::InvalidMessageFormat
+            | VcxErrorKind::InvalidMessages
+            | VcxErrorKind::SerializationError => ProblemCode::MessageParseFailure,
+            VcxErrorKind::InvalidProof
+            | VcxErrorKind::InvalidProofCredentialData
+            | VcxErrorKind::InvalidProofRequest
+            | VcxErrorKind::InvalidCredential
+            | VcxErrorKind::InvalidCredentialRequest
+            | VcxErrorKind::InvalidAttributesStructure
+            | VcxErrorKind::CredentialPreviewSchemaMismatch
+            | VcxErrorKind::CredentialPreviewValuesMismatch
+            | VcxErrorKind::InvalidSchema
+            | VcxErrorKind::InvalidRevocationDetails
+            | VcxErrorKind::InvalidState
+            | VcxErrorKind::ActionNotSupported => ProblemCode::RequestNotAccepted,
+            VcxErrorKind::InvalidDid => ProblemCode::Did,
+            VcxErrorKind::MessageExpired => ProblemCode::RequestTime,
+            VcxErrorKind::InvalidVerkey => ProblemCode::TrustCrypto,
+            VcxErrorKind::PostMessageFailed | VcxErrorKind::NoEndpoint | VcxErrorKind::InvalidHttpResponse => {
+                ProblemCode::Transfer
+            }
+            VcxErrorKind::WalletAccessFailed
+            | VcxErrorKind::InvalidWalletHandle
+            | VcxErrorKind::WalletNotFound
+            | VcxErrorKind::NoPoolOpen
+            | VcxErrorKind::PoolLedgerConnect
+            | VcxErrorKind::InvalidLedgerResponse => ProblemCode::MeResource,
+            _ => ProblemCode::RequestProcessingError,
+        }
+    }
+}
+
+impl Serialize for ProblemCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ProblemCode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // numeric codes were used by previous versions
+        match Value::deserialize(deserializer)? {
+            Value::String(code) => Ok(ProblemCode::from(code)),
+            Value::Number(code) => Ok(ProblemCode::Other(code.to_string())),
+            value => Err(de::Error::custom(format!("Invalid problem code: {}", value))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(untagged)]
+#[serde(rename_all = "lowercase")]
 pub enum WhoRetries {
-    #[serde(rename = "me")]
     Me,
-    #[serde(rename = "you")]
     You,
-    #[serde(rename = "both")]
     Both,
-    #[serde(rename = "none")]
     None,
 }
 
@@ -89,17 +343,20 @@ impl Default for WhoRetries {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct FixHint {
-    en: String,
+    pub en: String,
+}
+
+impl FixHint {
+    pub fn new(en: &str) -> Self {
+        FixHint { en: en.to_string() }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(untagged)]
+#[serde(rename_all = "lowercase")]
 pub enum Impact {
-    #[serde(rename = "message")]
     Message,
-    #[serde(rename = "thread")]
     Thread,
-    #[serde(rename = "connection")]
     Connection,
 }
 
@@ -110,8 +367,8 @@ pub mod test_utils {
 
     use super::*;
 
-    pub fn _code() -> u32 {
-        0
+    pub fn _code() -> ProblemCode {
+        ProblemCode::RequestProcessingError
     }
 
     pub fn _comment() -> Option<String> {
@@ -135,6 +392,7 @@ pub mod test_utils {
             location: None,
             problem_items: None,
             comment: _comment(),
+            localization: None,
             timing: None,
         }
     }
@@ -157,4 +415,52 @@ pub mod unit_tests {
 
         assert_eq!(_problem_report(), report);
     }
+
+    #[test]
+    fn test_problem_report_from_error() {
+        let err = VcxError::from_msg(VcxErrorKind::InvalidJson, "Cannot parse credential request");
+        let report = ProblemReport::from_error(&err).set_thread_id(&_thread_id());
+
+        assert_eq!(Some(ProblemCode::MessageParseFailure), report.get_code());
+        assert_eq!(
+            Some(ProblemCode::MessageParseFailure.explanation().to_string()),
+            report.get_explanation()
+        );
+        assert_eq!(None, report.comment);
+        assert_eq!(Some(Impact::Thread), report.impact);
+        assert!(report.noticed_time.is_some());
+    }
+
+    #[test]
+    fn test_problem_report_serialization() {
+        let report = ProblemReport::create()
+            .set_description(ProblemCode::RequestNotAccepted)
+            .set_description_text("Offer was rejected")
+            .set_who_retries(WhoRetries::None)
+            .set_localization("en")
+            .add_problem_item("attribute", "age");
+        let value = json!(report);
+
+        assert_eq!(value["description"]["code"], "request_not_accepted");
+        assert_eq!(value["description"]["en"], "Offer was rejected");
+        assert_eq!(value["who_retries"], "none");
+        assert_eq!(value["~l10n"]["locale"], "en");
+        assert_eq!(value["problem_items"]["attribute"], "age");
+        assert_eq!(report, serde_json::from_value(value).unwrap());
+    }
+
+    #[test]
+    fn test_problem_code_deserialization() {
+        let description: Description = serde_json::from_value(json!({"code": "req.time"})).unwrap();
+        assert_eq!(ProblemCode::RequestTime, description.code);
+
+        let description: Description = serde_json::from_value(json!({"code": "issuance-abandoned"})).unwrap();
+        assert_eq!(ProblemCode::IssuanceAbandoned, description.code);
+
+        let description: Description = serde_json::from_value(json!({"code": "custom-code"})).unwrap();
+        assert_eq!(ProblemCode::Other("custom-code".to_string()), description.code);
+
+        let description: Description = serde_json::from_value(json!({"code": 0})).unwrap();
+        assert_eq!(ProblemCode::Other("0".to_string()), description.code);
+    }
 }
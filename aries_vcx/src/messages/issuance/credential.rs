@@ -66,8 +66,9 @@ pub mod test_utils {
     use crate::messages::a2a::MessageId;
     use crate::messages::attachment::{AttachmentId, Attachments};
     use crate::messages::issuance::credential::Credential;
-    use crate::messages::issuance::credential_offer::test_utils::thread;
+    use crate::messages::issuance::credential_offer::test_utils::{_value, thread};
     use crate::utils::constants;
+    use crate::utils::openssl::encode;
 
     pub fn _attachment() -> ::serde_json::Value {
         json!({
@@ -78,14 +79,31 @@ pub mod test_utils {
         })
     }
 
+    /// Credential issued with the value previewed by `credential_offer::test_utils::_credential_offer`.
+    pub fn _attachment_issued_from_offer() -> ::serde_json::Value {
+        let (name, value) = _value();
+        let mut attachment = _attachment();
+        attachment["values"] = json!({});
+        attachment["values"][name] = json!({"raw": value, "encoded": encode(value).unwrap()});
+        attachment
+    }
+
     pub fn _comment() -> String {
         String::from("comment")
     }
 
     pub fn _credential() -> Credential {
+        _credential_with_attachment(_attachment())
+    }
+
+    pub fn _credential_issued_from_offer() -> Credential {
+        _credential_with_attachment(_attachment_issued_from_offer())
+    }
+
+    fn _credential_with_attachment(credential_attachment: ::serde_json::Value) -> Credential {
         let mut attachment = Attachments::new();
         attachment
-            .add_base64_encoded_json_attachment(AttachmentId::Credential, _attachment())
+            .add_base64_encoded_json_attachment(AttachmentId::Credential, credential_attachment)
             .unwrap();
 
         Credential {
@@ -1,7 +1,13 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
 use crate::messages::a2a::message_family::MessageFamilies;
 use crate::messages::a2a::message_type::MessageType;
 use crate::messages::mime_type::MimeType;
+use crate::utils::encoding::normalize_raw_value;
+use crate::utils::openssl::encode;
 
 pub mod credential;
 pub mod credential_ack;
@@ -28,6 +34,11 @@ impl CredentialPreviewData {
                 value: value.to_string(),
                 _type: None,
             },
+            mime_type => CredentialValue {
+                name: name.to_string(),
+                value: value.to_string(),
+                _type: Some(mime_type),
+            },
         };
         self.attributes.push(data_value);
         self
@@ -41,6 +52,109 @@ impl CredentialPreviewData {
             )
         })
     }
+
+    /// Checks that the previewed attributes are exactly the attributes of the schema, compared the
+    /// way libindy does, ignoring case and spaces.
+    pub fn validate_against_schema(&self, schema_json: &str) -> VcxResult<()> {
+        trace!(
+            "CredentialPreviewData::validate_against_schema >>> schema_json: {}",
+            schema_json
+        );
+        let schema: Value = _parse_json(schema_json, "schema")?;
+        let schema_attrs: HashSet<String> = schema["attrNames"]
+            .as_array()
+            .ok_or_else(|| VcxError::from_msg(VcxErrorKind::InvalidSchema, "Schema has no attrNames"))?
+            .iter()
+            .filter_map(Value::as_str)
+            .map(_attr_common_view)
+            .collect();
+        let preview_attrs: Vec<String> = self
+            .attributes
+            .iter()
+            .map(|attr| _attr_common_view(&attr.name))
+            .collect();
+        let unique_preview_attrs: HashSet<String> = preview_attrs.iter().cloned().collect();
+        if unique_preview_attrs.len() != preview_attrs.len() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::CredentialPreviewSchemaMismatch,
+                format!("Credential preview contains duplicate attributes: {:?}", preview_attrs),
+            ));
+        }
+        let mut missing: Vec<&String> = schema_attrs.difference(&unique_preview_attrs).collect();
+        let mut unknown: Vec<&String> = unique_preview_attrs.difference(&schema_attrs).collect();
+        if !missing.is_empty() || !unknown.is_empty() {
+            missing.sort();
+            unknown.sort();
+            return Err(VcxError::from_msg(
+                VcxErrorKind::CredentialPreviewSchemaMismatch,
+                format!(
+                    "Credential preview does not match schema, missing attributes: {:?}, unknown attributes: {:?}",
+                    missing, unknown
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the libindy credential was issued with the previewed raw values, normalized by
+    /// their MIME types, and that each value is encoded from its raw value.
+    pub fn validate_issued_values(&self, credential_json: &str) -> VcxResult<()> {
+        trace!("CredentialPreviewData::validate_issued_values >>>");
+        let credential: Value = _parse_json(credential_json, "credential")?;
+        let values = credential["values"]
+            .as_object()
+            .ok_or_else(|| VcxError::from_msg(VcxErrorKind::InvalidCredential, "Credential has no values"))?;
+        if values.len() != self.attributes.len() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::CredentialPreviewValuesMismatch,
+                format!(
+                    "Credential has {} attributes, credential preview has {}",
+                    values.len(),
+                    self.attributes.len()
+                ),
+            ));
+        }
+        for attribute in self.attributes.iter() {
+            let raw = values
+                .get(&attribute.name)
+                .and_then(|value| value["raw"].as_str())
+                .ok_or_else(|| {
+                    VcxError::from_msg(
+                        VcxErrorKind::CredentialPreviewValuesMismatch,
+                        format!("Attribute {} of credential preview was not issued", attribute.name),
+                    )
+                })?;
+            if raw != normalize_raw_value(&attribute.value, &attribute.mime_type())? {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::CredentialPreviewValuesMismatch,
+                    format!(
+                        "Attribute {} was issued with value {:?}, credential preview has {:?}",
+                        attribute.name, raw, attribute.value
+                    ),
+                ));
+            }
+            if values[&attribute.name]["encoded"].as_str() != Some(encode(raw)?.as_str()) {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::CredentialPreviewValuesMismatch,
+                    format!("Attribute {} is not encoded from its raw value", attribute.name),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn _attr_common_view(attr: &str) -> String {
+    attr.replace(' ', "").to_lowercase()
+}
+
+fn _parse_json(json: &str, name: &str) -> VcxResult<Value> {
+    serde_json::from_str(json).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!("Cannot deserialize {}: {}", name, err),
+        )
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
@@ -52,6 +166,12 @@ pub struct CredentialValue {
     pub _type: Option<MimeType>,
 }
 
+impl CredentialValue {
+    pub fn mime_type(&self) -> MimeType {
+        self._type.clone().unwrap_or_default()
+    }
+}
+
 impl Default for CredentialPreviewData {
     fn default() -> CredentialPreviewData {
         CredentialPreviewData {
@@ -76,3 +196,71 @@ pub mod test_utils {
         error::test_utils::_problem_report().set_thread_id(&_credential_offer().id.0)
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use crate::messages::issuance::credential::test_utils::_attachment_issued_from_offer;
+    use crate::messages::issuance::credential_offer::test_utils::_value;
+    use crate::utils::constants::SCHEMA_JSON;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_against_schema() {
+        let preview = CredentialPreviewData::new()
+            .add_value("height", "170", MimeType::Plain)
+            .add_value("Name", "Alice", MimeType::Plain)
+            .add_value("sex", "female", MimeType::Plain);
+        assert_eq!(
+            preview.validate_against_schema(SCHEMA_JSON).unwrap_err().kind(),
+            VcxErrorKind::CredentialPreviewSchemaMismatch
+        );
+
+        let preview = preview.add_value("age", "25", MimeType::Plain);
+        preview.validate_against_schema(SCHEMA_JSON).unwrap();
+
+        let preview = preview.add_value("degree", "maths", MimeType::Plain);
+        assert_eq!(
+            preview.validate_against_schema(SCHEMA_JSON).unwrap_err().kind(),
+            VcxErrorKind::CredentialPreviewSchemaMismatch
+        );
+    }
+
+    #[test]
+    fn test_validate_issued_values() {
+        let (name, value) = _value();
+        let credential_json = _attachment_issued_from_offer().to_string();
+        CredentialPreviewData::new()
+            .add_value(name, value, MimeType::Plain)
+            .validate_issued_values(&credential_json)
+            .unwrap();
+
+        let err = CredentialPreviewData::new()
+            .add_value(name, "Alice", MimeType::Plain)
+            .validate_issued_values(&credential_json)
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::CredentialPreviewValuesMismatch);
+
+        let err = CredentialPreviewData::new()
+            .add_value(name, value, MimeType::Plain)
+            .add_value("age", "25", MimeType::Plain)
+            .validate_issued_values(&credential_json)
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::CredentialPreviewValuesMismatch);
+
+        let credential = json!({"values": {"birthdate": {"raw": "19900421", "encoded": "19900421"}}}).to_string();
+        CredentialPreviewData::new()
+            .add_value("birthdate", "1990-04-21", MimeType::Date)
+            .validate_issued_values(&credential)
+            .unwrap();
+
+        let mut credential = _attachment_issued_from_offer();
+        credential["values"][name]["encoded"] = json!("1");
+        let err = CredentialPreviewData::new()
+            .add_value(name, value, MimeType::Plain)
+            .validate_issued_values(&credential.to_string())
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::CredentialPreviewValuesMismatch);
+    }
+}
@@ -1,16 +1,33 @@
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub const DEFAULT_LOCALE: &str = "en";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Localization {
-    locale: Locales,
+    pub locale: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum Locales {
-    #[serde(rename = "en")]
-    En,
+impl Localization {
+    pub fn new(locale: &str) -> Localization {
+        Localization {
+            locale: locale.to_string(),
+        }
+    }
 }
 
-impl Default for Locales {
-    fn default() -> Locales {
-        Locales::En
+impl Default for Localization {
+    fn default() -> Localization {
+        Localization::new(DEFAULT_LOCALE)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_localization_serialization() {
+        assert_eq!(json!({"locale": "en"}), json!(Localization::default()));
+        let localization: Localization = serde_json::from_value(json!({"locale": "cs"})).unwrap();
+        assert_eq!(Localization::new("cs"), localization);
     }
 }
@@ -2,6 +2,12 @@
 pub enum MimeType {
     #[serde(rename = "text/plain")]
     Plain,
+    #[serde(rename = "text/date")]
+    Date,
+    #[serde(rename = "image/jpeg")]
+    Jpeg,
+    #[serde(rename = "image/png")]
+    Png,
 }
 
 impl Default for MimeType {
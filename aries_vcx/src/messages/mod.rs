@@ -7,6 +7,8 @@ pub mod ack;
 pub mod attachment;
 pub mod basic_message;
 pub mod connection;
+pub mod coordinate_mediation;
+pub mod did_rotate;
 pub mod discovery;
 pub mod error;
 pub mod forward;
@@ -14,6 +16,7 @@ pub mod issuance;
 pub mod localization;
 pub mod mime_type;
 pub mod out_of_band;
+pub mod pickup;
 pub mod proof_presentation;
 pub mod status;
 pub mod timing;
@@ -0,0 +1,95 @@
+use crate::error::{VcxError, VcxErrorKind, VcxResult};
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::thread::Thread;
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct DeliveryAttachmentData {
+    pub base64: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct DeliveryAttachment {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub data: DeliveryAttachmentData,
+}
+
+impl DeliveryAttachment {
+    pub fn new(id: &str, envelope: &[u8]) -> Self {
+        Self {
+            id: id.to_string(),
+            data: DeliveryAttachmentData {
+                base64: base64::encode(envelope),
+            },
+        }
+    }
+
+    pub fn get_envelope(&self) -> VcxResult<Vec<u8>> {
+        base64::decode(&self.data.base64).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidMessageFormat,
+                format!("Cannot decode delivered message {}: {:?}", self.id, err),
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct Delivery {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+    #[serde(rename = "~attach")]
+    pub attachments: Vec<DeliveryAttachment>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl Delivery {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn add_attachment(mut self, id: &str, envelope: &[u8]) -> Self {
+        self.attachments.push(DeliveryAttachment::new(id, envelope));
+        self
+    }
+
+    pub fn get_message_ids(&self) -> Vec<String> {
+        self.attachments.iter().map(|attach| attach.id.clone()).collect()
+    }
+}
+
+threadlike_optional!(Delivery);
+a2a_message!(Delivery, PickupDelivery);
+timing_optional!(Delivery);
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_delivery_attachment_roundtrip() {
+        let delivery = Delivery::create().add_attachment("msg-1", "{\"foo\":\"bar\"}".as_bytes());
+        let serialized = serde_json::to_string(&delivery.to_a2a_message()).unwrap();
+        let deserialized: A2AMessage = serde_json::from_str(&serialized).unwrap();
+        match deserialized {
+            A2AMessage::PickupDelivery(delivery) => {
+                assert_eq!(delivery.get_message_ids(), vec!["msg-1".to_string()]);
+                assert_eq!(
+                    delivery.attachments[0].get_envelope().unwrap(),
+                    "{\"foo\":\"bar\"}".as_bytes().to_vec()
+                );
+            }
+            other => panic!("Expected PickupDelivery, got {:?}", other),
+        }
+    }
+}
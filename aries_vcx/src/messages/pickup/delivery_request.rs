@@ -0,0 +1,34 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct DeliveryRequest {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl DeliveryRequest {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn set_recipient_key(mut self, recipient_key: Option<String>) -> Self {
+        self.recipient_key = recipient_key;
+        self
+    }
+}
+
+a2a_message!(DeliveryRequest, PickupDeliveryRequest);
+timing_optional!(DeliveryRequest);
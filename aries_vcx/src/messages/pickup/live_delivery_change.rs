@@ -0,0 +1,27 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct LiveDeliveryChange {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub live_delivery: bool,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl LiveDeliveryChange {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_live_delivery(mut self, live_delivery: bool) -> Self {
+        self.live_delivery = live_delivery;
+        self
+    }
+}
+
+a2a_message!(LiveDeliveryChange, PickupLiveDeliveryChange);
+timing_optional!(LiveDeliveryChange);
@@ -0,0 +1,27 @@
+use crate::messages::a2a::{A2AMessage, MessageId};
+use crate::messages::timing::Timing;
+use crate::timing_optional;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct MessagesReceived {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub message_id_list: Vec<String>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl MessagesReceived {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    pub fn set_message_id_list(mut self, message_id_list: Vec<String>) -> Self {
+        self.message_id_list = message_id_list;
+        self
+    }
+}
+
+a2a_message!(MessagesReceived, PickupMessagesReceived);
+timing_optional!(MessagesReceived);
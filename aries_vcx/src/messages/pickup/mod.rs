@@ -0,0 +1,6 @@
+pub mod delivery;
+pub mod delivery_request;
+pub mod live_delivery_change;
+pub mod messages_received;
+pub mod status;
+pub mod status_request;
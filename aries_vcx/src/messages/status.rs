@@ -24,6 +24,13 @@ impl Status {
         }
     }
 
+    pub fn problem_report(&self) -> Option<&ProblemReport> {
+        match self {
+            Status::Failed(problem_report) | Status::Declined(problem_report) => Some(problem_report),
+            _ => None,
+        }
+    }
+
     pub fn from_u32(state: u32) -> Self {
         match state {
             1 => Self::Success,
@@ -70,6 +70,23 @@ impl Timing {
     pub fn get_out_time(&self) -> Option<&str> {
         self.out_time.as_deref()
     }
+
+    pub fn get_expires_time(&self) -> Option<&str> {
+        self.expires_time.as_deref()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        match &self.expires_time {
+            Some(expires_time) => match DateTime::parse_from_rfc3339(expires_time) {
+                Ok(expires_time) => expires_time < Utc::now(),
+                Err(err) => {
+                    warn!("Failed to parse expires_time {}, err: {:?}", expires_time, err);
+                    false
+                }
+            },
+            None => false,
+        }
+    }
 }
 
 impl Default for Timing {
@@ -88,9 +105,18 @@ impl Default for Timing {
 macro_rules! timing_optional (($type:ident) => (
     impl $type {
         pub fn set_out_time(mut self) -> Self {
-            self.timing = Some(Timing::new().set_out_time_to_now());
+            self.timing = Some(self.timing.unwrap_or_default().set_out_time_to_now());
             self
         }
+
+        pub fn set_expires_time(mut self, expires_time: chrono::DateTime<chrono::Utc>) -> Self {
+            self.timing = Some(self.timing.unwrap_or_default().set_expires_time(expires_time));
+            self
+        }
+
+        pub fn is_expired(&self) -> bool {
+            self.timing.as_ref().map_or(false, |timing| timing.is_expired())
+        }
     }
 ));
 
@@ -101,7 +127,7 @@ pub mod unit_tests {
     use std::thread;
     use std::time::Duration;
 
-    
+    use crate::messages::error::ProblemReport;
     use crate::utils::devsetup::SetupMocks;
 
     use super::*;
@@ -145,4 +171,22 @@ pub mod unit_tests {
         assert!(t1 < t);
         assert!(t < t2);
     }
+
+    #[test]
+    fn test_is_expired() {
+        let timing = Timing::new().set_expires_time(Utc::now() - chrono::Duration::seconds(1));
+        assert!(timing.is_expired());
+        let timing = Timing::new().set_expires_time(Utc::now() + chrono::Duration::hours(1));
+        assert!(!timing.is_expired());
+        assert!(!Timing::new().is_expired());
+    }
+
+    #[test]
+    fn test_set_out_time_keeps_expires_time() {
+        let expires_time = Utc::now() - chrono::Duration::seconds(1);
+        let problem_report = ProblemReport::create().set_expires_time(expires_time).set_out_time();
+        assert!(problem_report.is_expired());
+        assert!(problem_report.timing.unwrap().out_time.is_some());
+        assert!(!ProblemReport::create().is_expired());
+    }
 }
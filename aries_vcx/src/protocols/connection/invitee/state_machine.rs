@@ -15,6 +15,7 @@ use crate::messages::connection::problem_report::{ProblemCode, ProblemReport};
 use crate::messages::connection::request::Request;
 use crate::messages::connection::response::{Response, SignedResponse};
 use crate::messages::discovery::disclose::{Disclose, ProtocolDescriptor};
+use crate::messages::discovery::disclosures::{Disclosure, FeatureDisclosures};
 use crate::protocols::connection::invitee::states::complete::CompleteState;
 use crate::protocols::connection::invitee::states::initial::InitialState;
 use crate::protocols::connection::invitee::states::invited::InvitedState;
@@ -162,6 +163,13 @@ impl SmConnectionInvitee {
         }
     }
 
+    pub fn get_remote_features(&self) -> Option<Vec<Disclosure>> {
+        match self.state {
+            InviteeFullState::Completed(ref state) => state.features.clone(),
+            _ => None,
+        }
+    }
+
     pub fn remote_did(&self) -> VcxResult<String> {
         self.their_did_doc()
             .map(|did_doc: DidDoc| did_doc.id)
@@ -344,6 +352,29 @@ impl SmConnectionInvitee {
         Ok(Self { state, ..self })
     }
 
+    pub fn rotate_pairwise_info(self, pairwise_info: PairwiseInfo) -> VcxResult<Self> {
+        match self.state {
+            InviteeFullState::Completed(_) => Ok(Self { pairwise_info, ..self }),
+            _ => Err(VcxError::from_msg(
+                VcxErrorKind::NotReady,
+                "Pairwise keys can be rotated only on completed connection",
+            )),
+        }
+    }
+
+    pub fn handle_did_rotate(self, did_doc: DidDoc) -> VcxResult<Self> {
+        let state = match self.state {
+            InviteeFullState::Completed(state) => InviteeFullState::Completed(CompleteState { did_doc, ..state }),
+            _ => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::NotReady,
+                    "Counterparty DID can be rotated only on completed connection",
+                ))
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
     pub fn handle_disclose(self, disclose: Disclose) -> VcxResult<Self> {
         let state = match self.state {
             InviteeFullState::Completed(state) => InviteeFullState::Completed((state, disclose.protocols).into()),
@@ -352,6 +383,18 @@ impl SmConnectionInvitee {
         Ok(Self { state, ..self })
     }
 
+    pub fn handle_disclosures(self, disclosures: FeatureDisclosures) -> VcxResult<Self> {
+        let state = match self.state {
+            InviteeFullState::Completed(state) => InviteeFullState::Completed(CompleteState {
+                protocols: Some(disclosures.get_protocols()),
+                features: Some(disclosures.disclosures),
+                ..state
+            }),
+            _ => self.state,
+        };
+        Ok(Self { state, ..self })
+    }
+
     // todo: send ack is validaiting connection response, should be moved to handle_connection_response
     pub async fn handle_send_ack<F, T>(self, wallet_handle: WalletHandle, send_message: &F) -> VcxResult<Self>
     where
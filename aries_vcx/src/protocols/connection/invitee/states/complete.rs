@@ -3,6 +3,7 @@ use std::clone::Clone;
 use crate::did_doc::DidDoc;
 use crate::messages::connection::response::Response;
 use crate::messages::discovery::disclose::ProtocolDescriptor;
+use crate::messages::discovery::disclosures::Disclosure;
 use crate::protocols::connection::invitee::states::requested::RequestedState;
 use crate::protocols::connection::invitee::states::responded::RespondedState;
 
@@ -11,6 +12,9 @@ pub struct CompleteState {
     pub did_doc: DidDoc,
     pub bootstrap_did_doc: DidDoc,
     pub protocols: Option<Vec<ProtocolDescriptor>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<Vec<Disclosure>>,
 }
 
 impl From<(CompleteState, Vec<ProtocolDescriptor>)> for CompleteState {
@@ -20,6 +24,7 @@ impl From<(CompleteState, Vec<ProtocolDescriptor>)> for CompleteState {
             bootstrap_did_doc: state.bootstrap_did_doc,
             did_doc: state.did_doc,
             protocols: Some(protocols),
+            features: state.features,
         }
     }
 }
@@ -31,6 +36,7 @@ impl From<(RequestedState, Response)> for CompleteState {
             bootstrap_did_doc: state.did_doc,
             did_doc: response.connection.did_doc,
             protocols: None,
+            features: None,
         }
     }
 }
@@ -42,6 +48,7 @@ impl From<(RespondedState, Response)> for CompleteState {
             bootstrap_did_doc: state.did_doc,
             did_doc: response.connection.did_doc,
             protocols: None,
+            features: None,
         }
     }
 }
@@ -14,6 +14,7 @@ use crate::messages::connection::problem_report::{ProblemCode, ProblemReport};
 use crate::messages::connection::request::Request;
 use crate::messages::connection::response::{Response, SignedResponse};
 use crate::messages::discovery::disclose::{Disclose, ProtocolDescriptor};
+use crate::messages::discovery::disclosures::{Disclosure, FeatureDisclosures};
 use crate::protocols::connection::inviter::states::complete::CompleteState;
 use crate::protocols::connection::inviter::states::initial::InitialState;
 use crate::protocols::connection::inviter::states::invited::InvitedState;
@@ -137,6 +138,13 @@ impl SmConnectionInviter {
         }
     }
 
+    pub fn get_remote_features(&self) -> Option<Vec<Disclosure>> {
+        match self.state {
+            InviterFullState::Completed(ref state) => state.features.clone(),
+            _ => None,
+        }
+    }
+
     pub fn is_in_null_state(&self) -> bool {
         match self.state {
             InviterFullState::Initial(_) => true,
@@ -327,6 +335,29 @@ impl SmConnectionInviter {
         Ok(Self { state, ..self })
     }
 
+    pub fn rotate_pairwise_info(self, pairwise_info: PairwiseInfo) -> VcxResult<Self> {
+        match self.state {
+            InviterFullState::Completed(_) => Ok(Self { pairwise_info, ..self }),
+            _ => Err(VcxError::from_msg(
+                VcxErrorKind::NotReady,
+                "Pairwise keys can be rotated only on completed connection",
+            )),
+        }
+    }
+
+    pub fn handle_did_rotate(self, did_doc: DidDoc) -> VcxResult<Self> {
+        let state = match self.state {
+            InviterFullState::Completed(state) => InviterFullState::Completed(CompleteState { did_doc, ..state }),
+            _ => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::NotReady,
+                    "Counterparty DID can be rotated only on completed connection",
+                ))
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
     pub fn handle_disclose(self, disclose: Disclose) -> VcxResult<Self> {
         let state = match self.state {
             InviterFullState::Completed(state) => InviterFullState::Completed((state, disclose.protocols).into()),
@@ -335,6 +366,18 @@ impl SmConnectionInviter {
         Ok(Self { state, ..self })
     }
 
+    pub fn handle_disclosures(self, disclosures: FeatureDisclosures) -> VcxResult<Self> {
+        let state = match self.state {
+            InviterFullState::Completed(state) => InviterFullState::Completed(CompleteState {
+                protocols: Some(disclosures.get_protocols()),
+                features: Some(disclosures.disclosures),
+                ..state
+            }),
+            _ => self.state,
+        };
+        Ok(Self { state, ..self })
+    }
+
     pub async fn handle_confirmation_message(self, msg: &A2AMessage) -> VcxResult<Self> {
         verify_thread_id(&self.get_thread_id(), msg)?;
         match self.state {
@@ -387,6 +430,7 @@ pub mod unit_tests {
     use crate::messages::connection::request::unit_tests::_request;
     use crate::messages::connection::response::test_utils::_signed_response;
     use crate::messages::discovery::disclose::test_utils::_disclose;
+    use crate::messages::discovery::queries::FeatureType;
     use crate::messages::discovery::query::test_utils::_query;
     use crate::messages::trust_ping::ping::unit_tests::_ping;
     
@@ -775,6 +819,22 @@ pub mod unit_tests {
 
                 assert!(did_exchange_sm.get_remote_protocols().is_some());
 
+                // Disclosures
+                assert!(did_exchange_sm.get_remote_features().is_none());
+
+                let disclosures = FeatureDisclosures::create().set_disclosures(vec![
+                    Disclosure::new(FeatureType::Protocol, "https://didcomm.org/trust_ping/1.0"),
+                    Disclosure::new(FeatureType::GoalCode, "aries.vc.issue"),
+                ]);
+                did_exchange_sm = did_exchange_sm.handle_disclosures(disclosures.clone()).unwrap();
+                assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
+
+                assert_eq!(
+                    Some(disclosures.get_protocols()),
+                    did_exchange_sm.get_remote_protocols()
+                );
+                assert_eq!(Some(disclosures.disclosures), did_exchange_sm.get_remote_features());
+
                 // Problem Report
                 did_exchange_sm = did_exchange_sm.handle_problem_report(_problem_report()).unwrap();
                 assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
@@ -951,5 +1011,52 @@ pub mod unit_tests {
                 );
             }
         }
+
+        mod did_rotate {
+            use crate::did_doc::test_utils::_did_doc_recipient_keys_by_value;
+
+            use super::*;
+
+            #[tokio::test]
+            #[cfg(feature = "general_test")]
+            async fn test_did_rotate_on_completed_connection() {
+                let _setup = SetupMocks::init();
+
+                let new_pairwise_info = PairwiseInfo {
+                    pw_did: "NewDid".to_string(),
+                    pw_vk: "NewVerkey".to_string(),
+                };
+                let did_exchange_sm = inviter_sm()
+                    .await
+                    .to_inviter_completed_state()
+                    .await
+                    .rotate_pairwise_info(new_pairwise_info.clone())
+                    .unwrap()
+                    .handle_did_rotate(_did_doc_recipient_keys_by_value())
+                    .unwrap();
+
+                assert_eq!(&new_pairwise_info, did_exchange_sm.pairwise_info());
+                assert_eq!(
+                    Some(_did_doc_recipient_keys_by_value()),
+                    did_exchange_sm.their_did_doc()
+                );
+                assert_eq!(InviterState::Completed, did_exchange_sm.get_state());
+            }
+
+            #[tokio::test]
+            #[cfg(feature = "general_test")]
+            async fn test_did_rotate_fails_before_completed_state() {
+                let _setup = SetupMocks::init();
+
+                let did_exchange_sm = inviter_sm().await.to_inviter_responded_state().await;
+                did_exchange_sm
+                    .clone()
+                    .rotate_pairwise_info(PairwiseInfo::default())
+                    .unwrap_err();
+                did_exchange_sm
+                    .handle_did_rotate(_did_doc_recipient_keys_by_value())
+                    .unwrap_err();
+            }
+        }
     }
 }
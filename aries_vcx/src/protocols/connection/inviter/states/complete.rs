@@ -2,11 +2,15 @@ use std::clone::Clone;
 
 use crate::did_doc::DidDoc;
 use crate::messages::discovery::disclose::ProtocolDescriptor;
+use crate::messages::discovery::disclosures::Disclosure;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CompleteState {
     pub did_doc: DidDoc,
     pub protocols: Option<Vec<ProtocolDescriptor>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<Vec<Disclosure>>,
     pub thread_id: Option<String>,
 }
 
@@ -17,6 +21,7 @@ impl From<(CompleteState, Vec<ProtocolDescriptor>)> for CompleteState {
             did_doc: state.did_doc,
             thread_id: state.thread_id,
             protocols: Some(protocols),
+            features: state.features,
         }
     }
 }
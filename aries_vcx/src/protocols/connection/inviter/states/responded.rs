@@ -31,6 +31,7 @@ impl From<RespondedState> for CompleteState {
             did_doc: state.did_doc,
             thread_id: Some(state.signed_response.get_thread_id()),
             protocols: None,
+            features: None,
         }
     }
 }
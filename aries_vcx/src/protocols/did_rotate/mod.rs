@@ -0,0 +1,96 @@
+use indy_sys::WalletHandle;
+
+use crate::did_doc::DidDoc;
+use crate::error::prelude::*;
+use crate::messages::connection::response::{ConnectionData, ConnectionSignature};
+use crate::messages::did_rotate::ack::RotateAck;
+use crate::messages::did_rotate::rotate::Rotate;
+
+pub fn build_did_doc(did: &str, recipient_key: &str, routing_keys: Vec<String>, service_endpoint: String) -> DidDoc {
+    let mut did_doc = DidDoc::default();
+    did_doc.set_id(did.to_string());
+    did_doc.set_service_endpoint(service_endpoint);
+    did_doc.set_recipient_keys(vec![recipient_key.to_string()]);
+    did_doc.set_routing_keys(routing_keys);
+    did_doc
+}
+
+pub async fn build_rotate_msg(
+    wallet_handle: WalletHandle,
+    signing_key: &str,
+    to_did: &str,
+    to_did_doc: DidDoc,
+) -> VcxResult<Rotate> {
+    let connection = ConnectionData {
+        did: to_did.to_string(),
+        did_doc: to_did_doc,
+    };
+    let signature = ConnectionSignature::sign(wallet_handle, signing_key, &connection).await?;
+    Ok(Rotate::create(to_did, signature).set_out_time())
+}
+
+pub async fn verify_rotate_msg(rotate: &Rotate, their_vk: &str) -> VcxResult<DidDoc> {
+    let connection = rotate.to_did_doc_sig.verify(their_vk).await?;
+    if connection.did != rotate.to_did || connection.did_doc.id != rotate.to_did {
+        return Err(VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!(
+                "Signed DID {} does not match DID {} the connection is being rotated to",
+                connection.did, rotate.to_did
+            ),
+        ));
+    }
+    connection.did_doc.validate()?;
+    Ok(connection.did_doc)
+}
+
+pub fn build_rotate_ack(rotate: &Rotate) -> RotateAck {
+    RotateAck::create().set_thread_id(&rotate.id.0).set_out_time()
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _new_did_doc() -> DidDoc {
+        build_did_doc(
+            "NewDid",
+            "NewVerkey",
+            vec!["RoutingKey".to_string()],
+            "http://localhost:8080".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rotate_msg_roundtrip() {
+        let _setup = SetupMocks::init();
+        let rotate = build_rotate_msg(WalletHandle(0), "OldVerkey", "NewDid", _new_did_doc())
+            .await
+            .unwrap();
+        let did_doc = verify_rotate_msg(&rotate, "OldVerkey").await.unwrap();
+        assert_eq!(did_doc, _new_did_doc());
+        assert_eq!(build_rotate_ack(&rotate).get_thread_id(), rotate.id.0);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_msg_signed_by_other_key_fails() {
+        let _setup = SetupMocks::init();
+        let rotate = build_rotate_msg(WalletHandle(0), "OldVerkey", "NewDid", _new_did_doc())
+            .await
+            .unwrap();
+        verify_rotate_msg(&rotate, "OtherVerkey").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_rotate_msg_with_mismatching_did_fails() {
+        let _setup = SetupMocks::init();
+        let mut rotate = build_rotate_msg(WalletHandle(0), "OldVerkey", "NewDid", _new_did_doc())
+            .await
+            .unwrap();
+        rotate.to_did = "OtherDid".to_string();
+        verify_rotate_msg(&rotate, "OldVerkey").await.unwrap_err();
+    }
+}
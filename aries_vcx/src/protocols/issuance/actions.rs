@@ -20,6 +20,7 @@ pub enum CredentialIssuanceAction {
     Credential(Credential),
     CredentialAck(CredentialAck),
     ProblemReport(ProblemReport),
+    CredentialOfferExpired,
     Unknown,
 }
 
@@ -32,6 +33,19 @@ impl CredentialIssuanceAction {
             _ => true,
         }
     }
+
+    /// Returns the message received from the counterparty which triggered this action, if any.
+    pub fn received_message(&self) -> Option<A2AMessage> {
+        match self {
+            Self::CredentialProposal(proposal) => Some(A2AMessage::CredentialProposal(proposal.clone())),
+            Self::CredentialOffer(offer) => Some(A2AMessage::CredentialOffer(offer.clone())),
+            Self::CredentialRequest(request) => Some(A2AMessage::CredentialRequest(request.clone())),
+            Self::Credential(credential) => Some(A2AMessage::Credential(credential.clone())),
+            Self::CredentialAck(ack) => Some(A2AMessage::CredentialAck(ack.clone())),
+            Self::ProblemReport(report) => Some(A2AMessage::CommonProblemReport(report.clone())),
+            _ => None,
+        }
+    }
 }
 
 impl From<A2AMessage> for CredentialIssuanceAction {
@@ -9,12 +9,13 @@ use crate::libindy::utils::anoncreds::{
 };
 use crate::messages::a2a::{A2AMessage, MessageId};
 use crate::messages::ack::Ack;
-use crate::messages::error::ProblemReport;
+use crate::messages::error::{ProblemCode, ProblemReport};
 use crate::messages::issuance::credential::Credential;
 use crate::messages::issuance::credential_ack::CredentialAck;
 use crate::messages::issuance::credential_offer::CredentialOffer;
 use crate::messages::issuance::credential_proposal::CredentialProposal;
 use crate::messages::issuance::credential_request::CredentialRequest;
+use crate::messages::issuance::CredentialPreviewData;
 use crate::messages::status::Status;
 use crate::protocols::issuance::actions::CredentialIssuanceAction;
 use crate::protocols::issuance::holder::states::finished::FinishedHolderState;
@@ -22,7 +23,7 @@ use crate::protocols::issuance::holder::states::initial::InitialHolderState;
 use crate::protocols::issuance::holder::states::offer_received::OfferReceivedState;
 use crate::protocols::issuance::holder::states::proposal_sent::ProposalSentState;
 use crate::protocols::issuance::holder::states::request_sent::RequestSentState;
-use crate::protocols::issuance::verify_thread_id;
+use crate::protocols::issuance::{verify_thread_id, CredentialNegotiationMessage};
 use crate::protocols::SendClosure;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -79,7 +80,7 @@ impl HolderSM {
 
     pub fn from_offer(offer: CredentialOffer, source_id: String) -> Self {
         HolderSM {
-            thread_id: offer.id.0.clone(),
+            thread_id: offer.get_thread_id(),
             state: HolderFullState::OfferReceived(OfferReceivedState::new(offer)),
             source_id,
         }
@@ -156,6 +157,34 @@ impl HolderSM {
         }
     }
 
+    /// Terminates the issuance in any non-terminal state, the issuer is notified by problem report
+    /// only if `send_message` is provided and some message was already exchanged.
+    pub async fn abandon(self, reason: Option<String>, send_message: Option<SendClosure>) -> VcxResult<HolderSM> {
+        trace!("HolderSM::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+        let negotiation = self.get_negotiation_history();
+        let HolderSM {
+            state,
+            source_id,
+            thread_id,
+        } = self;
+        if let HolderFullState::Finished(_) = state {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidState,
+                "Can not abandon issuance in Finished state",
+            ));
+        }
+        let problem_report =
+            ProblemReport::from_abandonment(ProblemCode::IssuanceAbandoned, reason).set_thread_id(&thread_id);
+        if let Some(send_message) = send_message.filter(|_| !matches!(state, HolderFullState::Initial(_))) {
+            send_message(problem_report.to_a2a_message()).await?;
+        }
+        Ok(HolderSM::step(
+            HolderFullState::Finished(FinishedHolderState::from(problem_report).set_negotiation(negotiation)),
+            source_id,
+            thread_id,
+        ))
+    }
+
     pub async fn handle_message(
         self,
         wallet_handle: WalletHandle,
@@ -184,11 +213,11 @@ impl HolderSM {
             },
             HolderFullState::ProposalSent(state_data) => match cim {
                 CredentialIssuanceAction::CredentialOffer(offer) => {
-                    HolderFullState::OfferReceived(OfferReceivedState::new(offer))
-                }
-                CredentialIssuanceAction::ProblemReport(problem_report) => {
-                    HolderFullState::Finished(problem_report.into())
+                    HolderFullState::OfferReceived((state_data, offer).into())
                 }
+                CredentialIssuanceAction::ProblemReport(problem_report) => HolderFullState::Finished(
+                    FinishedHolderState::from(problem_report).set_negotiation(state_data.negotiation),
+                ),
                 _ => {
                     warn!("Unable to process received message in this state");
                     HolderFullState::ProposalSent(state_data)
@@ -208,15 +237,15 @@ impl HolderSM {
                             HolderFullState::RequestSent((state_data, req_meta, cred_def_json).into())
                         }
                         Err(err) => {
-                            let problem_report = ProblemReport::create()
-                                .set_comment(Some(err.to_string()))
-                                .set_thread_id(&thread_id);
+                            let problem_report = ProblemReport::from_error(&err).set_thread_id(&thread_id);
                             send_message.ok_or(VcxError::from_msg(
                                 VcxErrorKind::InvalidState,
                                 "Attempted to call undefined send_message callback",
                             ))?(problem_report.to_a2a_message())
                             .await?;
-                            HolderFullState::Finished(problem_report.into())
+                            HolderFullState::Finished(
+                                FinishedHolderState::from(problem_report).set_negotiation(state_data.negotiation),
+                            )
                         }
                     }
                 }
@@ -227,16 +256,21 @@ impl HolderSM {
                         "Attempted to call undefined send_message callback",
                     ))?(proposal.to_a2a_message())
                     .await?;
-                    HolderFullState::ProposalSent(ProposalSentState::new(proposal))
+                    HolderFullState::ProposalSent((state_data, proposal).into())
                 }
                 CredentialIssuanceAction::CredentialOfferReject(comment) => {
-                    let problem_report = ProblemReport::create().set_thread_id(&thread_id).set_comment(comment);
+                    let problem_report = ProblemReport::create()
+                        .set_description(ProblemCode::RequestNotAccepted)
+                        .set_thread_id(&thread_id)
+                        .set_comment(comment);
                     send_message.ok_or(VcxError::from_msg(
                         VcxErrorKind::InvalidState,
                         "Attempted to call undefined send_message callback",
                     ))?(problem_report.to_a2a_message())
                     .await?;
-                    HolderFullState::Finished(problem_report.into())
+                    HolderFullState::Finished(
+                        FinishedHolderState::from(problem_report).set_negotiation(state_data.negotiation),
+                    )
                 }
                 _ => {
                     warn!("Unable to process received message in this state");
@@ -250,6 +284,7 @@ impl HolderSM {
                         &credential,
                         &state_data.req_meta,
                         &state_data.cred_def_json,
+                        state_data.credential_preview.as_ref(),
                     )
                     .await;
                     match result {
@@ -265,21 +300,21 @@ impl HolderSM {
                             HolderFullState::Finished((state_data, cred_id, credential, rev_reg_def_json).into())
                         }
                         Err(err) => {
-                            let problem_report = ProblemReport::create()
-                                .set_comment(Some(err.to_string()))
-                                .set_thread_id(&thread_id);
+                            let problem_report = ProblemReport::from_error(&err).set_thread_id(&thread_id);
                             send_message.ok_or(VcxError::from_msg(
                                 VcxErrorKind::InvalidState,
                                 "Attempted to call undefined send_message callback",
                             ))?(problem_report.to_a2a_message())
                             .await?;
-                            HolderFullState::Finished(problem_report.into())
+                            HolderFullState::Finished(
+                                FinishedHolderState::from(problem_report).set_negotiation(state_data.negotiation),
+                            )
                         }
                     }
                 }
-                CredentialIssuanceAction::ProblemReport(problem_report) => {
-                    HolderFullState::Finished(problem_report.into())
-                }
+                CredentialIssuanceAction::ProblemReport(problem_report) => HolderFullState::Finished(
+                    FinishedHolderState::from(problem_report).set_negotiation(state_data.negotiation),
+                ),
                 _ => {
                     warn!("Unable to process received message in this state");
                     HolderFullState::RequestSent(state_data)
@@ -300,6 +335,14 @@ impl HolderSM {
         }
     }
 
+    /// Problem report which failed or declined the protocol, either received or sent by us.
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        match self.state {
+            HolderFullState::Finished(ref state) => state.status.problem_report().cloned(),
+            _ => None,
+        }
+    }
+
     pub fn is_terminal_state(&self) -> bool {
         match self.state {
             HolderFullState::Finished(_) => true,
@@ -393,6 +436,18 @@ impl HolderSM {
         Ok(self.thread_id.clone())
     }
 
+    /// Proposals and offers exchanged before the holder requested the credential, in the order they
+    /// were sent or received. Kept until the issuance is finished.
+    pub fn get_negotiation_history(&self) -> Vec<CredentialNegotiationMessage> {
+        match self.state {
+            HolderFullState::Initial(_) => vec![],
+            HolderFullState::ProposalSent(ref state) => state.negotiation.clone(),
+            HolderFullState::OfferReceived(ref state) => state.negotiation.clone(),
+            HolderFullState::RequestSent(ref state) => state.negotiation.clone(),
+            HolderFullState::Finished(ref state) => state.negotiation.clone(),
+        }
+    }
+
     pub async fn is_revokable(&self, wallet_handle: WalletHandle) -> VcxResult<bool> {
         match self.state {
             HolderFullState::Initial(ref state) => state.is_revokable(),
@@ -466,6 +521,7 @@ async fn _store_credential(
     credential: &Credential,
     req_meta: &str,
     cred_def_json: &str,
+    credential_preview: Option<&CredentialPreviewData>,
 ) -> VcxResult<(String, Option<String>)> {
     trace!(
         "Holder::_store_credential >>> credential: {:?}, req_meta: {}, cred_def_json: {}",
@@ -475,6 +531,9 @@ async fn _store_credential(
     );
 
     let credential_json = credential.credentials_attach.content()?;
+    if let Some(credential_preview) = credential_preview {
+        credential_preview.validate_issued_values(&credential_json)?;
+    }
     let rev_reg_id = _parse_rev_reg_id_from_credential(&credential_json)?;
     let rev_reg_def_json = if let Some(rev_reg_id) = rev_reg_id {
         let (_, json) = anoncreds::get_rev_reg_def_json(&rev_reg_id).await?;
@@ -525,6 +584,12 @@ async fn _make_credential_request(
         my_pw_did,
         offer
     );
+    if offer.is_expired() {
+        return Err(VcxError::from_msg(
+            VcxErrorKind::MessageExpired,
+            format!("Credential offer {} has expired", offer.id.0),
+        ));
+    }
 
     let cred_offer = offer.offers_attach.content()?;
     trace!("Parsed cred offer attachment: {}", cred_offer);
@@ -539,11 +604,14 @@ async fn _make_credential_request(
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod test {
-    use crate::messages::issuance::credential::test_utils::_credential;
+    use chrono::Utc;
+
+    use crate::messages::issuance::credential::test_utils::{_credential, _credential_issued_from_offer};
     use crate::messages::issuance::credential_offer::test_utils::_credential_offer;
-    use crate::messages::issuance::credential_proposal::test_utils::_credential_proposal;
+    use crate::messages::issuance::credential_proposal::test_utils::{_credential_proposal, _credential_proposal_data};
     use crate::messages::issuance::credential_request::test_utils::{_credential_request, _my_pw_did};
     use crate::messages::issuance::test_utils::{_ack, _problem_report};
+    use crate::messages::mime_type::MimeType;
     use crate::test::source_id;
     use crate::utils::constants;
     use crate::utils::devsetup::SetupMocks;
@@ -587,7 +655,7 @@ mod test {
             self = self
                 .handle_message(
                     _dummy_wallet_handle(),
-                    CredentialIssuanceAction::Credential(_credential()),
+                    CredentialIssuanceAction::Credential(_credential_issued_from_offer()),
                     _send_message(),
                 )
                 .await
@@ -660,6 +728,62 @@ mod test {
             assert_match!(HolderFullState::OfferReceived(_), holder_sm.state);
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_holder_negotiation_rounds_retain_history() {
+            let _setup = SetupMocks::init();
+
+            let mut holder_sm = _holder_sm();
+            let thread_id = holder_sm.get_thread_id().unwrap();
+            holder_sm = holder_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialProposalSend(_credential_proposal_data()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(HolderFullState::ProposalSent(_), holder_sm.state);
+            let counter_proposal = holder_sm.get_proposal().unwrap();
+            assert_eq!(counter_proposal.get_thread_id(), thread_id);
+
+            let revised_offer = _credential_offer().set_id("revised_offer").set_thread_id(&thread_id);
+            holder_sm = holder_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialOffer(revised_offer.clone()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(HolderFullState::OfferReceived(_), holder_sm.state);
+            assert_eq!(holder_sm.get_offer().unwrap(), revised_offer);
+            assert_eq!(holder_sm.get_thread_id().unwrap(), thread_id);
+            assert_eq!(
+                holder_sm.get_negotiation_history(),
+                vec![
+                    CredentialNegotiationMessage::Offer(_credential_offer()),
+                    CredentialNegotiationMessage::Proposal(counter_proposal),
+                    CredentialNegotiationMessage::Offer(revised_offer),
+                ]
+            );
+
+            holder_sm = holder_sm.to_request_sent_state().await;
+            assert_match!(HolderFullState::RequestSent(_), holder_sm.state);
+            assert_eq!(holder_sm.get_negotiation_history().len(), 3);
+
+            holder_sm = holder_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::Credential(_credential_issued_from_offer()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(HolderFullState::Finished(_), holder_sm.state);
+            assert_eq!(holder_sm.get_negotiation_history().len(), 3);
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_issuer_handle_credential_request_sent_message_from_offer_received_state() {
@@ -704,6 +828,44 @@ mod test {
             );
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_handle_credential_request_sent_message_from_offer_received_state_for_expired_offer() {
+            let _setup = SetupMocks::init();
+
+            let credential_offer = _credential_offer().set_expires_time(Utc::now() - chrono::Duration::seconds(1));
+            let mut holder_sm = HolderSM::from_offer(credential_offer, source_id());
+            holder_sm = holder_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialRequestSend(_my_pw_did()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(HolderState::Failed, holder_sm.get_state());
+            assert_eq!(
+                Some(ProblemCode::RequestTime),
+                holder_sm.get_problem_report().unwrap().get_code()
+            );
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_holder_abandon_from_request_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut holder_sm = _holder_sm().to_request_sent_state().await;
+            holder_sm = holder_sm.abandon(None, None).await.unwrap();
+
+            assert_eq!(HolderState::Failed, holder_sm.get_state());
+            assert_eq!(
+                Some(ProblemCode::IssuanceAbandoned),
+                holder_sm.get_problem_report().unwrap().get_code()
+            );
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_issuer_handle_other_messages_from_offer_received_state() {
@@ -748,7 +910,7 @@ mod test {
             holder_sm = holder_sm
                 .handle_message(
                     _dummy_wallet_handle(),
-                    CredentialIssuanceAction::Credential(_credential()),
+                    CredentialIssuanceAction::Credential(_credential_issued_from_offer()),
                     _send_message(),
                 )
                 .await
@@ -788,6 +950,41 @@ mod test {
             );
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_handle_credential_not_matching_preview_from_request_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let offer = _credential_offer().set_credential_preview_data(CredentialPreviewData::new().add_value(
+                "name",
+                "Alice",
+                MimeType::Plain,
+            ));
+            let mut holder_sm = HolderSM::from_offer(offer, source_id());
+            holder_sm = holder_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialRequestSend(_my_pw_did()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            holder_sm = holder_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::Credential(_credential()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+
+            assert_match!(HolderFullState::Finished(_), holder_sm.state);
+            assert_eq!(
+                Status::Failed(ProblemReport::default()).code(),
+                holder_sm.credential_status()
+            );
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_issuer_handle_problem_report_from_request_sent_state() {
@@ -871,7 +1068,7 @@ mod test {
             holder_sm = holder_sm
                 .handle_message(
                     _dummy_wallet_handle(),
-                    CredentialIssuanceAction::Credential(_credential()),
+                    CredentialIssuanceAction::Credential(_credential_issued_from_offer()),
                     _send_message(),
                 )
                 .await
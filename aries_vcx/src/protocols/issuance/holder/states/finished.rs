@@ -2,6 +2,7 @@ use crate::error::prelude::*;
 use crate::messages::error::ProblemReport;
 use crate::messages::issuance::credential::{Credential, CredentialData};
 use crate::messages::status::Status;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FinishedHolderState {
@@ -9,9 +10,16 @@ pub struct FinishedHolderState {
     pub credential: Option<Credential>,
     pub status: Status,
     pub rev_reg_def_json: Option<String>,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
 }
 
 impl FinishedHolderState {
+    pub fn set_negotiation(mut self, negotiation: Vec<CredentialNegotiationMessage>) -> Self {
+        self.negotiation = negotiation;
+        self
+    }
+
     pub fn get_attributes(&self) -> VcxResult<String> {
         let attach = self.get_attachment()?;
         let cred_data: CredentialData = serde_json::from_str(&attach).map_err(|err| {
@@ -141,6 +149,7 @@ impl From<ProblemReport> for FinishedHolderState {
             credential: None,
             status: Status::Failed(problem_report),
             rev_reg_def_json: None,
+            negotiation: vec![],
         }
     }
 }
@@ -2,29 +2,50 @@ use indy_sys::WalletHandle;
 
 use crate::error::prelude::*;
 use crate::messages::issuance::credential_offer::CredentialOffer;
+use crate::messages::issuance::credential_proposal::CredentialProposal;
 use crate::protocols::issuance::holder::state_machine::parse_cred_def_id_from_cred_offer;
+use crate::protocols::issuance::holder::states::proposal_sent::ProposalSentState;
 use crate::protocols::issuance::holder::states::request_sent::RequestSentState;
-use crate::protocols::issuance::is_cred_def_revokable;
+use crate::protocols::issuance::{is_cred_def_revokable, CredentialNegotiationMessage};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct OfferReceivedState {
     pub offer: CredentialOffer,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
+}
+
+impl From<(OfferReceivedState, CredentialProposal)> for ProposalSentState {
+    fn from((state, credential_proposal): (OfferReceivedState, CredentialProposal)) -> Self {
+        trace!("SM is now in ProposalSent state");
+        let mut negotiation = state.negotiation;
+        negotiation.push(CredentialNegotiationMessage::Proposal(credential_proposal.clone()));
+        ProposalSentState {
+            credential_proposal,
+            negotiation,
+        }
+    }
 }
 
 impl From<(OfferReceivedState, String, String)> for RequestSentState {
-    fn from((_state, req_meta, cred_def_json): (OfferReceivedState, String, String)) -> Self {
+    fn from((state, req_meta, cred_def_json): (OfferReceivedState, String, String)) -> Self {
         trace!("SM is now in RequestSent state");
         trace!("cred_def_json={:?}", cred_def_json);
         RequestSentState {
             req_meta,
             cred_def_json,
+            credential_preview: Some(state.offer.credential_preview),
+            negotiation: state.negotiation,
         }
     }
 }
 
 impl OfferReceivedState {
     pub fn new(offer: CredentialOffer) -> Self {
-        OfferReceivedState { offer }
+        OfferReceivedState {
+            negotiation: vec![CredentialNegotiationMessage::Offer(offer.clone())],
+            offer,
+        }
     }
 
     pub fn get_attributes(&self) -> VcxResult<String> {
@@ -1,17 +1,33 @@
 use indy_sys::WalletHandle;
 
 use crate::error::prelude::*;
+use crate::messages::issuance::credential_offer::CredentialOffer;
 use crate::messages::issuance::credential_proposal::CredentialProposal;
-use crate::protocols::issuance::is_cred_def_revokable;
+use crate::protocols::issuance::holder::states::offer_received::OfferReceivedState;
+use crate::protocols::issuance::{is_cred_def_revokable, CredentialNegotiationMessage};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProposalSentState {
     pub credential_proposal: CredentialProposal,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
+}
+
+impl From<(ProposalSentState, CredentialOffer)> for OfferReceivedState {
+    fn from((state, offer): (ProposalSentState, CredentialOffer)) -> Self {
+        trace!("SM is now in OfferReceived state");
+        let mut negotiation = state.negotiation;
+        negotiation.push(CredentialNegotiationMessage::Offer(offer.clone()));
+        OfferReceivedState { offer, negotiation }
+    }
 }
 
 impl ProposalSentState {
     pub fn new(credential_proposal: CredentialProposal) -> Self {
-        Self { credential_proposal }
+        Self {
+            negotiation: vec![CredentialNegotiationMessage::Proposal(credential_proposal.clone())],
+            credential_proposal,
+        }
     }
 
     pub async fn is_revokable(&self, wallet_handle: WalletHandle) -> VcxResult<bool> {
@@ -1,17 +1,24 @@
 use crate::error::prelude::*;
 use crate::messages::issuance::credential::Credential;
+use crate::messages::issuance::CredentialPreviewData;
 use crate::messages::status::Status;
 use crate::protocols::issuance::holder::states::finished::FinishedHolderState;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RequestSentState {
     pub req_meta: String,
     pub cred_def_json: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_preview: Option<CredentialPreviewData>,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
 }
 
 impl From<(RequestSentState, String, Credential, Option<String>)> for FinishedHolderState {
     fn from(
-        (_, cred_id, credential, rev_reg_def_json): (RequestSentState, String, Credential, Option<String>),
+        (state, cred_id, credential, rev_reg_def_json): (RequestSentState, String, Credential, Option<String>),
     ) -> Self {
         trace!("SM is now in Finished state");
         FinishedHolderState {
@@ -19,6 +26,7 @@ impl From<(RequestSentState, String, Credential, Option<String>)> for FinishedHo
             credential: Some(credential),
             status: Status::Success,
             rev_reg_def_json,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -1,13 +1,14 @@
 use std::collections::HashMap;
 use std::fmt::Display;
 
+use chrono::{DateTime, Utc};
 use indy_sys::WalletHandle;
 
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
 use crate::libindy::credentials::encode_attributes;
 use crate::libindy::utils::anoncreds;
 use crate::messages::a2a::{A2AMessage, MessageId};
-use crate::messages::error::ProblemReport;
+use crate::messages::error::{ProblemCode, ProblemReport};
 use crate::messages::issuance::credential::Credential;
 use crate::messages::issuance::credential_offer::{CredentialOffer, OfferInfo};
 use crate::messages::issuance::credential_proposal::CredentialProposal;
@@ -22,7 +23,7 @@ use crate::protocols::issuance::issuer::states::offer_sent::OfferSentState;
 use crate::protocols::issuance::issuer::states::offer_set::OfferSetState;
 use crate::protocols::issuance::issuer::states::proposal_received::ProposalReceivedState;
 use crate::protocols::issuance::issuer::states::requested_received::RequestReceivedState;
-use crate::protocols::issuance::verify_thread_id;
+use crate::protocols::issuance::{verify_thread_id, CredentialNegotiationMessage};
 use crate::protocols::SendClosure;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -289,6 +290,15 @@ impl IssuerSM {
         }
     }
 
+    /// Whether the received credential request asks for credential of the offered credential
+    /// definition. Only meaningful in RequestReceived state, false otherwise.
+    pub fn is_request_matching_offer(&self) -> VcxResult<bool> {
+        match &self.state {
+            IssuerFullState::RequestReceived(state) => state.is_request_matching_offer(),
+            _ => Ok(false),
+        }
+    }
+
     pub fn get_proposal(&self) -> VcxResult<CredentialProposal> {
         match &self.state {
             IssuerFullState::ProposalReceived(state) => Ok(state.credential_proposal.clone()),
@@ -299,6 +309,20 @@ impl IssuerSM {
         }
     }
 
+    /// Proposals and offers exchanged before the holder requested the credential, in the order they
+    /// were sent or received. Kept until the issuance is finished.
+    pub fn get_negotiation_history(&self) -> Vec<CredentialNegotiationMessage> {
+        match &self.state {
+            IssuerFullState::Initial(_) => vec![],
+            IssuerFullState::ProposalReceived(state) => state.negotiation.clone(),
+            IssuerFullState::OfferSet(state) => state.negotiation.clone(),
+            IssuerFullState::OfferSent(state) => state.negotiation.clone(),
+            IssuerFullState::RequestReceived(state) => state.negotiation.clone(),
+            IssuerFullState::CredentialSent(state) => state.negotiation.clone(),
+            IssuerFullState::Finished(state) => state.negotiation.clone(),
+        }
+    }
+
     pub fn build_credential_offer_msg(
         self,
         credential_offer: &str,
@@ -311,17 +335,14 @@ impl IssuerSM {
             source_id,
             thread_id,
         } = self;
-        let state = match state {
-            IssuerFullState::Initial(_) | IssuerFullState::OfferSet(_) | IssuerFullState::ProposalReceived(_) => {
-                let cred_offer_msg = build_credential_offer(&thread_id, credential_offer, credential_preview, comment)?;
-                IssuerFullState::OfferSet(OfferSetState::new(
-                    cred_offer_msg,
-                    &offer_info.credential_json,
-                    &offer_info.cred_def_id,
-                    offer_info.rev_reg_id.clone(),
-                    offer_info.tails_file.clone(),
-                ))
+        let negotiation = match state {
+            IssuerFullState::Initial(_) => vec![],
+            IssuerFullState::OfferSet(state) => {
+                let mut negotiation = state.negotiation;
+                negotiation.pop();
+                negotiation
             }
+            IssuerFullState::ProposalReceived(state) => state.negotiation,
             _ => {
                 return Err(VcxError::from_msg(
                     VcxErrorKind::InvalidState,
@@ -329,9 +350,53 @@ impl IssuerSM {
                 ));
             }
         };
+        let mut cred_offer_msg = build_credential_offer(&thread_id, credential_offer, credential_preview, comment)?;
+        if !negotiation.is_empty() {
+            // offer responding to a proposal is a new message within the negotiated thread
+            cred_offer_msg = cred_offer_msg.set_id(&MessageId::new().0).set_thread_id(&thread_id);
+        }
+        let state = IssuerFullState::OfferSet(
+            OfferSetState::new(
+                cred_offer_msg,
+                &offer_info.credential_json,
+                &offer_info.cred_def_id,
+                offer_info.rev_reg_id.clone(),
+                offer_info.tails_file.clone(),
+            )
+            .set_negotiation(negotiation),
+        );
         Ok(Self::step(source_id, thread_id, state))
     }
 
+    pub fn set_offer_expiration(self, expires_time: DateTime<Utc>) -> VcxResult<Self> {
+        let Self {
+            state,
+            source_id,
+            thread_id,
+        } = self;
+        let state = match state {
+            IssuerFullState::OfferSet(mut state) => {
+                state.offer = state.offer.set_expires_time(expires_time);
+                IssuerFullState::OfferSet(state)
+            }
+            _ => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidState,
+                    format!("Can not set offer expiration in current state {}.", state),
+                ));
+            }
+        };
+        Ok(Self::step(source_id, thread_id, state))
+    }
+
+    /// Whether the holder did not respond to our offer until its expiration time.
+    pub fn is_expired(&self) -> bool {
+        match &self.state {
+            IssuerFullState::OfferSent(state) => state.offer.is_expired(),
+            _ => false,
+        }
+    }
+
     pub fn get_credential_offer_msg(&self) -> VcxResult<CredentialOffer> {
         match &self.state {
             IssuerFullState::OfferSet(state) => Ok(state.offer.clone()),
@@ -362,6 +427,40 @@ impl IssuerSM {
         Ok(Self::step(source_id, thread_id, state))
     }
 
+    /// Terminates the issuance in any non-terminal state, the holder is notified by problem report
+    /// only if `send_message` is provided and the offer was already sent.
+    pub async fn abandon(self, reason: Option<String>, send_message: Option<SendClosure>) -> VcxResult<Self> {
+        trace!("IssuerSM::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+        let negotiation = self.get_negotiation_history();
+        let Self {
+            state,
+            source_id,
+            thread_id,
+        } = self;
+        let problem_report =
+            ProblemReport::from_abandonment(ProblemCode::IssuanceAbandoned, reason).set_thread_id(&thread_id);
+        let send_message =
+            send_message.filter(|_| !matches!(state, IssuerFullState::Initial(_) | IssuerFullState::OfferSet(_)));
+        let state = match state {
+            IssuerFullState::Finished(_) => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidState,
+                    format!("Can not abandon issuance in current state {}.", state),
+                ));
+            }
+            IssuerFullState::OfferSent(state) => IssuerFullState::Finished((state, problem_report.clone()).into()),
+            IssuerFullState::RequestReceived(state) => {
+                IssuerFullState::Finished((state, problem_report.clone()).into())
+            }
+            IssuerFullState::CredentialSent(state) => IssuerFullState::Finished((state, problem_report.clone()).into()),
+            _ => IssuerFullState::Finished(FinishedState::abandoned(problem_report.clone(), negotiation)),
+        };
+        if let Some(send_message) = send_message {
+            send_message(problem_report.to_a2a_message()).await?;
+        }
+        Ok(Self::step(source_id, thread_id, state))
+    }
+
     pub async fn handle_message(
         self,
         wallet_handle: WalletHandle,
@@ -395,12 +494,30 @@ impl IssuerSM {
                 (IssuerFullState::ProposalReceived(state_data), thread_id)
             }
             IssuerFullState::OfferSent(state_data) => match cim {
+                CredentialIssuanceAction::CredentialRequest(_) | CredentialIssuanceAction::CredentialOfferExpired
+                    if state_data.offer.is_expired() =>
+                {
+                    let err = VcxError::from_msg(
+                        VcxErrorKind::MessageExpired,
+                        "Credential offer has expired before credential request was received",
+                    );
+                    let problem_report = ProblemReport::from_error(&err).set_thread_id(&thread_id);
+                    send_message.ok_or(VcxError::from_msg(
+                        VcxErrorKind::InvalidState,
+                        "Attempted to call undefined send_message callback",
+                    ))?(problem_report.to_a2a_message())
+                    .await?;
+                    (
+                        IssuerFullState::Finished((state_data, problem_report).into()),
+                        thread_id,
+                    )
+                }
                 CredentialIssuanceAction::CredentialRequest(request) => (
                     IssuerFullState::RequestReceived((state_data, request).into()),
                     thread_id,
                 ),
                 CredentialIssuanceAction::CredentialProposal(proposal) => (
-                    IssuerFullState::ProposalReceived(ProposalReceivedState::new(proposal, None)),
+                    IssuerFullState::ProposalReceived((state_data, proposal).into()),
                     thread_id,
                 ),
                 CredentialIssuanceAction::ProblemReport(problem_report) => (
@@ -438,9 +555,7 @@ impl IssuerSM {
                             )
                         }
                         Err(err) => {
-                            let problem_report = ProblemReport::create()
-                                .set_comment(Some(err.to_string()))
-                                .set_thread_id(&thread_id);
+                            let problem_report = ProblemReport::from_error(&err).set_thread_id(&thread_id);
 
                             send_message.ok_or(VcxError::from_msg(
                                 VcxErrorKind::InvalidState,
@@ -460,9 +575,12 @@ impl IssuerSM {
                 }
             },
             IssuerFullState::CredentialSent(state_data) => match cim {
-                CredentialIssuanceAction::ProblemReport(_problem_report) => {
+                CredentialIssuanceAction::ProblemReport(problem_report) => {
                     info!("Interaction closed with failure");
-                    (IssuerFullState::Finished(state_data.into()), thread_id)
+                    (
+                        IssuerFullState::Finished((state_data, problem_report).into()),
+                        thread_id,
+                    )
                 }
                 CredentialIssuanceAction::CredentialAck(_ack) => {
                     info!("Interaction closed with success");
@@ -495,6 +613,14 @@ impl IssuerSM {
         }
     }
 
+    /// Problem report which failed or declined the protocol, either received or sent by us.
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        match self.state {
+            IssuerFullState::Finished(ref state) => state.status.problem_report().cloned(),
+            _ => None,
+        }
+    }
+
     pub fn is_terminal_state(&self) -> bool {
         match self.state {
             IssuerFullState::Finished(_) => true,
@@ -546,8 +672,11 @@ async fn _create_credential(
 #[cfg(feature = "general_test")]
 pub mod unit_tests {
     use crate::messages::a2a::A2AMessage;
+    use crate::messages::error::ProblemCode;
     use crate::messages::issuance::credential::test_utils::_credential;
-    use crate::messages::issuance::credential_offer::test_utils::{_credential_offer, _offer_info};
+    use crate::messages::issuance::credential_offer::test_utils::{
+        _attachment as _offer_attachment, _credential_offer, _offer_info,
+    };
     use crate::messages::issuance::credential_proposal::test_utils::_credential_proposal;
     use crate::messages::issuance::credential_request::test_utils::{_credential_request, _credential_request_1};
     use crate::messages::issuance::test_utils::{_ack, _problem_report};
@@ -815,6 +944,36 @@ pub mod unit_tests {
             assert_match!(IssuerFullState::ProposalReceived(_), issuer_sm.state);
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_request_matching_offer() {
+            let _setup = SetupMocks::init();
+
+            let issuer_sm = _issuer_sm().to_request_received_state().await;
+            assert!(!issuer_sm.is_request_matching_offer().unwrap());
+
+            let issuer_sm = _issuer_sm()
+                .build_credential_offer_msg(
+                    &_offer_attachment().to_string(),
+                    CredentialPreviewData::new(),
+                    None,
+                    &_offer_info(),
+                )
+                .unwrap()
+                .mark_credential_offer_msg_sent()
+                .unwrap();
+            assert!(!issuer_sm.is_request_matching_offer().unwrap());
+            let issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialRequest(_credential_request()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert!(issuer_sm.is_request_matching_offer().unwrap());
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_issuer_handle_credential_request_message_from_offer_sent_state() {
@@ -853,6 +1012,103 @@ pub mod unit_tests {
             assert_match!(IssuerFullState::ProposalReceived(_), issuer_sm.state);
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_negotiation_rounds_retain_history() {
+            let _setup = SetupMocks::init();
+
+            let mut issuer_sm = _issuer_sm_from_proposal().to_offer_sent_state();
+            let thread_id = issuer_sm.thread_id().unwrap();
+            let first_offer = issuer_sm.get_credential_offer_msg().unwrap();
+            assert_eq!(first_offer.get_thread_id(), thread_id);
+
+            let counter_proposal = _credential_proposal().set_thread_id(&thread_id);
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialProposal(counter_proposal.clone()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(IssuerFullState::ProposalReceived(_), issuer_sm.state);
+            assert_eq!(issuer_sm.get_proposal().unwrap(), counter_proposal);
+
+            issuer_sm = issuer_sm.to_offer_sent_state();
+            let revised_offer = issuer_sm.get_credential_offer_msg().unwrap();
+            assert_ne!(revised_offer.id, first_offer.id);
+            assert_eq!(revised_offer.get_thread_id(), thread_id);
+            assert_eq!(issuer_sm.thread_id().unwrap(), thread_id);
+            assert_eq!(
+                issuer_sm.get_negotiation_history(),
+                vec![
+                    CredentialNegotiationMessage::Proposal(_credential_proposal()),
+                    CredentialNegotiationMessage::Offer(first_offer),
+                    CredentialNegotiationMessage::Proposal(counter_proposal),
+                    CredentialNegotiationMessage::Offer(revised_offer),
+                ]
+            );
+
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialRequest(_credential_request()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(IssuerFullState::RequestReceived(_), issuer_sm.state);
+            assert_eq!(issuer_sm.get_negotiation_history().len(), 4);
+
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialSend(),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialAck(_ack()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(IssuerFullState::Finished(_), issuer_sm.state);
+            assert_eq!(issuer_sm.get_negotiation_history().len(), 4);
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_handle_problem_report_message_from_credential_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut issuer_sm = _issuer_sm().to_request_received_state().await;
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialSend(),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert!(issuer_sm.get_problem_report().is_none());
+
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::ProblemReport(_problem_report()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(IssuerState::Failed, issuer_sm.get_state());
+            assert_eq!(Some(_problem_report()), issuer_sm.get_problem_report());
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_issuer_handle_problem_report_message_from_offer_sent_state() {
@@ -876,6 +1132,103 @@ pub mod unit_tests {
             );
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_handle_expired_offer_from_offer_sent_state() {
+            let _setup = SetupMocks::init();
+
+            fn _offer_sent_state(expires_time: DateTime<Utc>) -> IssuerSM {
+                _issuer_sm()
+                    .build_credential_offer_msg(LIBINDY_CRED_OFFER, CredentialPreviewData::new(), None, &_offer_info())
+                    .unwrap()
+                    .set_offer_expiration(expires_time)
+                    .unwrap()
+                    .mark_credential_offer_msg_sent()
+                    .unwrap()
+            }
+
+            assert!(!_offer_sent_state(Utc::now() + chrono::Duration::hours(1)).is_expired());
+            let mut issuer_sm = _offer_sent_state(Utc::now() - chrono::Duration::seconds(1));
+            assert!(issuer_sm.is_expired());
+
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialOfferExpired,
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(IssuerState::Failed, issuer_sm.get_state());
+            assert_eq!(
+                Some(ProblemCode::RequestTime),
+                issuer_sm.get_problem_report().unwrap().get_code()
+            );
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_handle_credential_request_after_offer_expired() {
+            let _setup = SetupMocks::init();
+
+            let mut issuer_sm = _issuer_sm()
+                .build_credential_offer_msg(LIBINDY_CRED_OFFER, CredentialPreviewData::new(), None, &_offer_info())
+                .unwrap()
+                .set_offer_expiration(Utc::now() - chrono::Duration::seconds(1))
+                .unwrap()
+                .mark_credential_offer_msg_sent()
+                .unwrap();
+            issuer_sm = issuer_sm
+                .handle_message(
+                    _dummy_wallet_handle(),
+                    CredentialIssuanceAction::CredentialRequest(_credential_request()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(IssuerState::Failed, issuer_sm.get_state());
+            assert_eq!(
+                Some(ProblemCode::RequestTime),
+                issuer_sm.get_problem_report().unwrap().get_code()
+            );
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_abandon_from_offer_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut issuer_sm = _issuer_sm();
+            issuer_sm = issuer_sm.to_offer_sent_state();
+            issuer_sm = issuer_sm
+                .abandon(Some("No longer needed".to_string()), _send_message())
+                .await
+                .unwrap();
+
+            assert_eq!(IssuerState::Failed, issuer_sm.get_state());
+            let problem_report = issuer_sm.get_problem_report().unwrap();
+            assert_eq!(Some(ProblemCode::IssuanceAbandoned), problem_report.get_code());
+            assert_eq!(Some("No longer needed".to_string()), problem_report.comment);
+
+            let err = issuer_sm.abandon(None, None).await.unwrap_err();
+            assert_eq!(VcxErrorKind::InvalidState, err.kind());
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_issuer_abandon_from_initial_state_does_not_notify_holder() {
+            let _setup = SetupMocks::init();
+
+            let send_message: Option<SendClosure> = Some(Box::new(|_: A2AMessage| {
+                Box::pin(async { Err(VcxError::from(VcxErrorKind::IOError)) })
+            }));
+            let issuer_sm = _issuer_sm().abandon(None, send_message).await.unwrap();
+
+            assert_eq!(IssuerState::Failed, issuer_sm.get_state());
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_issuer_handle_other_messages_from_offer_sent_state() {
@@ -1,10 +1,14 @@
+use crate::messages::error::ProblemReport;
 use crate::messages::status::Status;
 use crate::protocols::issuance::issuer::state_machine::RevocationInfoV1;
 use crate::protocols::issuance::issuer::states::finished::FinishedState;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CredentialSentState {
     pub revocation_info_v1: Option<RevocationInfoV1>,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
 }
 
 impl From<CredentialSentState> for FinishedState {
@@ -14,6 +18,19 @@ impl From<CredentialSentState> for FinishedState {
             cred_id: None,
             revocation_info_v1: state.revocation_info_v1,
             status: Status::Success,
+            negotiation: state.negotiation,
+        }
+    }
+}
+
+impl From<(CredentialSentState, ProblemReport)> for FinishedState {
+    fn from((state, err): (CredentialSentState, ProblemReport)) -> Self {
+        trace!("SM is now in Finished state");
+        FinishedState {
+            cred_id: None,
+            revocation_info_v1: state.revocation_info_v1,
+            status: Status::Failed(err),
+            negotiation: state.negotiation,
         }
     }
 }
@@ -1,9 +1,25 @@
+use crate::messages::error::ProblemReport;
 use crate::messages::status::Status;
 use crate::protocols::issuance::issuer::state_machine::RevocationInfoV1;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FinishedState {
     pub cred_id: Option<String>,
     pub revocation_info_v1: Option<RevocationInfoV1>,
     pub status: Status,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
+}
+
+impl FinishedState {
+    pub fn abandoned(problem_report: ProblemReport, negotiation: Vec<CredentialNegotiationMessage>) -> Self {
+        trace!("SM is now in Finished state");
+        FinishedState {
+            cred_id: None,
+            revocation_info_v1: None,
+            status: Status::Failed(problem_report),
+            negotiation,
+        }
+    }
 }
@@ -1,10 +1,13 @@
 use crate::messages::error::ProblemReport;
 use crate::messages::issuance::credential_offer::CredentialOffer;
+use crate::messages::issuance::credential_proposal::CredentialProposal;
 use crate::messages::issuance::credential_request::CredentialRequest;
 use crate::messages::status::Status;
 use crate::protocols::issuance::issuer::state_machine::RevocationInfoV1;
 use crate::protocols::issuance::issuer::states::finished::FinishedState;
+use crate::protocols::issuance::issuer::states::proposal_received::ProposalReceivedState;
 use crate::protocols::issuance::issuer::states::requested_received::RequestReceivedState;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OfferSentState {
@@ -12,6 +15,21 @@ pub struct OfferSentState {
     pub cred_data: String,
     pub rev_reg_id: Option<String>,
     pub tails_file: Option<String>,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
+}
+
+impl From<(OfferSentState, CredentialProposal)> for ProposalReceivedState {
+    fn from((state, credential_proposal): (OfferSentState, CredentialProposal)) -> Self {
+        trace!("SM is now in ProposalReceived state");
+        let mut negotiation = state.negotiation;
+        negotiation.push(CredentialNegotiationMessage::Proposal(credential_proposal.clone()));
+        ProposalReceivedState {
+            credential_proposal,
+            offer_info: None,
+            negotiation,
+        }
+    }
 }
 
 impl From<OfferSentState> for FinishedState {
@@ -25,6 +43,7 @@ impl From<OfferSentState> for FinishedState {
                 tails_file: state.tails_file,
             }),
             status: Status::Undefined,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -38,6 +57,7 @@ impl From<(OfferSentState, CredentialRequest)> for RequestReceivedState {
             rev_reg_id: state.rev_reg_id,
             tails_file: state.tails_file,
             request,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -53,6 +73,7 @@ impl From<(OfferSentState, ProblemReport)> for FinishedState {
                 tails_file: state.tails_file,
             }),
             status: Status::Failed(err),
+            negotiation: state.negotiation,
         }
     }
 }
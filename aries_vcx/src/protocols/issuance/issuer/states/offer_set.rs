@@ -1,5 +1,6 @@
 use crate::messages::issuance::credential_offer::CredentialOffer;
 use crate::protocols::issuance::issuer::states::offer_sent::OfferSentState;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct OfferSetState {
@@ -8,6 +9,8 @@ pub struct OfferSetState {
     pub cred_def_id: String,
     pub rev_reg_id: Option<String>,
     pub tails_file: Option<String>,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
 }
 
 impl OfferSetState {
@@ -24,8 +27,17 @@ impl OfferSetState {
             cred_def_id: cred_def_id.into(),
             rev_reg_id,
             tails_file,
+            negotiation: vec![],
         }
     }
+
+    /// Sets messages exchanged in previous negotiation rounds, the offer itself is appended as
+    /// the latest round.
+    pub fn set_negotiation(mut self, mut negotiation: Vec<CredentialNegotiationMessage>) -> Self {
+        negotiation.push(CredentialNegotiationMessage::Offer(self.offer.clone()));
+        self.negotiation = negotiation;
+        self
+    }
 }
 
 impl From<OfferSetState> for OfferSentState {
@@ -36,6 +48,7 @@ impl From<OfferSetState> for OfferSentState {
             cred_data: state.credential_json,
             rev_reg_id: state.rev_reg_id,
             tails_file: state.tails_file,
+            negotiation: state.negotiation,
         }
     }
 }
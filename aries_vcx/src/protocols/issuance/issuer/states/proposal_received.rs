@@ -1,16 +1,20 @@
 use crate::messages::issuance::credential_offer::{CredentialOffer, OfferInfo};
 use crate::messages::issuance::credential_proposal::CredentialProposal;
 use crate::protocols::issuance::issuer::states::offer_sent::OfferSentState;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProposalReceivedState {
     pub credential_proposal: CredentialProposal,
     pub offer_info: Option<OfferInfo>,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
 }
 
 impl ProposalReceivedState {
     pub fn new(credential_proposal: CredentialProposal, offer_info: Option<OfferInfo>) -> Self {
         Self {
+            negotiation: vec![CredentialNegotiationMessage::Proposal(credential_proposal.clone())],
             credential_proposal,
             offer_info,
         }
@@ -21,6 +25,7 @@ impl From<(CredentialOffer, OfferInfo)> for OfferSentState {
     fn from((offer, offer_info): (CredentialOffer, OfferInfo)) -> Self {
         trace!("SM is now in OfferSent state");
         OfferSentState {
+            negotiation: vec![CredentialNegotiationMessage::Offer(offer.clone())],
             offer,
             cred_data: offer_info.credential_json,
             rev_reg_id: offer_info.rev_reg_id,
@@ -1,3 +1,6 @@
+use serde_json::Value;
+
+use crate::error::prelude::*;
 use crate::messages::error::ProblemReport;
 use crate::messages::issuance::credential_offer::CredentialOffer;
 use crate::messages::issuance::credential_request::CredentialRequest;
@@ -5,6 +8,7 @@ use crate::messages::status::Status;
 use crate::protocols::issuance::issuer::state_machine::RevocationInfoV1;
 use crate::protocols::issuance::issuer::states::credential_sent::CredentialSentState;
 use crate::protocols::issuance::issuer::states::finished::FinishedState;
+use crate::protocols::issuance::CredentialNegotiationMessage;
 
 // TODO: Use OfferInfo instead of ind. fields
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -14,6 +18,26 @@ pub struct RequestReceivedState {
     pub rev_reg_id: Option<String>,
     pub tails_file: Option<String>,
     pub request: CredentialRequest,
+    #[serde(default)]
+    pub negotiation: Vec<CredentialNegotiationMessage>,
+}
+
+impl RequestReceivedState {
+    /// Whether the holder requested credential of the credential definition we offered.
+    pub fn is_request_matching_offer(&self) -> VcxResult<bool> {
+        let offer = _parse_attachment(&self.offer.offers_attach.content()?)?;
+        let request = _parse_attachment(&self.request.requests_attach.content()?)?;
+        Ok(!offer["cred_def_id"].is_null() && offer["cred_def_id"] == request["cred_def_id"])
+    }
+}
+
+fn _parse_attachment(attachment: &str) -> VcxResult<Value> {
+    serde_json::from_str(attachment).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!("Cannot deserialize attachment {}: {}", attachment, err),
+        )
+    })
 }
 
 impl From<(RequestReceivedState, Option<String>)> for CredentialSentState {
@@ -25,6 +49,7 @@ impl From<(RequestReceivedState, Option<String>)> for CredentialSentState {
                 rev_reg_id: state.rev_reg_id,
                 tails_file: state.tails_file,
             }),
+            negotiation: state.negotiation,
         }
     }
 }
@@ -40,6 +65,7 @@ impl From<(RequestReceivedState, Option<String>)> for FinishedState {
                 tails_file: state.tails_file,
             }),
             status: Status::Success,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -55,6 +81,7 @@ impl From<(RequestReceivedState, ProblemReport)> for FinishedState {
                 tails_file: state.tails_file,
             }),
             status: Status::Failed(err),
+            negotiation: state.negotiation,
         }
     }
 }
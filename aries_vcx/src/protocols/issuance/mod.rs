@@ -2,13 +2,24 @@ use indy_sys::WalletHandle;
 
 use crate::error::prelude::*;
 use crate::global::settings;
-use crate::libindy::utils::anoncreds::get_cred_def_json;
+use crate::libindy::utils::anoncreds::{get_cred_def_json, get_schema_json};
+use crate::messages::issuance::credential_offer::CredentialOffer;
+use crate::messages::issuance::credential_proposal::CredentialProposal;
+use crate::messages::issuance::CredentialPreviewData;
 use crate::protocols::issuance::actions::CredentialIssuanceAction;
 
 pub mod actions;
 pub mod holder;
 pub mod issuer;
 
+/// Proposal or offer exchanged in one round of negotiating the credential, before the holder
+/// commits to it by sending credential request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CredentialNegotiationMessage {
+    Proposal(CredentialProposal),
+    Offer(CredentialOffer),
+}
+
 pub fn verify_thread_id(thread_id: &str, message: &CredentialIssuanceAction) -> VcxResult<()> {
     if !settings::indy_mocks_enabled() && !message.thread_id_matches(thread_id) {
         return Err(VcxError::from_msg(
@@ -40,3 +51,26 @@ pub async fn is_cred_def_revokable(wallet_handle: WalletHandle, cred_def_id: &st
     })?;
     Ok(!parsed_cred_def["value"]["revocation"].is_null())
 }
+
+/// Checks the credential preview against the schema of the credential definition the libindy
+/// credential offer was created for.
+pub async fn validate_credential_preview(
+    wallet_handle: WalletHandle,
+    libindy_cred_offer: &str,
+    credential_preview: &CredentialPreviewData,
+) -> VcxResult<()> {
+    if settings::indy_mocks_enabled() {
+        return Ok(());
+    }
+    let parsed_offer: serde_json::Value = serde_json::from_str(libindy_cred_offer).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!("Failed deserialize credential offer json: {}", err),
+        )
+    })?;
+    let schema_id = parsed_offer["schema_id"]
+        .as_str()
+        .ok_or_else(|| VcxError::from_msg(VcxErrorKind::InvalidJson, "Credential offer does not contain schema_id"))?;
+    let (_, schema_json) = get_schema_json(wallet_handle, schema_id).await?;
+    credential_preview.validate_against_schema(&schema_json)
+}
@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+
+use crate::error::{VcxError, VcxErrorKind, VcxResult};
+use crate::handlers::util::verify_thread_id;
+use crate::messages::a2a::A2AMessage;
+use crate::messages::coordinate_mediation::keylist::Keylist;
+use crate::messages::coordinate_mediation::keylist_query::KeylistQuery;
+use crate::messages::coordinate_mediation::keylist_update::{KeylistUpdate, KeylistUpdateAction};
+use crate::messages::coordinate_mediation::keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResult};
+use crate::messages::coordinate_mediation::mediate_deny::MediateDeny;
+use crate::messages::coordinate_mediation::mediate_grant::MediateGrant;
+use crate::messages::coordinate_mediation::mediate_request::MediateRequest;
+use crate::protocols::SendClosure;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestSentState {
+    pub request: MediateRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GrantedState {
+    pub grant: MediateGrant,
+    pub keys: Vec<String>,
+    pub pending_thread_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeniedState {
+    pub deny: MediateDeny,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MediationRecipientFullState {
+    Initial,
+    RequestSent(RequestSentState),
+    Granted(GrantedState),
+    Denied(DeniedState),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MediationRecipientState {
+    Initial,
+    RequestSent,
+    Granted,
+    Denied,
+}
+
+impl Default for MediationRecipientFullState {
+    fn default() -> Self {
+        Self::Initial
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MediationRecipientSM {
+    source_id: String,
+    thread_id: String,
+    state: MediationRecipientFullState,
+}
+
+impl MediationRecipientSM {
+    pub fn new(source_id: &str) -> Self {
+        Self {
+            source_id: source_id.to_string(),
+            thread_id: String::new(),
+            state: MediationRecipientFullState::Initial,
+        }
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn get_thread_id(&self) -> String {
+        self.thread_id.clone()
+    }
+
+    pub fn get_state(&self) -> MediationRecipientState {
+        match self.state {
+            MediationRecipientFullState::Initial => MediationRecipientState::Initial,
+            MediationRecipientFullState::RequestSent(_) => MediationRecipientState::RequestSent,
+            MediationRecipientFullState::Granted(_) => MediationRecipientState::Granted,
+            MediationRecipientFullState::Denied(_) => MediationRecipientState::Denied,
+        }
+    }
+
+    fn granted_state(&self) -> VcxResult<&GrantedState> {
+        match &self.state {
+            MediationRecipientFullState::Granted(state) => Ok(state),
+            _ => Err(VcxError::from_msg(
+                VcxErrorKind::NotReady,
+                "Mediation has not been granted",
+            )),
+        }
+    }
+
+    pub fn get_routing_keys(&self) -> VcxResult<Vec<String>> {
+        Ok(self.granted_state()?.grant.routing_keys.clone())
+    }
+
+    pub fn get_endpoint(&self) -> VcxResult<String> {
+        Ok(self.granted_state()?.grant.endpoint.clone())
+    }
+
+    pub fn get_keys(&self) -> VcxResult<Vec<String>> {
+        Ok(self.granted_state()?.keys.clone())
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
+        trace!(
+            "MediationRecipientSM::find_message_to_handle >>> messages: {:?}, state: {:?}",
+            messages,
+            self.state
+        );
+        for (uid, message) in messages {
+            match &self.state {
+                MediationRecipientFullState::RequestSent(_) => match message {
+                    A2AMessage::MediateGrant(ref grant) if grant.from_thread(&self.thread_id) => {
+                        return Some((uid, message));
+                    }
+                    A2AMessage::MediateDeny(ref deny) if deny.from_thread(&self.thread_id) => {
+                        return Some((uid, message));
+                    }
+                    _ => {}
+                },
+                MediationRecipientFullState::Granted(state) => match message {
+                    A2AMessage::KeylistUpdateResponse(ref response)
+                        if state.pending_thread_ids.contains(&response.get_thread_id()) =>
+                    {
+                        return Some((uid, message));
+                    }
+                    A2AMessage::Keylist(ref keylist) if state.pending_thread_ids.contains(&keylist.get_thread_id()) => {
+                        return Some((uid, message));
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub async fn send_mediation_request(self, send_message: SendClosure) -> VcxResult<Self> {
+        trace!("MediationRecipientSM::send_mediation_request >>>");
+        let Self { source_id, state, .. } = self;
+        match state {
+            MediationRecipientFullState::Initial | MediationRecipientFullState::Denied(_) => {
+                let request = MediateRequest::create().set_out_time();
+                send_message(request.to_a2a_message()).await?;
+                Ok(Self {
+                    source_id,
+                    thread_id: request.id.0.clone(),
+                    state: MediationRecipientFullState::RequestSent(RequestSentState { request }),
+                })
+            }
+            _ => Err(VcxError::from_msg(
+                VcxErrorKind::NotReady,
+                "Mediation request can only be sent from Initial or Denied state",
+            )),
+        }
+    }
+
+    pub async fn send_keylist_update(
+        self,
+        add: Vec<String>,
+        remove: Vec<String>,
+        send_message: SendClosure,
+    ) -> VcxResult<Self> {
+        trace!(
+            "MediationRecipientSM::send_keylist_update >>> add: {:?}, remove: {:?}",
+            add,
+            remove
+        );
+        let mut state = self.granted_state()?.clone();
+        let update = add
+            .iter()
+            .fold(KeylistUpdate::create(), |update, key| update.add_key(key));
+        let update = remove
+            .iter()
+            .fold(update, |update, key| update.remove_key(key))
+            .set_out_time();
+        send_message(update.to_a2a_message()).await?;
+        state.pending_thread_ids.push(update.id.0.clone());
+        Ok(Self {
+            state: MediationRecipientFullState::Granted(state),
+            ..self
+        })
+    }
+
+    pub async fn send_keylist_query(self, paginate: Option<(u32, u32)>, send_message: SendClosure) -> VcxResult<Self> {
+        trace!("MediationRecipientSM::send_keylist_query >>> paginate: {:?}", paginate);
+        let mut state = self.granted_state()?.clone();
+        let query = match paginate {
+            Some((limit, offset)) => KeylistQuery::create().set_paginate(limit, offset),
+            None => KeylistQuery::create(),
+        }
+        .set_out_time();
+        send_message(query.to_a2a_message()).await?;
+        state.pending_thread_ids.push(query.id.0.clone());
+        Ok(Self {
+            state: MediationRecipientFullState::Granted(state),
+            ..self
+        })
+    }
+
+    pub fn handle_message(self, message: A2AMessage) -> VcxResult<Self> {
+        trace!("MediationRecipientSM::handle_message >>> message: {:?}", message);
+        let Self {
+            source_id,
+            thread_id,
+            state,
+        } = self;
+        let from_thread = match &state {
+            MediationRecipientFullState::RequestSent(_) => verify_thread_id(&thread_id, &message).is_ok(),
+            MediationRecipientFullState::Granted(state_data) => state_data
+                .pending_thread_ids
+                .iter()
+                .any(|pending_thread_id| message.thread_id_matches(pending_thread_id)),
+            _ => true,
+        };
+        if !from_thread {
+            warn!("Ignoring mediation message {:?} from unexpected thread", message);
+            return Ok(Self {
+                source_id,
+                thread_id,
+                state,
+            });
+        }
+        let state = match state {
+            MediationRecipientFullState::RequestSent(state_data) => match message {
+                A2AMessage::MediateGrant(grant) => MediationRecipientFullState::Granted(GrantedState {
+                    grant,
+                    keys: vec![],
+                    pending_thread_ids: vec![],
+                }),
+                A2AMessage::MediateDeny(deny) => MediationRecipientFullState::Denied(DeniedState { deny }),
+                _ => {
+                    warn!("Unable to process received message in RequestSent state");
+                    MediationRecipientFullState::RequestSent(state_data)
+                }
+            },
+            MediationRecipientFullState::Granted(state_data) => match message {
+                A2AMessage::KeylistUpdateResponse(response) => {
+                    MediationRecipientFullState::Granted(_apply_keylist_update_response(state_data, &response))
+                }
+                A2AMessage::Keylist(keylist) => {
+                    MediationRecipientFullState::Granted(_apply_keylist(state_data, &keylist))
+                }
+                _ => {
+                    warn!("Unable to process received message in Granted state");
+                    MediationRecipientFullState::Granted(state_data)
+                }
+            },
+            state => {
+                warn!("Unable to process received message in state {:?}", state);
+                state
+            }
+        };
+        Ok(Self {
+            source_id,
+            thread_id,
+            state,
+        })
+    }
+}
+
+fn _apply_keylist_update_response(mut state: GrantedState, response: &KeylistUpdateResponse) -> GrantedState {
+    state.pending_thread_ids.retain(|id| id != &response.get_thread_id());
+    for item in &response.updated {
+        match item.result {
+            KeylistUpdateResult::Success | KeylistUpdateResult::NoChange => match item.action {
+                KeylistUpdateAction::Add => {
+                    if !state.keys.contains(&item.recipient_key) {
+                        state.keys.push(item.recipient_key.clone());
+                    }
+                }
+                KeylistUpdateAction::Remove => state.keys.retain(|key| key != &item.recipient_key),
+            },
+            _ => warn!(
+                "Mediator failed to apply keylist update for key {}: {:?}",
+                item.recipient_key, item.result
+            ),
+        }
+    }
+    state
+}
+
+fn _apply_keylist(mut state: GrantedState, keylist: &Keylist) -> GrantedState {
+    state.pending_thread_ids.retain(|id| id != &keylist.get_thread_id());
+    let offset = keylist.pagination.as_ref().map_or(0, |pagination| pagination.offset);
+    if offset == 0 {
+        state.keys = keylist.get_keys();
+    } else {
+        for key in keylist.get_keys() {
+            if !state.keys.contains(&key) {
+                state.keys.push(key);
+            }
+        }
+    }
+    state
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use crate::messages::coordinate_mediation::keylist_update_response::KeylistUpdateResponseItem;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _send_message() -> SendClosure {
+        Box::new(|_: A2AMessage| Box::pin(async { VcxResult::Ok(()) }))
+    }
+
+    fn _grant(thread_id: &str) -> MediateGrant {
+        MediateGrant::create()
+            .set_endpoint("http://mediator.org/agent")
+            .set_routing_keys(vec!["routing-key".to_string()])
+            .set_thread_id(thread_id)
+    }
+
+    async fn _granted_sm() -> MediationRecipientSM {
+        let sm = MediationRecipientSM::new("test")
+            .send_mediation_request(_send_message())
+            .await
+            .unwrap();
+        let thread_id = sm.get_thread_id();
+        sm.handle_message(A2AMessage::MediateGrant(_grant(&thread_id))).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mediation_request_granted() {
+        let _setup = SetupMocks::init();
+        let sm = MediationRecipientSM::new("test")
+            .send_mediation_request(_send_message())
+            .await
+            .unwrap();
+        assert_eq!(sm.get_state(), MediationRecipientState::RequestSent);
+        sm.get_routing_keys().unwrap_err();
+
+        let sm = _granted_sm().await;
+        assert_eq!(sm.get_state(), MediationRecipientState::Granted);
+        assert_eq!(sm.get_routing_keys().unwrap(), vec!["routing-key".to_string()]);
+        assert_eq!(sm.get_endpoint().unwrap(), "http://mediator.org/agent");
+    }
+
+    #[tokio::test]
+    async fn test_mediation_request_denied() {
+        let _setup = SetupMocks::init();
+        let sm = MediationRecipientSM::new("test")
+            .send_mediation_request(_send_message())
+            .await
+            .unwrap();
+        let deny = MediateDeny::create().set_thread_id(&sm.get_thread_id());
+        let sm = sm.handle_message(A2AMessage::MediateDeny(deny)).unwrap();
+        assert_eq!(sm.get_state(), MediationRecipientState::Denied);
+    }
+
+    #[tokio::test]
+    async fn test_messages_from_other_threads_are_ignored() {
+        let _setup = SetupMocks::init();
+        let sm = MediationRecipientSM::new("test")
+            .send_mediation_request(_send_message())
+            .await
+            .unwrap();
+        let sm = sm.handle_message(A2AMessage::MediateGrant(_grant("other"))).unwrap();
+        assert_eq!(sm.get_state(), MediationRecipientState::RequestSent);
+
+        let sm = _granted_sm().await;
+        let keylist = Keylist::create().set_keys(vec!["key3".into()]).set_thread_id("other");
+        let sm = sm.handle_message(A2AMessage::Keylist(keylist)).unwrap();
+        assert!(sm.get_keys().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keylist_update_cannot_be_sent_before_grant() {
+        let _setup = SetupMocks::init();
+        MediationRecipientSM::new("test")
+            .send_keylist_update(vec!["key1".into()], vec![], _send_message())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_keylist_update_response_updates_keys() {
+        let _setup = SetupMocks::init();
+        let sm = _granted_sm()
+            .await
+            .send_keylist_update(vec!["key1".into(), "key2".into()], vec![], _send_message())
+            .await
+            .unwrap();
+        let thread_id = sm.granted_state().unwrap().pending_thread_ids[0].clone();
+        let response = KeylistUpdateResponse::create()
+            .set_updated(vec![
+                KeylistUpdateResponseItem {
+                    recipient_key: "key1".into(),
+                    action: KeylistUpdateAction::Add,
+                    result: KeylistUpdateResult::Success,
+                },
+                KeylistUpdateResponseItem {
+                    recipient_key: "key2".into(),
+                    action: KeylistUpdateAction::Add,
+                    result: KeylistUpdateResult::ServerError,
+                },
+            ])
+            .set_thread_id(&thread_id);
+
+        let messages = map!("uid".to_string() => A2AMessage::KeylistUpdateResponse(response));
+        let (_, message) = sm.find_message_to_handle(messages).unwrap();
+        let sm = sm.handle_message(message).unwrap();
+        assert_eq!(sm.get_keys().unwrap(), vec!["key1".to_string()]);
+        assert!(sm.granted_state().unwrap().pending_thread_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keylist_replaces_known_keys() {
+        let _setup = SetupMocks::init();
+        let sm = _granted_sm()
+            .await
+            .send_keylist_query(None, _send_message())
+            .await
+            .unwrap();
+        let thread_id = sm.granted_state().unwrap().pending_thread_ids[0].clone();
+        let keylist = Keylist::create()
+            .set_keys(vec!["key3".into()])
+            .set_thread_id(&thread_id);
+        let sm = sm.handle_message(A2AMessage::Keylist(keylist)).unwrap();
+        assert_eq!(sm.get_keys().unwrap(), vec!["key3".to_string()]);
+    }
+}
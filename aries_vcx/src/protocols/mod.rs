@@ -4,9 +4,14 @@ use crate::error::VcxResult;
 use crate::messages::a2a::A2AMessage;
 
 pub mod connection;
+pub mod did_rotate;
 pub mod issuance;
+pub mod mediation;
 pub mod oob;
+pub mod pickup;
 pub mod proof_presentation;
 pub mod trustping;
 
 pub type SendClosure = Box<dyn Fn(A2AMessage) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
+/// Called with every message received from the outside, e.g. by the inbound endpoint or a live pickup session.
+pub type InboundCallback = Box<dyn Fn(A2AMessage) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
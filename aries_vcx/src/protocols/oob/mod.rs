@@ -3,6 +3,8 @@ use crate::handlers::out_of_band::OutOfBandInvitation;
 use crate::messages::out_of_band::handshake_reuse::OutOfBandHandshakeReuse;
 use crate::messages::out_of_band::handshake_reuse_accepted::OutOfBandHandshakeReuseAccepted;
 
+pub mod sender;
+
 pub fn build_handshake_reuse_msg(oob_invitation: &OutOfBandInvitation) -> OutOfBandHandshakeReuse {
     OutOfBandHandshakeReuse::default()
         .set_thread_id_matching_id()
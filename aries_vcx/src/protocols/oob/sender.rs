@@ -0,0 +1,198 @@
+use crate::error::{VcxError, VcxErrorKind, VcxResult};
+use crate::handlers::out_of_band::OutOfBandInvitation;
+use crate::messages::connection::request::Request;
+use crate::messages::out_of_band::handshake_reuse::OutOfBandHandshakeReuse;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum OutOfBandSenderState {
+    Initial,
+    InUse,
+    Completed,
+    Expired,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct OutOfBandSenderSM {
+    multi_use: bool,
+    connections: Vec<String>,
+    handshake_reuses: Vec<String>,
+}
+
+impl OutOfBandSenderSM {
+    pub fn new(multi_use: bool) -> Self {
+        Self {
+            multi_use,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_multi_use(&self) -> bool {
+        self.multi_use
+    }
+
+    pub fn set_multi_use(mut self, multi_use: bool) -> Self {
+        self.multi_use = multi_use;
+        self
+    }
+
+    pub fn get_connections(&self) -> Vec<String> {
+        self.connections.clone()
+    }
+
+    pub fn get_handshake_reuses(&self) -> Vec<String> {
+        self.handshake_reuses.clone()
+    }
+
+    fn times_used(&self) -> usize {
+        self.connections.len() + self.handshake_reuses.len()
+    }
+
+    pub fn get_state(&self, invitation: &OutOfBandInvitation) -> OutOfBandSenderState {
+        match (self.multi_use, self.times_used()) {
+            (false, used) if used > 0 => OutOfBandSenderState::Completed,
+            _ if invitation.timing.as_ref().map_or(false, |timing| timing.is_expired()) => {
+                OutOfBandSenderState::Expired
+            }
+            (_, 0) => OutOfBandSenderState::Initial,
+            _ => OutOfBandSenderState::InUse,
+        }
+    }
+
+    fn verify_can_be_used(&self, invitation: &OutOfBandInvitation) -> VcxResult<()> {
+        match self.get_state(invitation) {
+            OutOfBandSenderState::Initial | OutOfBandSenderState::InUse => Ok(()),
+            OutOfBandSenderState::Completed => Err(VcxError::from_msg(
+                VcxErrorKind::InvalidState,
+                format!("Single-use out of band invitation {} was already used", invitation.id.0),
+            )),
+            OutOfBandSenderState::Expired => Err(VcxError::from_msg(
+                VcxErrorKind::InvalidState,
+                format!("Out of band invitation {} has expired", invitation.id.0),
+            )),
+        }
+    }
+
+    fn verify_parent_thread_id(invitation: &OutOfBandInvitation, pthid: Option<&str>) -> VcxResult<()> {
+        match pthid {
+            Some(pthid) if pthid == invitation.id.0 => Ok(()),
+            _ => Err(VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!(
+                    "Parent thread id {:?} does not match out of band invitation id {}",
+                    pthid, invitation.id.0
+                ),
+            )),
+        }
+    }
+
+    pub fn handle_connection_request(self, invitation: &OutOfBandInvitation, request: &Request) -> VcxResult<Self> {
+        trace!(
+            "OutOfBandSenderSM::handle_connection_request >>> request: {:?}",
+            request
+        );
+        let pthid = request.thread.as_ref().and_then(|thread| thread.pthid.as_deref());
+        Self::verify_parent_thread_id(invitation, pthid)?;
+        self.verify_can_be_used(invitation)?;
+        let mut sm = self;
+        sm.connections.push(request.get_thread_id());
+        Ok(sm)
+    }
+
+    pub fn handle_handshake_reuse(
+        self,
+        invitation: &OutOfBandInvitation,
+        handshake_reuse: &OutOfBandHandshakeReuse,
+    ) -> VcxResult<Self> {
+        trace!(
+            "OutOfBandSenderSM::handle_handshake_reuse >>> handshake_reuse: {:?}",
+            handshake_reuse
+        );
+        Self::verify_parent_thread_id(invitation, handshake_reuse.thread.pthid.as_deref())?;
+        self.verify_can_be_used(invitation)?;
+        let mut sm = self;
+        sm.handshake_reuses.push(handshake_reuse.get_thread_id());
+        Ok(sm)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use chrono::{Duration, Utc};
+
+    use crate::messages::a2a::MessageId;
+    use crate::messages::timing::Timing;
+    use crate::protocols::oob::build_handshake_reuse_msg;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _invitation() -> OutOfBandInvitation {
+        let mut invitation = OutOfBandInvitation::default();
+        invitation.id = MessageId("invitation-id".to_string());
+        invitation
+    }
+
+    fn _request(invitation: &OutOfBandInvitation) -> Request {
+        Request::create()
+            .set_parent_thread_id(&invitation.id.0)
+            .set_thread_id_matching_id()
+    }
+
+    #[test]
+    fn test_single_use_invitation_is_completed_after_connection() {
+        let _setup = SetupMocks::init();
+        let invitation = _invitation();
+        let sm = OutOfBandSenderSM::new(false);
+        assert_eq!(sm.get_state(&invitation), OutOfBandSenderState::Initial);
+
+        let sm = sm
+            .handle_connection_request(&invitation, &_request(&invitation))
+            .unwrap();
+        assert_eq!(sm.get_state(&invitation), OutOfBandSenderState::Completed);
+        assert_eq!(sm.get_connections().len(), 1);
+
+        sm.handle_connection_request(&invitation, &_request(&invitation))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_multi_use_invitation_accepts_connections_and_reuses() {
+        let _setup = SetupMocks::init();
+        let invitation = _invitation();
+        let sm = OutOfBandSenderSM::new(true)
+            .handle_connection_request(&invitation, &_request(&invitation))
+            .unwrap();
+        assert_eq!(sm.get_state(&invitation), OutOfBandSenderState::InUse);
+
+        let sm = sm
+            .handle_handshake_reuse(&invitation, &build_handshake_reuse_msg(&invitation))
+            .unwrap()
+            .handle_connection_request(&invitation, &_request(&invitation))
+            .unwrap();
+        assert_eq!(sm.get_state(&invitation), OutOfBandSenderState::InUse);
+        assert_eq!(sm.get_connections().len(), 2);
+        assert_eq!(sm.get_handshake_reuses().len(), 1);
+    }
+
+    #[test]
+    fn test_expired_invitation_cannot_be_used() {
+        let _setup = SetupMocks::init();
+        let mut invitation = _invitation();
+        invitation.timing = Some(Timing::new().set_expires_time(Utc::now() - Duration::seconds(1)));
+        let sm = OutOfBandSenderSM::new(true);
+        assert_eq!(sm.get_state(&invitation), OutOfBandSenderState::Expired);
+        sm.handle_handshake_reuse(&invitation, &build_handshake_reuse_msg(&invitation))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_handshake_reuse_for_other_invitation_is_rejected() {
+        let _setup = SetupMocks::init();
+        let invitation = _invitation();
+        let reuse = build_handshake_reuse_msg(&OutOfBandInvitation::default());
+        OutOfBandSenderSM::new(false)
+            .handle_handshake_reuse(&invitation, &reuse)
+            .unwrap_err();
+    }
+}
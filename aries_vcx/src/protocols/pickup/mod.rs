@@ -0,0 +1,50 @@
+use crate::messages::pickup::delivery_request::DeliveryRequest;
+use crate::messages::pickup::live_delivery_change::LiveDeliveryChange;
+use crate::messages::pickup::messages_received::MessagesReceived;
+use crate::messages::pickup::status_request::StatusRequest;
+
+pub fn build_status_request(recipient_key: Option<String>) -> StatusRequest {
+    StatusRequest::create().set_recipient_key(recipient_key).set_out_time()
+}
+
+pub fn build_delivery_request(limit: u32, recipient_key: Option<String>) -> DeliveryRequest {
+    DeliveryRequest::create()
+        .set_limit(limit)
+        .set_recipient_key(recipient_key)
+        .set_out_time()
+}
+
+pub fn build_messages_received(message_ids: Vec<String>) -> MessagesReceived {
+    MessagesReceived::create()
+        .set_message_id_list(message_ids)
+        .set_out_time()
+}
+
+pub fn build_live_delivery_change(live_delivery: bool) -> LiveDeliveryChange {
+    LiveDeliveryChange::create()
+        .set_live_delivery(live_delivery)
+        .set_out_time()
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use crate::messages::pickup::delivery::Delivery;
+
+    use super::*;
+
+    #[test]
+    fn test_build_delivery_request() {
+        let request = build_delivery_request(10, Some("key".to_string()));
+        assert_eq!(request.limit, 10);
+        assert_eq!(request.recipient_key, Some("key".to_string()));
+        assert!(request.timing.is_some());
+    }
+
+    #[test]
+    fn test_build_messages_received() {
+        let delivery = Delivery::create().add_attachment("msg-1", "{}".as_bytes());
+        let received = build_messages_received(delivery.get_message_ids());
+        assert_eq!(received.message_id_list, vec!["msg-1".to_string()]);
+    }
+}
@@ -1,2 +1,13 @@
+use crate::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use crate::messages::proof_presentation::presentation_request::PresentationRequest;
+
 pub mod prover;
 pub mod verifier;
+
+/// Proposal or request exchanged in one round of negotiating the presentation, before the prover
+/// commits to it by sending presentation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PresentationNegotiationMessage {
+    Proposal(PresentationProposal),
+    Request(PresentationRequest),
+}
@@ -12,7 +12,7 @@ pub enum ProverMessages {
     PresentationRequestReceived(PresentationRequest),
     RejectPresentationRequest(String),
     SetPresentation(Presentation),
-    PreparePresentation((String, String)),
+    PreparePresentation((String, String, Vec<String>)),
     SendPresentation,
     PresentationAckReceived(PresentationAck),
     PresentationRejectReceived(ProblemReport),
@@ -29,6 +29,16 @@ impl ProverMessages {
             _ => true,
         }
     }
+
+    /// Returns the message received from the counterparty which triggered this action, if any.
+    pub fn received_message(&self) -> Option<A2AMessage> {
+        match self {
+            Self::PresentationRequestReceived(request) => Some(A2AMessage::PresentationRequest(request.clone())),
+            Self::PresentationAckReceived(ack) => Some(A2AMessage::PresentationAck(ack.clone())),
+            Self::PresentationRejectReceived(report) => Some(A2AMessage::CommonProblemReport(report.clone())),
+            _ => None,
+        }
+    }
 }
 
 impl From<A2AMessage> for ProverMessages {
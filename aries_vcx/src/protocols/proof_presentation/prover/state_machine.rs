@@ -4,7 +4,7 @@ use indy_sys::WalletHandle;
 
 use crate::error::prelude::*;
 use crate::messages::a2a::{A2AMessage, MessageId};
-use crate::messages::error::ProblemReport;
+use crate::messages::error::{ProblemCode, ProblemReport};
 use crate::messages::proof_presentation::presentation::Presentation;
 use crate::messages::proof_presentation::presentation_proposal::{PresentationPreview, PresentationProposal};
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
@@ -18,6 +18,7 @@ use crate::protocols::proof_presentation::prover::states::presentation_proposal_
 use crate::protocols::proof_presentation::prover::states::presentation_request_received::PresentationRequestReceived;
 use crate::protocols::proof_presentation::prover::states::presentation_sent::PresentationSentState;
 use crate::protocols::proof_presentation::prover::verify_thread_id;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 use crate::protocols::SendClosure;
 
 /// A state machine that tracks the evolution of states for a Prover during
@@ -62,6 +63,7 @@ fn build_presentation_message(thread_id: &str, presentation_attachment: String)
 
 fn build_problem_report(thread_id: &str, comment: &str) -> ProblemReport {
     ProblemReport::create()
+        .set_description(ProblemCode::RequestNotAccepted)
         .set_comment(Some(comment.into()))
         .set_thread_id(&thread_id)
         .set_out_time()
@@ -85,8 +87,8 @@ impl ProverSM {
     pub fn from_request(presentation_request: PresentationRequest, source_id: String) -> ProverSM {
         ProverSM {
             source_id,
-            thread_id: presentation_request.id.0.clone(),
-            state: ProverFullState::PresentationRequestReceived(PresentationRequestReceived { presentation_request }),
+            thread_id: presentation_request.get_thread_id(),
+            state: ProverFullState::PresentationRequestReceived(PresentationRequestReceived::new(presentation_request)),
         }
     }
 
@@ -126,6 +128,46 @@ impl ProverSM {
         None
     }
 
+    /// Terminates the presentation in any non-terminal state, the verifier is notified by problem
+    /// report only if `send_message` is provided and some message was already exchanged.
+    pub async fn abandon(self, reason: Option<String>, send_message: Option<SendClosure>) -> VcxResult<ProverSM> {
+        trace!("ProverSM::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+        let presentation_request = self.presentation_request().ok().cloned();
+        let negotiation = self.get_negotiation_history();
+        let ProverSM {
+            source_id,
+            state,
+            thread_id,
+        } = self;
+        let problem_report =
+            ProblemReport::from_abandonment(ProblemCode::PresentationAbandoned, reason).set_thread_id(&thread_id);
+        let send_message = send_message.filter(|_| !matches!(state, ProverFullState::Initial(_)));
+        let state = match state {
+            ProverFullState::Finished(_) => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidState,
+                    "Can not abandon presentation in Finished state",
+                ));
+            }
+            ProverFullState::PresentationSent(state) => {
+                ProverFullState::Finished((state, problem_report.clone()).into())
+            }
+            _ => ProverFullState::Finished(FinishedState::abandoned(
+                presentation_request,
+                problem_report.clone(),
+                negotiation,
+            )),
+        };
+        if let Some(send_message) = send_message {
+            send_message(problem_report.to_a2a_message()).await?;
+        }
+        Ok(ProverSM {
+            source_id,
+            state,
+            thread_id,
+        })
+    }
+
     pub async fn step(
         self,
         wallet_handle: WalletHandle,
@@ -158,11 +200,11 @@ impl ProverSM {
             ProverFullState::PresentationProposalSent(state) => {
                 match message {
                     ProverMessages::PresentationRequestReceived(request) => {
-                        ProverFullState::PresentationRequestReceived(PresentationRequestReceived::new(request))
+                        ProverFullState::PresentationRequestReceived((state, request).into())
                     }
                     // TODO: Perhaps use a different message type?
                     ProverMessages::PresentationRejectReceived(problem_report) => {
-                        ProverFullState::Finished(FinishedState::declined(problem_report))
+                        ProverFullState::Finished(FinishedState::declined(problem_report, state.negotiation))
                     }
                     _ => {
                         warn!("Unable to process received message in this state");
@@ -178,25 +220,28 @@ impl ProverSM {
                         "Attempted to call undefined send_message callback",
                     ))?(proposal.to_a2a_message())
                     .await?;
-                    ProverFullState::PresentationProposalSent(PresentationProposalSent::new(proposal))
+                    ProverFullState::PresentationProposalSent((state, proposal).into())
                 }
                 ProverMessages::SetPresentation(presentation) => {
                     let presentation = presentation.set_thread_id(&thread_id);
                     ProverFullState::PresentationPrepared((state, presentation).into())
                 }
-                ProverMessages::PreparePresentation((credentials, self_attested_attrs)) => match state
-                    .build_presentation(wallet_handle, &credentials, &self_attested_attrs)
-                    .await
-                {
-                    Ok(presentation) => {
-                        let presentation = build_presentation_message(&thread_id, presentation)?;
-                        ProverFullState::PresentationPrepared((state, presentation).into())
-                    }
-                    Err(err) => {
-                        let problem_report = build_problem_report(&thread_id, &err.to_string());
-                        ProverFullState::PresentationPreparationFailed((state, problem_report).into())
+                ProverMessages::PreparePresentation((credentials, self_attested_attrs, unrevealed_attrs)) => {
+                    match state
+                        .build_presentation(wallet_handle, &credentials, &self_attested_attrs, &unrevealed_attrs)
+                        .await
+                    {
+                        Ok(presentation) => {
+                            let presentation = build_presentation_message(&thread_id, presentation)?;
+                            ProverFullState::PresentationPrepared((state, presentation).into())
+                        }
+                        Err(err) => {
+                            let problem_report =
+                                ProblemReport::from_error(&err).set_thread_id(&thread_id).set_out_time();
+                            ProverFullState::PresentationPreparationFailed((state, problem_report).into())
+                        }
                     }
-                },
+                }
                 ProverMessages::RejectPresentationRequest(reason) => {
                     if let Some(send_message) = send_message {
                         let problem_report =
@@ -228,8 +273,18 @@ impl ProverSM {
             ProverFullState::PresentationPrepared(state) => match message {
                 ProverMessages::SendPresentation => {
                     if let Some(send_message) = send_message {
-                        send_message(state.presentation.to_a2a_message()).await?;
-                        ProverFullState::PresentationSent((state).into())
+                        if state.presentation_request.is_expired() {
+                            let err = VcxError::from_msg(
+                                VcxErrorKind::MessageExpired,
+                                "Presentation request has expired before presentation was sent",
+                            );
+                            let problem_report = ProblemReport::from_error(&err).set_thread_id(&thread_id);
+                            send_message(problem_report.to_a2a_message()).await?;
+                            ProverFullState::Finished((state, problem_report).into())
+                        } else {
+                            send_message(state.presentation.to_a2a_message()).await?;
+                            ProverFullState::PresentationSent((state).into())
+                        }
                     } else {
                         return Err(VcxError::from_msg(
                             VcxErrorKind::ActionNotSupported,
@@ -241,7 +296,7 @@ impl ProverSM {
                     if let Some(send_message) = send_message {
                         let problem_report =
                             Self::_handle_reject_presentation_request(send_message, &reason, &thread_id).await?;
-                        ProverFullState::Finished(FinishedState::declined(problem_report))
+                        ProverFullState::Finished(FinishedState::declined(problem_report, state.negotiation))
                     } else {
                         return Err(VcxError::from_msg(
                             VcxErrorKind::ActionNotSupported,
@@ -330,6 +385,20 @@ impl ProverSM {
         send_message(proposal.to_a2a_message()).await
     }
 
+    /// Proposals and requests exchanged before the presentation was prepared, in the order they were
+    /// sent or received. Kept until the presentation is finished.
+    pub fn get_negotiation_history(&self) -> Vec<PresentationNegotiationMessage> {
+        match self.state {
+            ProverFullState::Initial(_) => vec![],
+            ProverFullState::PresentationProposalSent(ref state) => state.negotiation.clone(),
+            ProverFullState::PresentationRequestReceived(ref state) => state.negotiation.clone(),
+            ProverFullState::PresentationPreparationFailed(ref state) => state.negotiation.clone(),
+            ProverFullState::PresentationPrepared(ref state) => state.negotiation.clone(),
+            ProverFullState::PresentationSent(ref state) => state.negotiation.clone(),
+            ProverFullState::Finished(ref state) => state.negotiation.clone(),
+        }
+    }
+
     pub fn source_id(&self) -> String {
         self.source_id.clone()
     }
@@ -373,6 +442,15 @@ impl ProverSM {
         }
     }
 
+    /// Problem report which failed or declined the protocol, either received or sent by us.
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        match self.state {
+            ProverFullState::Finished(ref state) => state.status.problem_report().cloned(),
+            ProverFullState::PresentationPreparationFailed(ref state) => Some(state.problem_report.clone()),
+            _ => None,
+        }
+    }
+
     pub fn presentation_request(&self) -> VcxResult<&PresentationRequest> {
         match self.state {
             ProverFullState::Initial(_) => Err(VcxError::from_msg(
@@ -424,6 +502,9 @@ impl ProverSM {
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 pub mod unit_tests {
+    use chrono::Utc;
+
+    use crate::messages::error::ProblemCode;
     use crate::messages::proof_presentation::presentation::test_utils::_presentation;
     use crate::messages::proof_presentation::presentation_proposal::test_utils::{
         _presentation_preview, _presentation_proposal, _presentation_proposal_data,
@@ -468,7 +549,7 @@ pub mod unit_tests {
             self = self
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     None,
                 )
                 .await
@@ -480,7 +561,7 @@ pub mod unit_tests {
             self = self
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -500,7 +581,7 @@ pub mod unit_tests {
             self = self
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     None,
                 )
                 .await
@@ -706,6 +787,73 @@ pub mod unit_tests {
             assert_match!(ProverFullState::PresentationProposalSent(_), prover_sm.state);
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_prover_negotiation_rounds_retain_history() {
+            let _setup = SetupMocks::init();
+
+            let mut prover_sm = _prover_sm_from_request();
+            let thread_id = prover_sm.get_thread_id().unwrap();
+            prover_sm = prover_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    ProverMessages::PresentationProposalSend(_presentation_proposal_data()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(ProverFullState::PresentationProposalSent(_), prover_sm.state);
+            let history = prover_sm.get_negotiation_history();
+            assert_eq!(history.len(), 2);
+            match &history[1] {
+                PresentationNegotiationMessage::Proposal(proposal) => assert_eq!(proposal.get_thread_id(), thread_id),
+                other => panic!("Expected counter proposal, got {:?}", other),
+            }
+
+            let revised_request = _presentation_request()
+                .set_id(MessageId::new().0)
+                .set_thread_id(&thread_id);
+            prover_sm = prover_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    ProverMessages::PresentationRequestReceived(revised_request.clone()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(ProverFullState::PresentationRequestReceived(_), prover_sm.state);
+            assert_eq!(prover_sm.presentation_request().unwrap(), &revised_request);
+            assert_eq!(prover_sm.get_thread_id().unwrap(), thread_id);
+            assert_eq!(prover_sm.get_negotiation_history().len(), 3);
+            assert_eq!(
+                prover_sm.get_negotiation_history()[2],
+                PresentationNegotiationMessage::Request(revised_request)
+            );
+
+            prover_sm = prover_sm.to_presentation_prepared_state().await;
+            assert_match!(ProverFullState::PresentationPrepared(_), prover_sm.state);
+            assert_eq!(prover_sm.get_negotiation_history().len(), 3);
+
+            prover_sm = prover_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    ProverMessages::SendPresentation,
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            prover_sm = prover_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    ProverMessages::PresentationAckReceived(_ack()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(ProverFullState::Finished(_), prover_sm.state);
+            assert_eq!(prover_sm.get_negotiation_history().len(), 3);
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_prover_handle_set_presentation_from_presentation_request_received_state() {
@@ -735,7 +883,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())),
+                    ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -818,7 +966,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -910,7 +1058,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())),
+                    ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -942,7 +1090,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())),
+                    ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -978,7 +1126,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -1030,7 +1178,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -1068,7 +1216,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -1085,7 +1233,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -1112,7 +1260,7 @@ pub mod unit_tests {
             prover_sm = prover_sm
                 .step(
                     _dummy_wallet_handle(),
-                    ProverMessages::PreparePresentation((_credentials(), _self_attested())),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
                     _send_message(),
                 )
                 .await
@@ -1154,6 +1302,64 @@ pub mod unit_tests {
                 .unwrap();
             assert_match!(ProverFullState::Finished(_), prover_sm.state);
         }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_prover_handle_expired_presentation_request() {
+            let _setup = SetupMocks::init();
+
+            let presentation_request =
+                _presentation_request().set_expires_time(Utc::now() - chrono::Duration::seconds(1));
+            let prover_sm = ProverSM::from_request(presentation_request, source_id());
+
+            let failed_sm = prover_sm
+                .clone()
+                .step(
+                    _dummy_wallet_handle(),
+                    ProverMessages::PreparePresentation((_credentials(), _self_attested(), vec![])),
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_eq!(ProverState::PresentationPreparationFailed, failed_sm.get_state());
+
+            let prover_sm = prover_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    ProverMessages::SetPresentation(_presentation()),
+                    None,
+                )
+                .await
+                .unwrap()
+                .step(
+                    _dummy_wallet_handle(),
+                    ProverMessages::SendPresentation,
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(ProverState::Failed, prover_sm.get_state());
+            assert_eq!(
+                Some(ProblemCode::RequestTime),
+                prover_sm.get_problem_report().unwrap().get_code()
+            );
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_prover_abandon_from_presentation_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut prover_sm = _prover_sm_from_request().to_presentation_sent_state().await;
+            prover_sm = prover_sm.abandon(None, None).await.unwrap();
+
+            assert_eq!(ProverState::Failed, prover_sm.get_state());
+            assert!(prover_sm.presentation().is_ok());
+            assert_eq!(
+                Some(ProblemCode::PresentationAbandoned),
+                prover_sm.get_problem_report().unwrap().get_code()
+            );
+        }
     }
 
     mod find_message_to_handle {
@@ -2,21 +2,39 @@ use crate::messages::error::ProblemReport;
 use crate::messages::proof_presentation::presentation::Presentation;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::messages::status::Status;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FinishedState {
     pub presentation_request: Option<PresentationRequest>,
     pub presentation: Option<Presentation>,
     pub status: Status,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl FinishedState {
-    pub fn declined(problem_report: ProblemReport) -> Self {
+    pub fn declined(problem_report: ProblemReport, negotiation: Vec<PresentationNegotiationMessage>) -> Self {
         trace!("transit state to FinishedState due to a rejection");
         FinishedState {
             presentation_request: None,
             presentation: None,
             status: Status::Declined(problem_report),
+            negotiation,
+        }
+    }
+
+    pub fn abandoned(
+        presentation_request: Option<PresentationRequest>,
+        problem_report: ProblemReport,
+        negotiation: Vec<PresentationNegotiationMessage>,
+    ) -> Self {
+        trace!("transit state to FinishedState due to abandonment");
+        FinishedState {
+            presentation_request,
+            presentation: None,
+            status: Status::Failed(problem_report),
+            negotiation,
         }
     }
 }
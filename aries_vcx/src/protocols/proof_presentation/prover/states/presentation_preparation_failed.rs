@@ -2,11 +2,14 @@ use crate::messages::error::ProblemReport;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::messages::status::Status;
 use crate::protocols::proof_presentation::prover::states::finished::FinishedState;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PresentationPreparationFailedState {
     pub presentation_request: PresentationRequest,
     pub problem_report: ProblemReport,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl From<PresentationPreparationFailedState> for FinishedState {
@@ -16,6 +19,7 @@ impl From<PresentationPreparationFailedState> for FinishedState {
             presentation_request: Some(state.presentation_request),
             presentation: None,
             status: Status::Failed(state.problem_report),
+            negotiation: state.negotiation,
         }
     }
 }
@@ -1,13 +1,17 @@
+use crate::messages::error::ProblemReport;
 use crate::messages::proof_presentation::presentation::Presentation;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::messages::status::Status;
 use crate::protocols::proof_presentation::prover::states::finished::FinishedState;
 use crate::protocols::proof_presentation::prover::states::presentation_sent::PresentationSentState;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PresentationPreparedState {
     pub presentation_request: PresentationRequest,
     pub presentation: Presentation,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl From<PresentationPreparedState> for PresentationSentState {
@@ -16,6 +20,7 @@ impl From<PresentationPreparedState> for PresentationSentState {
         PresentationSentState {
             presentation_request: state.presentation_request,
             presentation: state.presentation,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -27,6 +32,19 @@ impl From<PresentationPreparedState> for FinishedState {
             presentation_request: Some(state.presentation_request),
             presentation: Default::default(),
             status: Status::Undefined,
+            negotiation: state.negotiation,
+        }
+    }
+}
+
+impl From<(PresentationPreparedState, ProblemReport)> for FinishedState {
+    fn from((state, problem_report): (PresentationPreparedState, ProblemReport)) -> Self {
+        trace!("transit state from PresentationPreparedState to FinishedState");
+        FinishedState {
+            presentation_request: Some(state.presentation_request),
+            presentation: None,
+            status: Status::Failed(problem_report),
+            negotiation: state.negotiation,
         }
     }
 }
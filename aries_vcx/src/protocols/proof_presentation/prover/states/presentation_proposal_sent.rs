@@ -1,12 +1,41 @@
 use crate::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use crate::messages::proof_presentation::presentation_request::PresentationRequest;
+use crate::protocols::proof_presentation::prover::states::presentation_request_received::PresentationRequestReceived;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PresentationProposalSent {
     proposal: PresentationProposal,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl PresentationProposalSent {
     pub fn new(proposal: PresentationProposal) -> Self {
-        Self { proposal }
+        Self {
+            negotiation: vec![PresentationNegotiationMessage::Proposal(proposal.clone())],
+            proposal,
+        }
+    }
+}
+
+impl From<(PresentationProposalSent, PresentationRequest)> for PresentationRequestReceived {
+    fn from((state, presentation_request): (PresentationProposalSent, PresentationRequest)) -> Self {
+        trace!("transit state from PresentationProposalSent to PresentationRequestReceived");
+        let mut negotiation = state.negotiation;
+        negotiation.push(PresentationNegotiationMessage::Request(presentation_request.clone()));
+        PresentationRequestReceived {
+            presentation_request,
+            negotiation,
+        }
+    }
+}
+
+impl From<(PresentationRequestReceived, PresentationProposal)> for PresentationProposalSent {
+    fn from((state, proposal): (PresentationRequestReceived, PresentationProposal)) -> Self {
+        trace!("transit state from PresentationRequestReceived to PresentationProposalSent");
+        let mut negotiation = state.negotiation;
+        negotiation.push(PresentationNegotiationMessage::Proposal(proposal.clone()));
+        PresentationProposalSent { proposal, negotiation }
     }
 }
@@ -9,15 +9,21 @@ use crate::messages::status::Status;
 use crate::protocols::proof_presentation::prover::states::finished::FinishedState;
 use crate::protocols::proof_presentation::prover::states::presentation_preparation_failed::PresentationPreparationFailedState;
 use crate::protocols::proof_presentation::prover::states::presentation_prepared::PresentationPreparedState;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct PresentationRequestReceived {
     pub presentation_request: PresentationRequest,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl PresentationRequestReceived {
     pub fn new(presentation_request: PresentationRequest) -> Self {
-        Self { presentation_request }
+        Self {
+            negotiation: vec![PresentationNegotiationMessage::Request(presentation_request.clone())],
+            presentation_request,
+        }
     }
 
     pub async fn build_presentation(
@@ -25,12 +31,20 @@ impl PresentationRequestReceived {
         wallet_handle: WalletHandle,
         credentials: &str,
         self_attested_attrs: &str,
+        unrevealed_attrs: &[String],
     ) -> VcxResult<String> {
+        if self.presentation_request.is_expired() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::MessageExpired,
+                format!("Presentation request {} has expired", self.presentation_request.id.0),
+            ));
+        }
         generate_indy_proof(
             wallet_handle,
             credentials,
             self_attested_attrs,
             &self.presentation_request.request_presentations_attach.content()?,
+            unrevealed_attrs,
         )
         .await
     }
@@ -42,6 +56,7 @@ impl From<(PresentationRequestReceived, ProblemReport)> for PresentationPreparat
         PresentationPreparationFailedState {
             presentation_request: state.presentation_request,
             problem_report,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -52,6 +67,7 @@ impl From<(PresentationRequestReceived, Presentation)> for PresentationPreparedS
         PresentationPreparedState {
             presentation_request: state.presentation_request,
             presentation,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -63,6 +79,7 @@ impl From<PresentationRequestReceived> for FinishedState {
             presentation_request: Some(state.presentation_request),
             presentation: None,
             status: Status::Success,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -74,6 +91,7 @@ impl From<(PresentationRequestReceived, ProblemReport)> for FinishedState {
             presentation_request: Some(state.presentation_request),
             presentation: None,
             status: Status::Declined(problem_report),
+            negotiation: state.negotiation,
         }
     }
 }
@@ -4,11 +4,14 @@ use crate::messages::proof_presentation::presentation_ack::PresentationAck;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::messages::status::Status;
 use crate::protocols::proof_presentation::prover::states::finished::FinishedState;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PresentationSentState {
     pub presentation_request: PresentationRequest,
     pub presentation: Presentation,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl From<(PresentationSentState, PresentationAck)> for FinishedState {
@@ -18,6 +21,7 @@ impl From<(PresentationSentState, PresentationAck)> for FinishedState {
             presentation_request: Some(state.presentation_request),
             presentation: Some(state.presentation),
             status: Status::Success,
+            negotiation: state.negotiation,
         }
     }
 }
@@ -29,6 +33,7 @@ impl From<(PresentationSentState, ProblemReport)> for FinishedState {
             presentation_request: Some(state.presentation_request),
             presentation: Some(state.presentation),
             status: Status::Failed(problem_report),
+            negotiation: state.negotiation,
         }
     }
 }
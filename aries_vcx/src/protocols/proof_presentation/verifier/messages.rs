@@ -14,6 +14,7 @@ pub enum VerifierMessages {
     PresentationProposalReceived(PresentationProposal),
     PresentationRejectReceived(ProblemReport),
     SendPresentationAck(),
+    PresentationRequestExpired,
     Unknown,
 }
 
@@ -26,6 +27,16 @@ impl VerifierMessages {
             _ => true,
         }
     }
+
+    /// Returns the message received from the counterparty which triggered this action, if any.
+    pub fn received_message(&self) -> Option<A2AMessage> {
+        match self {
+            Self::VerifyPresentation(presentation) => Some(A2AMessage::Presentation(presentation.clone())),
+            Self::PresentationProposalReceived(proposal) => Some(A2AMessage::PresentationProposal(proposal.clone())),
+            Self::PresentationRejectReceived(report) => Some(A2AMessage::CommonProblemReport(report.clone())),
+            _ => None,
+        }
+    }
 }
 
 impl From<A2AMessage> for VerifierMessages {
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 use std::fmt::Display;
 
+use chrono::{DateTime, Utc};
 use indy_sys::WalletHandle;
 
 use crate::error::prelude::*;
 use crate::messages::a2a::{A2AMessage, MessageId};
-use crate::messages::error::ProblemReport;
+use crate::messages::error::{ProblemCode, ProblemReport};
 use crate::messages::proof_presentation::presentation::Presentation;
 use crate::messages::proof_presentation::presentation_ack::PresentationAck;
 use crate::messages::proof_presentation::presentation_proposal::PresentationProposal;
@@ -18,6 +19,7 @@ use crate::protocols::proof_presentation::verifier::states::presentation_proposa
 use crate::protocols::proof_presentation::verifier::states::presentation_request_sent::PresentationRequestSentState;
 use crate::protocols::proof_presentation::verifier::states::presentation_request_set::PresentationRequestSetState;
 use crate::protocols::proof_presentation::verifier::verify_thread_id;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 use crate::protocols::SendClosure;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -158,13 +160,14 @@ impl VerifierSM {
             thread_id,
             state,
         } = self;
-        let state = match state {
-            VerifierFullState::Initial(_)
-            | VerifierFullState::PresentationRequestSet(_)
-            | VerifierFullState::PresentationProposalReceived(_) => {
-                let presentation_request = build_starting_presentation_request(&thread_id, request_data, comment)?;
-                VerifierFullState::PresentationRequestSet(PresentationRequestSetState::new(presentation_request))
+        let negotiation = match state {
+            VerifierFullState::Initial(_) => vec![],
+            VerifierFullState::PresentationRequestSet(state) => {
+                let mut negotiation = state.negotiation;
+                negotiation.pop();
+                negotiation
             }
+            VerifierFullState::PresentationProposalReceived(state) => state.negotiation,
             _ => {
                 return Err(VcxError::from_msg(
                     VcxErrorKind::InvalidState,
@@ -172,6 +175,41 @@ impl VerifierSM {
                 ));
             }
         };
+        let mut presentation_request = build_starting_presentation_request(&thread_id, request_data, comment)?;
+        if !negotiation.is_empty() {
+            // request responding to a proposal is a new message within the negotiated thread
+            presentation_request = presentation_request
+                .set_id(MessageId::new().0)
+                .set_thread_id(&thread_id);
+        }
+        let state = VerifierFullState::PresentationRequestSet(
+            PresentationRequestSetState::new(presentation_request).set_negotiation(negotiation),
+        );
+        Ok(Self {
+            source_id,
+            state,
+            thread_id,
+        })
+    }
+
+    pub fn set_request_expiration(self, expires_time: DateTime<Utc>) -> VcxResult<Self> {
+        let Self {
+            source_id,
+            thread_id,
+            state,
+        } = self;
+        let state = match state {
+            VerifierFullState::PresentationRequestSet(mut state) => {
+                state.presentation_request = state.presentation_request.set_expires_time(expires_time);
+                VerifierFullState::PresentationRequestSet(state)
+            }
+            _ => {
+                return Err(VcxError::from_msg(
+                    VcxErrorKind::InvalidState,
+                    "Cannot set presentation request expiration in this state",
+                ));
+            }
+        };
         Ok(Self {
             source_id,
             state,
@@ -179,6 +217,14 @@ impl VerifierSM {
         })
     }
 
+    /// Whether the prover did not respond to our presentation request until its expiration time.
+    pub fn is_expired(&self) -> bool {
+        match &self.state {
+            VerifierFullState::PresentationRequestSent(state) => state.presentation_request.is_expired(),
+            _ => false,
+        }
+    }
+
     pub fn mark_presentation_request_msg_sent(self) -> VcxResult<Self> {
         let Self {
             state,
@@ -204,6 +250,40 @@ impl VerifierSM {
         })
     }
 
+    /// Terminates the presentation in any non-terminal state, the prover is notified by problem
+    /// report only if `send_message` is provided and some message was already exchanged.
+    pub async fn abandon(self, reason: Option<String>, send_message: Option<SendClosure>) -> VcxResult<Self> {
+        trace!("VerifierSM::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+        if let VerifierFullState::Finished(_) = self.state {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidState,
+                "Can not abandon presentation in Finished state",
+            ));
+        }
+        let presentation_request = self.presentation_request().ok();
+        let negotiation = self.get_negotiation_history();
+        let problem_report =
+            ProblemReport::from_abandonment(ProblemCode::PresentationAbandoned, reason).set_thread_id(&self.thread_id);
+        let send_message = send_message.filter(|_| {
+            !matches!(
+                self.state,
+                VerifierFullState::Initial(_) | VerifierFullState::PresentationRequestSet(_)
+            )
+        });
+        if let Some(send_message) = send_message {
+            send_message(problem_report.to_a2a_message()).await?;
+        }
+        Ok(Self {
+            source_id: self.source_id,
+            thread_id: self.thread_id,
+            state: VerifierFullState::Finished(FinishedState::abandoned(
+                presentation_request,
+                problem_report,
+                negotiation,
+            )),
+        })
+    }
+
     pub async fn step(
         self,
         wallet_handle: WalletHandle,
@@ -253,6 +333,7 @@ impl VerifierSM {
                         None => state.presentation_proposal.id.0,
                     };
                     let problem_report = ProblemReport::create()
+                        .set_description(ProblemCode::RequestNotAccepted)
                         .set_comment(Some(reason.to_string()))
                         .set_thread_id(&thread_id);
                     send_message.ok_or(VcxError::from_msg(
@@ -261,7 +342,7 @@ impl VerifierSM {
                     ))?(problem_report.to_a2a_message())
                     .await?;
                     (
-                        VerifierFullState::Finished(FinishedState::declined(problem_report)),
+                        VerifierFullState::Finished(FinishedState::declined(problem_report, state.negotiation)),
                         thread_id,
                     )
                 }
@@ -271,6 +352,21 @@ impl VerifierSM {
                 }
             },
             VerifierFullState::PresentationRequestSent(state) => match message {
+                VerifierMessages::VerifyPresentation(_) | VerifierMessages::PresentationRequestExpired
+                    if state.presentation_request.is_expired() =>
+                {
+                    let err = VcxError::from_msg(
+                        VcxErrorKind::MessageExpired,
+                        "Presentation request has expired before presentation was received",
+                    );
+                    let problem_report = ProblemReport::from_error(&err).set_thread_id(&thread_id);
+                    send_message.ok_or(VcxError::from_msg(
+                        VcxErrorKind::InvalidState,
+                        "Attempted to call undefined send_message callback",
+                    ))?(problem_report.to_a2a_message())
+                    .await?;
+                    (VerifierFullState::Finished((state, problem_report).into()), thread_id)
+                }
                 VerifierMessages::VerifyPresentation(presentation) => match state
                     .verify_presentation(wallet_handle, &presentation, &thread_id)
                     .await
@@ -290,9 +386,7 @@ impl VerifierSM {
                         )
                     }
                     Err(err) => {
-                        let problem_report = ProblemReport::create()
-                            .set_comment(Some(err.to_string()))
-                            .set_thread_id(&thread_id);
+                        let problem_report = ProblemReport::from_error(&err).set_thread_id(&thread_id);
                         send_message.ok_or(VcxError::from_msg(
                             VcxErrorKind::InvalidState,
                             "Attempted to call undefined send_message callback",
@@ -311,7 +405,7 @@ impl VerifierSM {
                     (VerifierFullState::Finished((state, problem_report).into()), thread_id)
                 }
                 VerifierMessages::PresentationProposalReceived(proposal) => (
-                    VerifierFullState::PresentationProposalReceived(PresentationProposalReceivedState::new(proposal)),
+                    VerifierFullState::PresentationProposalReceived((state, proposal).into()),
                     thread_id,
                 ),
                 _ => {
@@ -343,6 +437,18 @@ impl VerifierSM {
         self.source_id.clone()
     }
 
+    /// Proposals and requests exchanged before the presentation was received, in the order they were
+    /// sent or received. Kept once the presentation is finished.
+    pub fn get_negotiation_history(&self) -> Vec<PresentationNegotiationMessage> {
+        match &self.state {
+            VerifierFullState::Initial(_) => vec![],
+            VerifierFullState::PresentationProposalReceived(state) => state.negotiation.clone(),
+            VerifierFullState::PresentationRequestSet(state) => state.negotiation.clone(),
+            VerifierFullState::PresentationRequestSent(state) => state.negotiation.clone(),
+            VerifierFullState::Finished(state) => state.negotiation.clone(),
+        }
+    }
+
     pub fn thread_id(&self) -> String {
         self.thread_id.clone()
     }
@@ -380,6 +486,7 @@ impl VerifierSM {
                             None => Status::Success.code(), // for backward compatibility
                             Some(RevocationStatus::Revoked) => {
                                 let problem_report = ProblemReport::create()
+                                    .set_description(ProblemCode::RequestNotAccepted)
                                     .set_comment(Some(String::from("Revoked credential was used.")));
                                 Status::Failed(problem_report).code()
                             }
@@ -392,6 +499,14 @@ impl VerifierSM {
         }
     }
 
+    /// Problem report which failed or declined the protocol, either received or sent by us.
+    pub fn get_problem_report(&self) -> Option<ProblemReport> {
+        match self.state {
+            VerifierFullState::Finished(ref state) => state.status.problem_report().cloned(),
+            _ => None,
+        }
+    }
+
     pub fn presentation_request(&self) -> VcxResult<PresentationRequest> {
         match self.state {
             VerifierFullState::Initial(_) => Err(VcxError::from_msg(
@@ -857,6 +972,58 @@ pub mod unit_tests {
             assert_match!(VerifierFullState::PresentationProposalReceived(_), verifier_sm.state);
         }
 
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_verifier_negotiation_rounds_retain_history() {
+            let _setup = SetupMocks::init();
+
+            let mut verifier_sm = _verifier_sm_from_request();
+            let thread_id = verifier_sm.thread_id();
+            let first_request = verifier_sm.presentation_request().unwrap();
+            verifier_sm = verifier_sm.mark_presentation_request_msg_sent().unwrap();
+
+            let counter_proposal = _presentation_proposal().set_thread_id(&thread_id);
+            verifier_sm = verifier_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    VerifierMessages::PresentationProposalReceived(counter_proposal.clone()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(VerifierFullState::PresentationProposalReceived(_), verifier_sm.state);
+            assert_eq!(verifier_sm.presentation_proposal().unwrap(), counter_proposal);
+
+            verifier_sm = verifier_sm
+                .set_request(&_presentation_request_data(), Some("revised".into()))
+                .unwrap()
+                .mark_presentation_request_msg_sent()
+                .unwrap();
+            let revised_request = verifier_sm.presentation_request().unwrap();
+            assert_ne!(revised_request.id, first_request.id);
+            assert_eq!(revised_request.get_thread_id(), thread_id);
+            assert_eq!(verifier_sm.thread_id(), thread_id);
+            assert_eq!(
+                verifier_sm.get_negotiation_history(),
+                vec![
+                    PresentationNegotiationMessage::Request(first_request),
+                    PresentationNegotiationMessage::Proposal(counter_proposal),
+                    PresentationNegotiationMessage::Request(revised_request),
+                ]
+            );
+
+            verifier_sm = verifier_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    VerifierMessages::VerifyPresentation(_presentation()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(VerifierFullState::Finished(_), verifier_sm.state);
+            assert_eq!(verifier_sm.get_negotiation_history().len(), 3);
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_prover_handle_presentation_reject_message_from_presentation_request_sent_state() {
@@ -916,6 +1083,79 @@ pub mod unit_tests {
                 .unwrap();
             assert_match!(VerifierFullState::Finished(_), verifier_sm.state);
         }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_verifier_handle_expired_presentation_request() {
+            let _setup = SetupMocks::init();
+
+            let verifier_sm = _verifier_sm_from_request()
+                .set_request_expiration(Utc::now() + chrono::Duration::hours(1))
+                .unwrap()
+                .to_presentation_request_sent_state();
+            assert!(!verifier_sm.is_expired());
+
+            let mut verifier_sm = _verifier_sm_from_request()
+                .set_request_expiration(Utc::now() - chrono::Duration::seconds(1))
+                .unwrap()
+                .to_presentation_request_sent_state();
+            assert!(verifier_sm.is_expired());
+
+            verifier_sm = verifier_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    VerifierMessages::PresentationRequestExpired,
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(VerifierState::Failed, verifier_sm.get_state());
+            assert_eq!(
+                Some(ProblemCode::RequestTime),
+                verifier_sm.get_problem_report().unwrap().get_code()
+            );
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_verifier_handle_presentation_after_request_expired() {
+            let _setup = SetupMocks::init();
+
+            let mut verifier_sm = _verifier_sm_from_request()
+                .set_request_expiration(Utc::now() - chrono::Duration::seconds(1))
+                .unwrap()
+                .to_presentation_request_sent_state();
+            verifier_sm = verifier_sm
+                .step(
+                    _dummy_wallet_handle(),
+                    VerifierMessages::VerifyPresentation(_presentation()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(VerifierState::Failed, verifier_sm.get_state());
+            assert_eq!(
+                Some(ProblemCode::RequestTime),
+                verifier_sm.get_problem_report().unwrap().get_code()
+            );
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "general_test")]
+        async fn test_verifier_abandon_from_presentation_request_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut verifier_sm = _verifier_sm_from_request().to_presentation_request_sent_state();
+            verifier_sm = verifier_sm.abandon(Some(_reason()), _send_message()).await.unwrap();
+
+            assert_eq!(VerifierState::Failed, verifier_sm.get_state());
+            assert!(verifier_sm.presentation_request().is_ok());
+            assert_eq!(
+                Some(ProblemCode::PresentationAbandoned),
+                verifier_sm.get_problem_report().unwrap().get_code()
+            );
+        }
     }
 
     mod find_message_to_handle {
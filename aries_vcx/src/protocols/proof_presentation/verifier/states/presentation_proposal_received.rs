@@ -1,15 +1,19 @@
 use crate::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct PresentationProposalReceivedState {
     pub presentation_proposal: PresentationProposal,
     pub presentation_request: Option<PresentationRequest>,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl PresentationProposalReceivedState {
     pub fn new(presentation_proposal: PresentationProposal) -> Self {
         Self {
+            negotiation: vec![PresentationNegotiationMessage::Proposal(presentation_proposal.clone())],
             presentation_proposal,
             ..Self::default()
         }
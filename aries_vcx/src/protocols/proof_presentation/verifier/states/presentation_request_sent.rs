@@ -5,14 +5,19 @@ use crate::global::settings;
 use crate::libindy::proofs::verifier::verifier::validate_indy_proof;
 use crate::messages::error::ProblemReport;
 use crate::messages::proof_presentation::presentation::Presentation;
+use crate::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::messages::status::Status;
 use crate::protocols::proof_presentation::verifier::state_machine::RevocationStatus;
 use crate::protocols::proof_presentation::verifier::states::finished::FinishedState;
+use crate::protocols::proof_presentation::verifier::states::presentation_proposal_received::PresentationProposalReceivedState;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PresentationRequestSentState {
     pub presentation_request: PresentationRequest,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl PresentationRequestSentState {
@@ -60,6 +65,7 @@ impl From<(PresentationRequestSentState, Presentation, RevocationStatus)> for Fi
             presentation: Some(presentation),
             status: Status::Success,
             revocation_status: Some(was_revoked),
+            negotiation: state.negotiation,
         }
     }
 }
@@ -72,6 +78,20 @@ impl From<(PresentationRequestSentState, ProblemReport)> for FinishedState {
             presentation: None,
             status: Status::Failed(problem_report),
             revocation_status: None,
+            negotiation: state.negotiation,
+        }
+    }
+}
+
+impl From<(PresentationRequestSentState, PresentationProposal)> for PresentationProposalReceivedState {
+    fn from((state, presentation_proposal): (PresentationRequestSentState, PresentationProposal)) -> Self {
+        trace!("transit state from PresentationRequestSentState to PresentationProposalReceivedState");
+        let mut negotiation = state.negotiation;
+        negotiation.push(PresentationNegotiationMessage::Proposal(presentation_proposal.clone()));
+        PresentationProposalReceivedState {
+            presentation_proposal,
+            presentation_request: Some(state.presentation_request),
+            negotiation,
         }
     }
 }
@@ -1,14 +1,30 @@
 use crate::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::protocols::proof_presentation::verifier::states::presentation_request_sent::PresentationRequestSentState;
+use crate::protocols::proof_presentation::PresentationNegotiationMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct PresentationRequestSetState {
     pub presentation_request: PresentationRequest,
+    #[serde(default)]
+    pub negotiation: Vec<PresentationNegotiationMessage>,
 }
 
 impl PresentationRequestSetState {
     pub fn new(presentation_request: PresentationRequest) -> Self {
-        Self { presentation_request }
+        Self {
+            presentation_request,
+            negotiation: vec![],
+        }
+    }
+
+    /// Sets messages exchanged in previous negotiation rounds, the request itself is appended as
+    /// the latest round.
+    pub fn set_negotiation(mut self, mut negotiation: Vec<PresentationNegotiationMessage>) -> Self {
+        negotiation.push(PresentationNegotiationMessage::Request(
+            self.presentation_request.clone(),
+        ));
+        self.negotiation = negotiation;
+        self
     }
 }
 
@@ -17,6 +33,7 @@ impl From<PresentationRequestSetState> for PresentationRequestSentState {
         trace!("transit state from PresentationRequestSetState to PresentationRequestSentState");
         PresentationRequestSentState {
             presentation_request: state.presentation_request,
+            negotiation: state.negotiation,
         }
     }
 }
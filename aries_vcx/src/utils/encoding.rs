@@ -0,0 +1,124 @@
+use chrono::{Datelike, NaiveDate};
+use serde_json::Value;
+
+use crate::error::prelude::*;
+use crate::messages::mime_type::MimeType;
+use crate::utils::openssl::encode;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_INTEGER_FORMAT: &str = "%Y%m%d";
+
+/// Raw value of the attribute as it is issued in the credential. Dates given as `YYYY-MM-DD` or
+/// `YYYYMMDD` are issued as `YYYYMMDD`, which is encoded as integer and so can be used in predicates.
+/// Images must be base64 encoded and are issued unchanged.
+pub fn normalize_raw_value(value: &str, mime_type: &MimeType) -> VcxResult<String> {
+    match mime_type {
+        MimeType::Plain => Ok(value.to_string()),
+        MimeType::Date => Ok(date_to_integer(value)?.to_string()),
+        MimeType::Jpeg | MimeType::Png => {
+            base64::decode(value).map_err(|err| {
+                VcxError::from_msg(
+                    VcxErrorKind::EncodeError,
+                    format!("Value of {:?} attribute is not base64 encoded: {}", mime_type, err),
+                )
+            })?;
+            Ok(value.to_string())
+        }
+    }
+}
+
+/// Encodes the attribute value by the Indy rule after normalizing it according to `mime_type`.
+pub fn encode_attribute(value: &str, mime_type: &MimeType) -> VcxResult<String> {
+    encode(&normalize_raw_value(value, mime_type)?)
+}
+
+/// MIME type of attribute given in Aries format `{"name": .., "value": .., "mime-type": ..}`, plain
+/// text unless specified.
+pub fn parse_mime_type(attribute: &Value) -> VcxResult<MimeType> {
+    match attribute.get("mime-type") {
+        None | Some(Value::Null) => Ok(MimeType::Plain),
+        Some(mime_type) => serde_json::from_value(mime_type.clone()).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidAttributesStructure,
+                format!("Unsupported mime-type {} of attribute: {}", mime_type, err),
+            )
+        }),
+    }
+}
+
+/// Date given as `YYYY-MM-DD` or `YYYYMMDD` converted to `YYYYMMDD` integer, the form in which date
+/// attributes are issued and compared by predicates.
+pub fn date_to_integer(date: &str) -> VcxResult<i32> {
+    let date = NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .or_else(|_| NaiveDate::parse_from_str(date, DATE_INTEGER_FORMAT))
+        .map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::EncodeError,
+                format!("Cannot parse date {:?}: {}", date, err),
+            )
+        })?;
+    if date.year() < 0 || date.year() > 9999 {
+        return Err(VcxError::from_msg(
+            VcxErrorKind::EncodeError,
+            format!("Year of date {} is out of range", date),
+        ));
+    }
+    Ok(date.year() * 10000 + date.month() as i32 * 100 + date.day() as i32)
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_date_to_integer() {
+        assert_eq!(date_to_integer("2021-03-05").unwrap(), 20210305);
+        assert_eq!(date_to_integer("20210305").unwrap(), 20210305);
+        assert_eq!(
+            date_to_integer("2021-02-30").unwrap_err().kind(),
+            VcxErrorKind::EncodeError
+        );
+        assert_eq!(
+            date_to_integer("05/03/2021").unwrap_err().kind(),
+            VcxErrorKind::EncodeError
+        );
+    }
+
+    #[test]
+    fn test_encode_attribute_by_mime_type() {
+        assert_eq!(
+            encode_attribute("Cat", &MimeType::Plain).unwrap(),
+            encode("Cat").unwrap()
+        );
+        assert_eq!(encode_attribute("2021-03-05", &MimeType::Date).unwrap(), "20210305");
+        assert_eq!(normalize_raw_value("2021-03-05", &MimeType::Date).unwrap(), "20210305");
+
+        let image = base64::encode(&[0x89, 0x50, 0x4e, 0x47]);
+        assert_eq!(normalize_raw_value(&image, &MimeType::Png).unwrap(), image);
+        assert_eq!(
+            encode_attribute(&image, &MimeType::Png).unwrap(),
+            encode(&image).unwrap()
+        );
+        assert_eq!(
+            encode_attribute("not base64!", &MimeType::Jpeg).unwrap_err().kind(),
+            VcxErrorKind::EncodeError
+        );
+    }
+
+    #[test]
+    fn test_parse_mime_type() {
+        let attribute = json!({"name": "photo", "value": "iVBORw==", "mime-type": "image/png"});
+        assert_eq!(parse_mime_type(&attribute).unwrap(), MimeType::Png);
+        assert_eq!(
+            parse_mime_type(&json!({"name": "name", "value": "Alice"})).unwrap(),
+            MimeType::Plain
+        );
+        assert_eq!(
+            parse_mime_type(&json!({"name": "doc", "value": "", "mime-type": "application/pdf"}))
+                .unwrap_err()
+                .kind(),
+            VcxErrorKind::InvalidAttributesStructure
+        );
+    }
+}
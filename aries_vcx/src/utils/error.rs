@@ -454,6 +454,18 @@ pub static CREATE_AGENT: Error = Error {
     code_num: 1113,
     message: "Error creating agent in agency",
 };
+pub static MESSAGE_EXPIRED: Error = Error {
+    code_num: 1114,
+    message: "Message has expired",
+};
+pub static CREDENTIAL_PREVIEW_SCHEMA_MISMATCH: Error = Error {
+    code_num: 1115,
+    message: "Credential preview attributes do not match the schema",
+};
+pub static CREDENTIAL_PREVIEW_VALUES_MISMATCH: Error = Error {
+    code_num: 1116,
+    message: "Issued credential values do not match the credential preview",
+};
 
 lazy_static! {
     static ref ERROR_C_MESSAGES: HashMap<u32, CString> = {
@@ -560,6 +572,9 @@ lazy_static! {
         insert_c_message(&mut m, &ACTION_NOT_SUPPORTED);
         insert_c_message(&mut m, &INVALID_REDIRECT_DETAILS);
         insert_c_message(&mut m, &NO_AGENT_INFO);
+        insert_c_message(&mut m, &MESSAGE_EXPIRED);
+        insert_c_message(&mut m, &CREDENTIAL_PREVIEW_SCHEMA_MISMATCH);
+        insert_c_message(&mut m, &CREDENTIAL_PREVIEW_VALUES_MISMATCH);
 
         m
     };
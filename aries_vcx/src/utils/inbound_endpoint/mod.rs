@@ -0,0 +1,108 @@
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::protocols::InboundCallback;
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+
+#[cfg(feature = "http_endpoint")]
+pub mod server;
+
+pub const CONTENT_TYPE_DIDCOMM_ENCRYPTED: &str = "application/didcomm-envelope-enc";
+pub const CONTENT_TYPE_SSI_AGENT_WIRE: &str = "application/ssi-agent-wire";
+
+pub fn is_supported_content_type(content_type: Option<&str>) -> bool {
+    match content_type {
+        Some(content_type) => {
+            let media_type = content_type.split(';').next().unwrap_or_default().trim();
+            media_type.eq_ignore_ascii_case(CONTENT_TYPE_DIDCOMM_ENCRYPTED)
+                || media_type.eq_ignore_ascii_case(CONTENT_TYPE_SSI_AGENT_WIRE)
+        }
+        None => false,
+    }
+}
+
+pub async fn process_inbound_envelope(
+    wallet_handle: WalletHandle,
+    content_type: Option<&str>,
+    payload: Vec<u8>,
+    on_message: &InboundCallback,
+) -> VcxResult<()> {
+    trace!(
+        "process_inbound_envelope >>> content_type: {:?}, payload of {} bytes",
+        content_type,
+        payload.len()
+    );
+    if !is_supported_content_type(content_type) {
+        return Err(VcxError::from_msg(
+            VcxErrorKind::InvalidMessageFormat,
+            format!("Unsupported content type of inbound message: {:?}", content_type),
+        ));
+    }
+    let message = EncryptionEnvelope::anon_unpack(wallet_handle, payload).await?;
+    on_message(message).await
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::{Arc, Mutex};
+
+    use agency_client::testing::mocking::AgencyMockDecrypted;
+
+    use crate::messages::a2a::A2AMessage;
+    use crate::messages::ack::test_utils::_ack;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _on_message(received: Arc<Mutex<Vec<A2AMessage>>>) -> InboundCallback {
+        Box::new(move |message: A2AMessage| {
+            received.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    #[test]
+    fn test_is_supported_content_type() {
+        assert!(is_supported_content_type(Some("application/didcomm-envelope-enc")));
+        assert!(is_supported_content_type(Some(
+            "application/ssi-agent-wire; charset=utf-8"
+        )));
+        assert!(!is_supported_content_type(Some("application/json")));
+        assert!(!is_supported_content_type(None));
+    }
+
+    #[tokio::test]
+    async fn test_process_inbound_envelope() {
+        let _setup = SetupMocks::init();
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(_ack().to_a2a_message()).to_string());
+        let received = Arc::new(Mutex::new(vec![]));
+
+        process_inbound_envelope(
+            WalletHandle(0),
+            Some(CONTENT_TYPE_DIDCOMM_ENCRYPTED),
+            vec![],
+            &_on_message(received.clone()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*received.lock().unwrap(), vec![_ack().to_a2a_message()]);
+    }
+
+    #[tokio::test]
+    async fn test_process_inbound_envelope_rejects_unsupported_content_type() {
+        let _setup = SetupMocks::init();
+        let received = Arc::new(Mutex::new(vec![]));
+        let err = process_inbound_envelope(
+            WalletHandle(0),
+            Some("text/plain"),
+            vec![],
+            &_on_message(received.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::InvalidMessageFormat);
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::body::HttpBody;
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::protocols::InboundCallback;
+use crate::utils::inbound_endpoint::{is_supported_content_type, process_inbound_envelope};
+
+/// Requests with a larger body are rejected with 413 Payload Too Large.
+pub const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Listens for DIDComm envelopes POSTed to `addr`, unpacks them and hands the resulting
+/// messages to `on_message`. Resolves only if the server fails.
+pub async fn serve(wallet_handle: WalletHandle, addr: SocketAddr, on_message: InboundCallback) -> VcxResult<()> {
+    trace!("inbound_endpoint::serve >>> addr: {}", addr);
+    let on_message = Arc::new(on_message);
+    let make_service = make_service_fn(move |_| {
+        let on_message = on_message.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let on_message = on_message.clone();
+                async move { Ok::<_, Infallible>(handle_request(wallet_handle, request, &on_message).await) }
+            }))
+        }
+    });
+    Server::bind(&addr).serve(make_service).await.map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::IOError,
+            format!("Inbound endpoint server failed: {}", err),
+        )
+    })
+}
+
+async fn handle_request(
+    wallet_handle: WalletHandle,
+    request: Request<Body>,
+    on_message: &InboundCallback,
+) -> Response<Body> {
+    if request.method() != Method::POST {
+        return _response(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let content_type = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    if !is_supported_content_type(content_type.as_deref()) {
+        return _response(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+    let content_length = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if content_length.map_or(false, |length| length > MAX_BODY_SIZE) {
+        return _response(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    let payload = match _read_body(request.into_body()).await {
+        Ok(payload) => payload,
+        Err(status) => return _response(status),
+    };
+    match process_inbound_envelope(wallet_handle, content_type.as_deref(), payload, on_message).await {
+        Ok(()) => _response(StatusCode::ACCEPTED),
+        Err(err) => {
+            warn!("inbound_endpoint >>> failed to process inbound message, err: {:?}", err);
+            match err.kind() {
+                VcxErrorKind::InvalidJson | VcxErrorKind::InvalidMessageFormat => _response(StatusCode::BAD_REQUEST),
+                _ => _response(StatusCode::INTERNAL_SERVER_ERROR),
+            }
+        }
+    }
+}
+
+async fn _read_body(mut body: Body) -> Result<Vec<u8>, StatusCode> {
+    let mut payload = vec![];
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|err| {
+            warn!("inbound_endpoint >>> failed to read request body, err: {:?}", err);
+            StatusCode::BAD_REQUEST
+        })?;
+        if payload.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        payload.extend_from_slice(&chunk);
+    }
+    Ok(payload)
+}
+
+fn _response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::messages::a2a::A2AMessage;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::inbound_endpoint::CONTENT_TYPE_DIDCOMM_ENCRYPTED;
+
+    use super::*;
+
+    fn _on_message(received: Arc<Mutex<Vec<A2AMessage>>>) -> InboundCallback {
+        Box::new(move |message: A2AMessage| {
+            received.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    fn _request(body: Vec<u8>, content_length: Option<usize>) -> Request<Body> {
+        let mut request = Request::post("/").header(CONTENT_TYPE, CONTENT_TYPE_DIDCOMM_ENCRYPTED);
+        if let Some(content_length) = content_length {
+            request = request.header(CONTENT_LENGTH, content_length);
+        }
+        request.body(Body::from(body)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_rejects_declared_oversized_body() {
+        let _setup = SetupMocks::init();
+        let received = Arc::new(Mutex::new(vec![]));
+        let response = handle_request(
+            WalletHandle(0),
+            _request(vec![], Some(MAX_BODY_SIZE + 1)),
+            &_on_message(received.clone()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_rejects_oversized_body_without_content_length() {
+        let _setup = SetupMocks::init();
+        let received = Arc::new(Mutex::new(vec![]));
+        let response = handle_request(
+            WalletHandle(0),
+            _request(vec![0; MAX_BODY_SIZE + 1], None),
+            &_on_message(received.clone()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
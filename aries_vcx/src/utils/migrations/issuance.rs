@@ -0,0 +1,89 @@
+use serde_json::Value;
+
+use crate::error::prelude::*;
+use crate::handlers::issuance::issuer::_build_credential_preview;
+use crate::messages::issuance::credential_offer::CredentialOffer;
+use crate::utils::migrations::{lift_thread_id, migrate_state_machine};
+
+/// Besides the thread id, issuer states before schema version 2 kept the libindy credential offer
+/// instead of the offer message which was sent to the holder.
+pub fn issuer_v1_to_v2(issuer: Value) -> VcxResult<Value> {
+    migrate_state_machine(issuer, "issuer_sm", |issuer_sm| {
+        let mut issuer_sm = lift_thread_id(issuer_sm);
+        let thread_id = issuer_sm["thread_id"].as_str().unwrap_or_default().to_string();
+        if let Some(states) = issuer_sm.get_mut("state").and_then(Value::as_object_mut) {
+            for state in states.values_mut() {
+                if let (Some(offer), Some(cred_data)) = (state["offer"].as_str(), state["cred_data"].as_str()) {
+                    let offer = CredentialOffer::create()
+                        .set_id(&thread_id)
+                        .set_credential_preview_data(_build_credential_preview(cred_data)?)
+                        .set_offers_attach(offer)?;
+                    state["offer"] = json!(offer);
+                }
+            }
+        }
+        Ok(issuer_sm)
+    })
+}
+
+pub fn holder_v1_to_v2(holder: Value) -> VcxResult<Value> {
+    migrate_state_machine(holder, "holder_sm", |holder_sm| Ok(lift_thread_id(holder_sm)))
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use crate::handlers::issuance::holder::Holder;
+    use crate::handlers::issuance::issuer::Issuer;
+    use crate::protocols::issuance::holder::state_machine::HolderState;
+    use crate::protocols::issuance::issuer::state_machine::IssuerState;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mockdata_credex::{
+        CREDENTIAL_ISSUER_SM_FINISHED, CREDENTIAL_ISSUER_SM_OFFER_SENT, CREDENTIAL_ISSUER_SM_REQUEST_RECEIVED,
+        CREDENTIAL_SM_FINISHED, CREDENTIAL_SM_OFFER_RECEIVED,
+    };
+
+    use super::*;
+
+    // fixtures are wrapped the way libvcx serializes handlers
+    fn _handler<T: serde::de::DeserializeOwned>(fixture: &str) -> T {
+        let fixture: Value = serde_json::from_str(fixture).unwrap();
+        serde_json::from_value(fixture["data"].clone()).unwrap()
+    }
+
+    #[test]
+    fn test_issuer_migrates_legacy_offer_sent() {
+        let _setup = SetupMocks::init();
+        let issuer: Issuer = _handler(CREDENTIAL_ISSUER_SM_OFFER_SENT);
+        assert_eq!(issuer.get_state(), IssuerState::OfferSent);
+        assert_eq!(issuer.get_thread_id().unwrap(), "cb54e2f9-ee17-488c-9bc7-d70c29cff802");
+
+        let issuer: Issuer = _handler(CREDENTIAL_ISSUER_SM_REQUEST_RECEIVED);
+        assert_eq!(issuer.get_state(), IssuerState::RequestReceived);
+        assert_eq!(issuer.get_thread_id().unwrap(), "cb54e2f9-ee17-488c-9bc7-d70c29cff802");
+    }
+
+    #[test]
+    fn test_issuer_keeps_thread_id_of_state_machine() {
+        let _setup = SetupMocks::init();
+        let issuer: Issuer = _handler(CREDENTIAL_ISSUER_SM_FINISHED);
+        assert_eq!(issuer.get_state(), IssuerState::Finished);
+        assert_eq!(issuer.get_thread_id().unwrap(), "0a794e24-c6b4-46bc-93b6-642b6dc98c98");
+
+        let serialized = json!(issuer);
+        assert_eq!(serialized["schema_version"], 2);
+        let issuer: Issuer = serde_json::from_value(serialized).unwrap();
+        assert_eq!(issuer.get_state(), IssuerState::Finished);
+    }
+
+    #[test]
+    fn test_holder_migrates_legacy_states() {
+        let _setup = SetupMocks::init();
+        let holder: Holder = _handler(CREDENTIAL_SM_OFFER_RECEIVED);
+        assert_eq!(holder.get_state(), HolderState::OfferReceived);
+
+        let holder: Holder = _handler(CREDENTIAL_SM_FINISHED);
+        assert_eq!(holder.get_state(), HolderState::Finished);
+        assert_eq!(json!(holder)["schema_version"], 2);
+    }
+}
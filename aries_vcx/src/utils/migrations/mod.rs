@@ -0,0 +1,198 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::prelude::*;
+
+pub mod issuance;
+pub mod proof_presentation;
+
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+pub type Migration = fn(Value) -> VcxResult<Value>;
+
+/// Upgrades serialized objects to the current layout. `migrations[n]` upgrades an object of schema
+/// version `n + 1` to version `n + 2`, so the current schema version is the number of migrations plus
+/// one. Objects serialized before schema versions were introduced are considered to be of version 1.
+pub struct MigrationChain {
+    pub name: &'static str,
+    pub migrations: &'static [Migration],
+}
+
+pub static CONNECTION_MIGRATIONS: MigrationChain = MigrationChain {
+    name: "Connection",
+    migrations: &[],
+};
+
+pub static ISSUER_MIGRATIONS: MigrationChain = MigrationChain {
+    name: "Issuer",
+    migrations: &[issuance::issuer_v1_to_v2],
+};
+
+pub static HOLDER_MIGRATIONS: MigrationChain = MigrationChain {
+    name: "Holder",
+    migrations: &[issuance::holder_v1_to_v2],
+};
+
+pub static PROVER_MIGRATIONS: MigrationChain = MigrationChain {
+    name: "Prover",
+    migrations: &[proof_presentation::prover_v1_to_v2],
+};
+
+pub static VERIFIER_MIGRATIONS: MigrationChain = MigrationChain {
+    name: "Verifier",
+    migrations: &[proof_presentation::verifier_v1_to_v2],
+};
+
+impl MigrationChain {
+    pub fn schema_version(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+
+    /// Returns the object upgraded to the current schema version, without the version key.
+    pub fn migrate(&self, mut value: Value) -> VcxResult<Value> {
+        let version = match value.get(SCHEMA_VERSION_KEY) {
+            Some(version) => version.as_u64().ok_or_else(|| {
+                VcxError::from_msg(
+                    VcxErrorKind::InvalidJson,
+                    format!("Invalid schema version of serialized {}: {}", self.name, version),
+                )
+            })? as u32,
+            None => 1,
+        };
+        if version == 0 || version > self.schema_version() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!(
+                    "Cannot deserialize {} of schema version {}, the latest supported version is {}",
+                    self.name,
+                    version,
+                    self.schema_version()
+                ),
+            ));
+        }
+        if let Some(object) = value.as_object_mut() {
+            object.remove(SCHEMA_VERSION_KEY);
+        }
+        for (index, migration) in self.migrations.iter().enumerate().skip(version as usize - 1) {
+            trace!(
+                "MigrationChain::migrate >>> migrating {} from schema version {} to {}",
+                self.name,
+                index + 1,
+                index + 2
+            );
+            value = migration(value)?;
+        }
+        Ok(value)
+    }
+
+    /// Upgrades serialized handler and deserializes its state machine kept under `key`.
+    pub fn state_machine<T: DeserializeOwned>(&self, value: Value, key: &str) -> VcxResult<T> {
+        let state_machine = self
+            .migrate(value)?
+            .as_object_mut()
+            .and_then(|object| object.remove(key))
+            .unwrap_or_default();
+        serde_json::from_value(state_machine).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Cannot deserialize {}: {}", self.name, err),
+            )
+        })
+    }
+
+    pub fn with_schema_version(&self, mut value: Value) -> Value {
+        if let Some(object) = value.as_object_mut() {
+            object.insert(SCHEMA_VERSION_KEY.to_string(), json!(self.schema_version()));
+        }
+        value
+    }
+}
+
+/// State machines before schema version 2 kept the thread id in their states, along with the handle of
+/// the connection the protocol was running on. Moves the thread id to the state machine.
+pub(crate) fn lift_thread_id(mut state_machine: Value) -> Value {
+    let mut state_thread_id = None;
+    if let Some(states) = state_machine.get_mut("state").and_then(Value::as_object_mut) {
+        for state in states.values_mut().filter_map(Value::as_object_mut) {
+            state.remove("connection_handle");
+            if let Some(Value::String(thread_id)) = state.remove("thread_id") {
+                state_thread_id = Some(thread_id);
+            }
+        }
+    }
+    let has_thread_id = state_machine
+        .get("thread_id")
+        .and_then(Value::as_str)
+        .map_or(false, |thread_id| !thread_id.is_empty());
+    if !has_thread_id {
+        state_machine["thread_id"] = json!(state_thread_id.unwrap_or_default());
+    }
+    state_machine
+}
+
+pub(crate) fn migrate_state_machine<F>(mut object: Value, key: &str, migration: F) -> VcxResult<Value>
+where
+    F: FnOnce(Value) -> VcxResult<Value>,
+{
+    let state_machine = object
+        .as_object_mut()
+        .and_then(|object| object.remove(key))
+        .ok_or_else(|| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!("Serialized object is missing {}", key),
+            )
+        })?;
+    object[key] = migration(state_machine)?;
+    Ok(object)
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    fn _add_field(mut value: Value) -> VcxResult<Value> {
+        value["field"] = json!("added");
+        Ok(value)
+    }
+
+    static TEST_MIGRATIONS: MigrationChain = MigrationChain {
+        name: "Test",
+        migrations: &[_add_field],
+    };
+
+    #[test]
+    fn test_migrate_unversioned_object() {
+        let migrated = TEST_MIGRATIONS.migrate(json!({})).unwrap();
+        assert_eq!(migrated, json!({"field": "added"}));
+        assert_eq!(
+            TEST_MIGRATIONS.with_schema_version(migrated),
+            json!({"field": "added", "schema_version": 2})
+        );
+    }
+
+    #[test]
+    fn test_migrate_skips_applied_migrations_and_rejects_newer_versions() {
+        let current = json!({"schema_version": 2});
+        assert_eq!(TEST_MIGRATIONS.migrate(current).unwrap(), json!({}));
+        assert_eq!(
+            TEST_MIGRATIONS
+                .migrate(json!({"schema_version": 3}))
+                .unwrap_err()
+                .kind(),
+            VcxErrorKind::InvalidJson
+        );
+    }
+
+    #[test]
+    fn test_lift_thread_id() {
+        let legacy = json!({
+            "source_id": "source_id",
+            "state": {"OfferSent": {"cred_data": "{}", "connection_handle": 1, "thread_id": "thread_id"}}
+        });
+        let migrated = lift_thread_id(legacy);
+        assert_eq!(migrated["thread_id"], "thread_id");
+        assert_eq!(migrated["state"]["OfferSent"], json!({"cred_data": "{}"}));
+    }
+}
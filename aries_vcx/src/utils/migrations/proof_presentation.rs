@@ -0,0 +1,84 @@
+use serde_json::Value;
+
+use crate::error::prelude::*;
+use crate::messages::proof_presentation::presentation_request::{PresentationRequest, PresentationRequestData};
+use crate::utils::migrations::{lift_thread_id, migrate_state_machine};
+
+pub fn prover_v1_to_v2(prover: Value) -> VcxResult<Value> {
+    migrate_state_machine(prover, "prover_sm", |prover_sm| Ok(lift_thread_id(prover_sm)))
+}
+
+/// Verifiers before schema version 2 kept the proof request data of a request which was not sent yet
+/// in the initial state, it is now kept as the presentation request message of the set state.
+pub fn verifier_v1_to_v2(verifier: Value) -> VcxResult<Value> {
+    migrate_state_machine(verifier, "verifier_sm", |verifier_sm| {
+        let mut verifier_sm = lift_thread_id(verifier_sm);
+        let request_data = verifier_sm
+            .get_mut("state")
+            .and_then(|state| state.get_mut("Initial"))
+            .and_then(Value::as_object_mut)
+            .and_then(|initial| initial.remove("presentation_request_data"));
+        if let Some(request_data) = request_data {
+            let request_data: PresentationRequestData = serde_json::from_value(request_data).map_err(|err| {
+                VcxError::from_msg(
+                    VcxErrorKind::InvalidJson,
+                    format!("Cannot migrate presentation request data of Verifier: {}", err),
+                )
+            })?;
+            let presentation_request = PresentationRequest::create()
+                .set_id(verifier_sm["thread_id"].as_str().unwrap_or_default().to_string())
+                .set_request_presentations_attach(&request_data)?;
+            verifier_sm["state"] = json!({
+                "PresentationRequestSet": { "presentation_request": presentation_request }
+            });
+        }
+        Ok(verifier_sm)
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use crate::handlers::proof_presentation::verifier::Verifier;
+    use crate::protocols::proof_presentation::verifier::state_machine::VerifierState;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mockdata_proof::{
+        SERIALIZIED_PROOF_INITIAL, SERIALIZIED_PROOF_PRESENTATION_REQUEST_SENT, SERIALIZIED_PROOF_REVOKED,
+    };
+
+    use super::*;
+
+    fn _verifier(fixture: &str) -> Verifier {
+        let fixture: Value = serde_json::from_str(fixture).unwrap();
+        serde_json::from_value(fixture["data"].clone()).unwrap()
+    }
+
+    #[test]
+    fn test_verifier_migrates_legacy_initial_state() {
+        let _setup = SetupMocks::init();
+        let verifier = _verifier(SERIALIZIED_PROOF_INITIAL);
+        assert_eq!(verifier.get_state(), VerifierState::PresentationRequestSet);
+        let request_data = verifier
+            .get_presentation_request()
+            .unwrap()
+            .get_presentation_request_data()
+            .unwrap();
+        assert_eq!(request_data.nonce, "953886567052794155092952");
+    }
+
+    #[test]
+    fn test_verifier_drops_legacy_connection_handle() {
+        let _setup = SetupMocks::init();
+        let verifier = _verifier(SERIALIZIED_PROOF_PRESENTATION_REQUEST_SENT);
+        assert_eq!(verifier.get_state(), VerifierState::PresentationRequestSent);
+
+        let verifier = _verifier(SERIALIZIED_PROOF_REVOKED);
+        assert_eq!(verifier.get_state(), VerifierState::Finished);
+        let serialized = json!(verifier);
+        assert_eq!(serialized["schema_version"], 2);
+        assert!(serialized["verifier_sm"]["state"]["Finished"]
+            .get("connection_handle")
+            .is_none());
+        assert_eq!(serde_json::from_value::<Verifier>(serialized).unwrap(), verifier);
+    }
+}
@@ -6,6 +6,7 @@ use crate::error::{VcxError, VcxResult};
 static MOCKED_GENERATED_PROOF: &str = "mocked_proof";
 static MOCKED_RETRIEVED_CREDS: &str = "mocked_retrieved_creds";
 static MOCKED_VALIDATE_INDY_PROOF: &str = "mocked_validate_indy_proof";
+static MOCKED_FOUND_CREDS: &str = "mocked_found_creds";
 
 lazy_static! {
     static ref MOCK_SETTINGS: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
@@ -39,6 +40,18 @@ impl MockBuilder {
         self
     }
 
+    /// Sets JSON array of credentials matched by next credential search, which are then fetched
+    /// from it in batches.
+    pub fn set_mock_creds_found_by_search(self, found_creds: &str) -> MockBuilder {
+        warn!(
+            "MockBuilder::set_mock_creds_found_by_search >>> found_creds: {}",
+            found_creds
+        );
+        let mut settings = MOCK_SETTINGS.write().unwrap();
+        settings.insert(String::from(MOCKED_FOUND_CREDS), found_creds.into());
+        self
+    }
+
     pub fn set_mock_result_for_validate_indy_proof(self, result: VcxResult<bool>) -> MockBuilder {
         warn!(
             "MockBuilder::set_mock_result_for_validate_indy_proof >>> result: {:?}",
@@ -73,6 +86,22 @@ pub fn get_mock_creds_retrieved_for_proof_request() -> Option<String> {
     config.get(MOCKED_RETRIEVED_CREDS).map(String::from)
 }
 
+pub fn get_mock_creds_found_by_search() -> Option<Vec<serde_json::Value>> {
+    let config = MOCK_SETTINGS.read().unwrap();
+    config
+        .get(MOCKED_FOUND_CREDS)
+        .map(|found_creds| serde_json::from_str(found_creds).unwrap())
+}
+
+/// Takes next batch of credentials of the mocked credential search.
+pub fn take_mock_creds_found_by_search(count: usize) -> Option<Vec<serde_json::Value>> {
+    let mut found_creds = get_mock_creds_found_by_search()?;
+    let remaining = found_creds.split_off(std::cmp::min(count, found_creds.len()));
+    let mut config = MOCK_SETTINGS.write().unwrap();
+    config.insert(String::from(MOCKED_FOUND_CREDS), json!(remaining).to_string());
+    Some(found_creds)
+}
+
 pub fn get_mock_result_for_validate_indy_proof() -> Option<VcxResult<bool>> {
     let config = MOCK_SETTINGS_RESULT_BOOL.read().unwrap();
     config.get(MOCKED_VALIDATE_INDY_PROOF).map(|result| match result {
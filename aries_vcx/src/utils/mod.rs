@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 use crate::did_doc::DidDoc;
 use crate::error::VcxResult;
+use crate::global::outbound_queue;
 use crate::messages::a2a::A2AMessage;
 use crate::utils::encryption_envelope::EncryptionEnvelope;
 
@@ -46,11 +47,15 @@ macro_rules! map (
 pub mod author_agreement;
 #[rustfmt::skip]
 pub mod constants;
+pub mod encoding;
 pub mod error;
 pub mod file;
 pub mod json;
+pub mod migrations;
 pub mod mockdata;
 pub mod openssl;
+pub mod outbound_queue;
+pub mod protocol_repository;
 pub mod provision;
 pub mod qualifier;
 pub mod random;
@@ -60,6 +65,7 @@ pub mod uuid;
 pub mod test_logger;
 pub mod encryption_envelope;
 pub mod filters;
+pub mod inbound_endpoint;
 pub mod serialization;
 pub mod service_resolvable;
 pub mod validation;
@@ -78,6 +84,12 @@ pub async fn send_message(
 ) -> VcxResult<()> {
     trace!("send_message >>> message: {:?}, did_doc: {:?}", message, &did_doc);
     let envelope = EncryptionEnvelope::create(wallet_handle, &message, Some(&sender_verkey), &did_doc).await?;
+    if let Some(queue) = outbound_queue::get_outbound_queue() {
+        queue
+            .enqueue(&sender_verkey, &did_doc.get_endpoint(), envelope.0)
+            .await?;
+        return Ok(());
+    }
     agency_client::httpclient::post_message(&envelope.0, &did_doc.get_endpoint()).await?;
     Ok(())
 }
@@ -93,6 +105,10 @@ pub async fn send_message_anonymously(
         &did_doc
     );
     let envelope = EncryptionEnvelope::create(wallet_handle, message, None, did_doc).await?;
+    if let Some(queue) = outbound_queue::get_outbound_queue() {
+        queue.enqueue(&did_doc.id, &did_doc.get_endpoint(), envelope.0).await?;
+        return Ok(());
+    }
     agency_client::httpclient::post_message(&envelope.0, &did_doc.get_endpoint()).await?;
     Ok(())
 }
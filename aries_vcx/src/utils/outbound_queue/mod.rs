@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::future::BoxFuture;
+use futures::lock::Mutex;
+
+use crate::error::prelude::*;
+use crate::utils::outbound_queue::storage::OutboundQueueStorage;
+
+pub mod storage;
+
+pub type Transport = Box<dyn Fn(Vec<u8>, String) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboundMessageStatus {
+    Pending,
+    DeadLetter,
+}
+
+impl ToString for OutboundMessageStatus {
+    fn to_string(&self) -> String {
+        match self {
+            OutboundMessageStatus::Pending => "pending".to_string(),
+            OutboundMessageStatus::DeadLetter => "dead_letter".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QueuedMessage {
+    pub id: String,
+    /// Messages are delivered in order within a channel. It is the sender verkey of the pairwise
+    /// connection, or the recipient DID Doc id for anonymously packed messages.
+    pub channel_id: String,
+    pub sequence: i64,
+    pub endpoint: String,
+    #[serde(with = "envelope_base64")]
+    pub envelope: Vec<u8>,
+    pub attempts: u32,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub status: OutboundMessageStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(15 * 60),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(attempts.saturating_sub(1));
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+mod envelope_base64 {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(envelope: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(envelope))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let envelope = String::deserialize(deserializer)?;
+        base64::decode(&envelope).map_err(D::Error::custom)
+    }
+}
+
+/// Delivers packed messages in the order they were queued per channel. A message which can not be
+/// delivered blocks the messages queued after it on the same channel until it is delivered. After
+/// `RetryPolicy::max_attempts` failed attempts it is moved to the dead letter queue, where it keeps
+/// blocking the channel until it is retried or discarded. Channels are delivered independently.
+pub struct OutboundQueue {
+    storage: Box<dyn OutboundQueueStorage>,
+    transport: Transport,
+    retry_policy: RetryPolicy,
+    last_sequence: Mutex<i64>,
+    channel_locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl OutboundQueue {
+    pub fn new(storage: Box<dyn OutboundQueueStorage>, retry_policy: RetryPolicy) -> Self {
+        let transport: Transport = Box::new(|envelope, endpoint| {
+            Box::pin(async move {
+                agency_client::httpclient::post_message(&envelope, &endpoint).await?;
+                Ok(())
+            })
+        });
+        Self::with_transport(storage, retry_policy, transport)
+    }
+
+    pub fn with_transport(
+        storage: Box<dyn OutboundQueueStorage>,
+        retry_policy: RetryPolicy,
+        transport: Transport,
+    ) -> Self {
+        Self {
+            storage,
+            transport,
+            retry_policy,
+            last_sequence: Mutex::new(0),
+            channel_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Stores the envelope and attempts to deliver the queue of the channel right away. Delivery
+    /// failures are not returned to the caller, the message stays queued and is retried later.
+    pub async fn enqueue(&self, channel_id: &str, endpoint: &str, envelope: Vec<u8>) -> VcxResult<String> {
+        trace!(
+            "OutboundQueue::enqueue >>> channel_id: {}, endpoint: {}",
+            channel_id,
+            endpoint
+        );
+        let sequence = {
+            let mut last_sequence = self.last_sequence.lock().await;
+            *last_sequence = (*last_sequence + 1).max(Utc::now().timestamp_nanos());
+            *last_sequence
+        };
+        let message = QueuedMessage {
+            id: crate::utils::uuid::uuid(),
+            channel_id: channel_id.to_string(),
+            sequence,
+            endpoint: endpoint.to_string(),
+            envelope,
+            attempts: 0,
+            next_attempt_at: 0,
+            last_error: None,
+            status: OutboundMessageStatus::Pending,
+        };
+        let id = message.id.clone();
+        self.storage.add(message).await?;
+        self.process_channel(channel_id).await?;
+        Ok(id)
+    }
+
+    pub async fn process_channel(&self, channel_id: &str) -> VcxResult<()> {
+        let channel_lock = self
+            .channel_locks
+            .lock()
+            .await
+            .entry(channel_id.to_string())
+            .or_default()
+            .clone();
+        let _channel_lock = channel_lock.lock().await;
+        if !self
+            .storage
+            .get_by_channel(channel_id.to_string(), OutboundMessageStatus::DeadLetter)
+            .await?
+            .is_empty()
+        {
+            debug!(
+                "OutboundQueue >>> channel {} is blocked by a dead letter message, skipping delivery",
+                channel_id
+            );
+            return Ok(());
+        }
+        let mut pending = self
+            .storage
+            .get_by_channel(channel_id.to_string(), OutboundMessageStatus::Pending)
+            .await?;
+        pending.sort_by_key(|message| message.sequence);
+        for message in pending {
+            if !self.attempt_delivery(message).await? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Retries all pending messages which are due. Meant to be called periodically by the application.
+    pub async fn process_all(&self) -> VcxResult<()> {
+        trace!("OutboundQueue::process_all >>>");
+        let mut channel_ids: Vec<String> = self
+            .storage
+            .get_by_status(OutboundMessageStatus::Pending)
+            .await?
+            .into_iter()
+            .map(|message| message.channel_id)
+            .collect();
+        channel_ids.sort();
+        channel_ids.dedup();
+        for channel_id in channel_ids {
+            self.process_channel(&channel_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_pending(&self) -> VcxResult<Vec<QueuedMessage>> {
+        self.storage.get_by_status(OutboundMessageStatus::Pending).await
+    }
+
+    pub async fn get_dead_letters(&self) -> VcxResult<Vec<QueuedMessage>> {
+        self.storage.get_by_status(OutboundMessageStatus::DeadLetter).await
+    }
+
+    pub async fn retry_dead_letter(&self, id: &str) -> VcxResult<()> {
+        let mut message = self.get_dead_letter(id).await?;
+        message.status = OutboundMessageStatus::Pending;
+        message.attempts = 0;
+        message.next_attempt_at = 0;
+        let channel_id = message.channel_id.clone();
+        self.storage.update(message).await?;
+        self.process_channel(&channel_id).await
+    }
+
+    pub async fn discard_dead_letter(&self, id: &str) -> VcxResult<()> {
+        let message = self.get_dead_letter(id).await?;
+        let channel_id = message.channel_id.clone();
+        self.storage.delete(message.id).await?;
+        self.process_channel(&channel_id).await
+    }
+
+    async fn get_dead_letter(&self, id: &str) -> VcxResult<QueuedMessage> {
+        self.get_dead_letters()
+            .await?
+            .into_iter()
+            .find(|message| message.id == id)
+            .ok_or(VcxError::from_msg(
+                VcxErrorKind::InvalidOption,
+                format!("Dead letter message {} not found", id),
+            ))
+    }
+
+    // Returns whether the next message of the channel may be attempted
+    async fn attempt_delivery(&self, mut message: QueuedMessage) -> VcxResult<bool> {
+        let now = Utc::now().timestamp_millis();
+        if message.next_attempt_at > now {
+            return Ok(false);
+        }
+        match (self.transport)(message.envelope.clone(), message.endpoint.clone()).await {
+            Ok(()) => {
+                self.storage.delete(message.id).await?;
+                Ok(true)
+            }
+            Err(err) => {
+                message.attempts += 1;
+                message.last_error = Some(err.to_string());
+                if message.attempts >= self.retry_policy.max_attempts {
+                    warn!(
+                        "OutboundQueue >>> moving message {} to dead letter queue after {} attempts, err: {:?}",
+                        message.id, message.attempts, err
+                    );
+                    message.status = OutboundMessageStatus::DeadLetter;
+                    self.storage.update(message).await?;
+                    Ok(false)
+                } else {
+                    let backoff = self.retry_policy.backoff(message.attempts).as_millis() as i64;
+                    message.next_attempt_at = now + backoff;
+                    debug!(
+                        "OutboundQueue >>> delivery of message {} failed, retrying in {} ms, err: {:?}",
+                        message.id, backoff, err
+                    );
+                    self.storage.update(message).await?;
+                    Ok(false)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::Mutex as StdMutex;
+
+    use futures::channel::oneshot;
+    use futures::FutureExt;
+
+    use crate::utils::outbound_queue::storage::InMemoryStorage;
+
+    use super::*;
+
+    fn _policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(0),
+            ..RetryPolicy::default()
+        }
+    }
+
+    fn _transport(failures: Arc<StdMutex<u32>>, delivered: Arc<StdMutex<Vec<Vec<u8>>>>) -> Transport {
+        Box::new(move |envelope, _endpoint| {
+            let failures = failures.clone();
+            let delivered = delivered.clone();
+            Box::pin(async move {
+                let mut failures = failures.lock().unwrap();
+                if *failures > 0 {
+                    *failures -= 1;
+                    return Err(VcxError::from_msg(VcxErrorKind::IOError, "Endpoint unreachable"));
+                }
+                delivered.lock().unwrap().push(envelope);
+                Ok(())
+            })
+        })
+    }
+
+    fn _queue(max_attempts: u32, failures: u32) -> (OutboundQueue, Arc<StdMutex<u32>>, Arc<StdMutex<Vec<Vec<u8>>>>) {
+        let failures = Arc::new(StdMutex::new(failures));
+        let delivered = Arc::new(StdMutex::new(vec![]));
+        let queue = OutboundQueue::with_transport(
+            Box::new(InMemoryStorage::default()),
+            _policy(max_attempts),
+            _transport(failures.clone(), delivered.clone()),
+        );
+        (queue, failures, delivered)
+    }
+
+    #[test]
+    fn test_queued_message_keeps_envelope_as_base64() {
+        let message = QueuedMessage {
+            id: "id".into(),
+            channel_id: "conn".into(),
+            sequence: 1,
+            endpoint: "http://endpoint".into(),
+            envelope: vec![1, 2],
+            attempts: 0,
+            next_attempt_at: 0,
+            last_error: None,
+            status: OutboundMessageStatus::Pending,
+        };
+        let serialized = serde_json::to_value(&message).unwrap();
+        assert_eq!(serialized["envelope"], json!("AQI="));
+        assert_eq!(serde_json::from_value::<QueuedMessage>(serialized).unwrap(), message);
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(4), Duration::from_secs(8));
+        assert_eq!(policy.backoff(5), Duration::from_secs(10));
+        assert_eq!(policy.backoff(64), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_failed_message_is_retried_in_order() {
+        let (queue, _failures, delivered) = _queue(5, 1);
+        queue.enqueue("conn", "http://endpoint", vec![1]).await.unwrap();
+        queue.enqueue("conn", "http://endpoint", vec![2]).await.unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![1], vec![2]]);
+        assert!(queue.get_pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_message_is_kept_while_endpoint_is_down() {
+        let (queue, failures, delivered) = _queue(5, 100);
+        queue.enqueue("conn", "http://endpoint", vec![1]).await.unwrap();
+        let pending = queue.get_pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert!(pending[0].last_error.is_some());
+
+        *failures.lock().unwrap() = 0;
+        queue.process_all().await.unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![1]]);
+        assert!(queue.get_pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_message_is_moved_to_dead_letters() {
+        let (queue, failures, delivered) = _queue(1, 1);
+        let id = queue.enqueue("conn", "http://endpoint", vec![1]).await.unwrap();
+        assert!(queue.get_pending().await.unwrap().is_empty());
+        assert_eq!(queue.get_dead_letters().await.unwrap()[0].id, id);
+
+        *failures.lock().unwrap() = 0;
+        queue.retry_dead_letter(&id).await.unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![1]]);
+        assert!(queue.get_dead_letters().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_slow_channel_does_not_block_other_channels() {
+        let (release, released) = oneshot::channel::<()>();
+        let released = Arc::new(StdMutex::new(Some(released)));
+        let delivered = Arc::new(StdMutex::new(vec![]));
+        let transport: Transport = {
+            let delivered = delivered.clone();
+            Box::new(move |envelope, endpoint| {
+                let released = released.clone();
+                let delivered = delivered.clone();
+                Box::pin(async move {
+                    if endpoint == "http://slow" {
+                        let released = released.lock().unwrap().take();
+                        if let Some(released) = released {
+                            released.await.ok();
+                        }
+                    }
+                    delivered.lock().unwrap().push(envelope);
+                    Ok(())
+                })
+            })
+        };
+        let queue = OutboundQueue::with_transport(Box::new(InMemoryStorage::default()), _policy(5), transport);
+
+        let slow = queue.enqueue("slow", "http://slow", vec![1]);
+        futures::pin_mut!(slow);
+        assert!(futures::poll!(&mut slow).is_pending());
+
+        queue
+            .enqueue("fast", "http://fast", vec![2])
+            .now_or_never()
+            .expect("delivery on other channel is blocked")
+            .unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![2]]);
+
+        release.send(()).unwrap();
+        slow.await.unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![2], vec![1]]);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_blocks_channel_until_discarded() {
+        let (queue, failures, delivered) = _queue(1, 1);
+        let id = queue.enqueue("conn", "http://endpoint", vec![1]).await.unwrap();
+        queue.enqueue("conn", "http://endpoint", vec![2]).await.unwrap();
+        queue.enqueue("other", "http://endpoint", vec![3]).await.unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![3]]);
+        assert_eq!(queue.get_pending().await.unwrap().len(), 1);
+
+        *failures.lock().unwrap() = 0;
+        queue.process_all().await.unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![3]]);
+
+        queue.discard_dead_letter(&id).await.unwrap();
+        assert_eq!(*delivered.lock().unwrap(), vec![vec![3], vec![2]]);
+        assert!(queue.get_pending().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures::future::BoxFuture;
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::libindy::utils::wallet::{
+    add_wallet_record, delete_wallet_record, search_wallet_records, update_wallet_record_tags,
+    update_wallet_record_value,
+};
+use crate::utils::outbound_queue::{OutboundMessageStatus, QueuedMessage};
+
+pub const OUTBOUND_MESSAGE_RECORD_TYPE: &str = "outbound_message";
+
+pub trait OutboundQueueStorage: Send + Sync {
+    fn add(&self, message: QueuedMessage) -> BoxFuture<'_, VcxResult<()>>;
+
+    fn update(&self, message: QueuedMessage) -> BoxFuture<'_, VcxResult<()>>;
+
+    fn delete(&self, id: String) -> BoxFuture<'_, VcxResult<()>>;
+
+    fn get_by_status(&self, status: OutboundMessageStatus) -> BoxFuture<'_, VcxResult<Vec<QueuedMessage>>>;
+
+    fn get_by_channel(
+        &self,
+        channel_id: String,
+        status: OutboundMessageStatus,
+    ) -> BoxFuture<'_, VcxResult<Vec<QueuedMessage>>>;
+}
+
+#[derive(Default)]
+pub struct InMemoryStorage {
+    messages: Mutex<HashMap<String, QueuedMessage>>,
+}
+
+impl InMemoryStorage {
+    fn _lock(&self) -> VcxResult<std::sync::MutexGuard<HashMap<String, QueuedMessage>>> {
+        self.messages
+            .lock()
+            .map_err(|_| VcxError::from_msg(VcxErrorKind::PoisonedLock, "Outbound queue storage lock is poisoned"))
+    }
+}
+
+impl OutboundQueueStorage for InMemoryStorage {
+    fn add(&self, message: QueuedMessage) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            self._lock()?.insert(message.id.clone(), message);
+            Ok(())
+        })
+    }
+
+    fn update(&self, message: QueuedMessage) -> BoxFuture<'_, VcxResult<()>> {
+        self.add(message)
+    }
+
+    fn delete(&self, id: String) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            self._lock()?.remove(&id);
+            Ok(())
+        })
+    }
+
+    fn get_by_status(&self, status: OutboundMessageStatus) -> BoxFuture<'_, VcxResult<Vec<QueuedMessage>>> {
+        Box::pin(async move {
+            Ok(self
+                ._lock()?
+                .values()
+                .filter(|message| message.status == status)
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn get_by_channel(
+        &self,
+        channel_id: String,
+        status: OutboundMessageStatus,
+    ) -> BoxFuture<'_, VcxResult<Vec<QueuedMessage>>> {
+        Box::pin(async move {
+            Ok(self
+                ._lock()?
+                .values()
+                .filter(|message| message.status == status && message.channel_id == channel_id)
+                .cloned()
+                .collect())
+        })
+    }
+}
+
+/// Keeps queued messages as wallet records so they survive restarts. Only packed envelopes are stored.
+pub struct WalletStorage {
+    wallet_handle: WalletHandle,
+}
+
+impl WalletStorage {
+    pub fn new(wallet_handle: WalletHandle) -> Self {
+        Self { wallet_handle }
+    }
+
+    fn _tags(message: &QueuedMessage) -> String {
+        json!({
+            "channel_id": message.channel_id,
+            "status": message.status.to_string(),
+        })
+        .to_string()
+    }
+
+    fn _serialize(message: &QueuedMessage) -> VcxResult<String> {
+        serde_json::to_string(message).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::SerializationError,
+                format!("Cannot serialize queued message: {}", err),
+            )
+        })
+    }
+
+    async fn _search(&self, query: &str) -> VcxResult<Vec<QueuedMessage>> {
+        let records = search_wallet_records(self.wallet_handle, OUTBOUND_MESSAGE_RECORD_TYPE, query).await?;
+        Ok(records
+            .iter()
+            .filter_map(|record| {
+                serde_json::from_str::<QueuedMessage>(record.value.as_deref().unwrap_or_default())
+                    .map_err(|err| warn!("WalletStorage >>> skipping malformed queued message, err: {:?}", err))
+                    .ok()
+            })
+            .collect())
+    }
+}
+
+impl OutboundQueueStorage for WalletStorage {
+    fn add(&self, message: QueuedMessage) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            add_wallet_record(
+                self.wallet_handle,
+                OUTBOUND_MESSAGE_RECORD_TYPE,
+                &message.id,
+                &Self::_serialize(&message)?,
+                Some(&Self::_tags(&message)),
+            )
+            .await
+        })
+    }
+
+    fn update(&self, message: QueuedMessage) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            update_wallet_record_value(
+                self.wallet_handle,
+                OUTBOUND_MESSAGE_RECORD_TYPE,
+                &message.id,
+                &Self::_serialize(&message)?,
+            )
+            .await?;
+            update_wallet_record_tags(
+                self.wallet_handle,
+                OUTBOUND_MESSAGE_RECORD_TYPE,
+                &message.id,
+                &Self::_tags(&message),
+            )
+            .await
+        })
+    }
+
+    fn delete(&self, id: String) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move { delete_wallet_record(self.wallet_handle, OUTBOUND_MESSAGE_RECORD_TYPE, &id).await })
+    }
+
+    fn get_by_status(&self, status: OutboundMessageStatus) -> BoxFuture<'_, VcxResult<Vec<QueuedMessage>>> {
+        Box::pin(async move { self._search(&json!({ "status": status.to_string() }).to_string()).await })
+    }
+
+    fn get_by_channel(
+        &self,
+        channel_id: String,
+        status: OutboundMessageStatus,
+    ) -> BoxFuture<'_, VcxResult<Vec<QueuedMessage>>> {
+        Box::pin(async move {
+            self._search(&json!({ "status": status.to_string(), "channel_id": channel_id }).to_string())
+                .await
+        })
+    }
+}
@@ -0,0 +1,415 @@
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::prelude::*;
+use crate::events::ProtocolKind;
+use crate::global::protocol_repository::get_protocol_repository;
+use crate::handlers::connection::connection::Connection;
+use crate::handlers::issuance::holder::Holder;
+use crate::handlers::issuance::issuer::Issuer;
+use crate::handlers::proof_presentation::prover::Prover;
+use crate::handlers::proof_presentation::verifier::Verifier;
+use crate::utils::protocol_repository::storage::ProtocolRecordStorage;
+
+pub mod storage;
+
+/// Handler which can be stored in the protocol repository.
+pub trait ProtocolInstance: Serialize + DeserializeOwned {
+    fn protocol() -> ProtocolKind;
+
+    fn instance_thread_id(&self) -> String;
+
+    fn instance_source_id(&self) -> String;
+
+    fn instance_state(&self) -> u32;
+}
+
+impl ProtocolInstance for Connection {
+    fn protocol() -> ProtocolKind {
+        ProtocolKind::Connection
+    }
+
+    fn instance_thread_id(&self) -> String {
+        self.get_thread_id()
+    }
+
+    fn instance_source_id(&self) -> String {
+        self.source_id()
+    }
+
+    fn instance_state(&self) -> u32 {
+        self.get_state().into()
+    }
+}
+
+impl ProtocolInstance for Issuer {
+    fn protocol() -> ProtocolKind {
+        ProtocolKind::Issuer
+    }
+
+    fn instance_thread_id(&self) -> String {
+        self.get_thread_id().unwrap_or_default()
+    }
+
+    fn instance_source_id(&self) -> String {
+        self.get_source_id().unwrap_or_default()
+    }
+
+    fn instance_state(&self) -> u32 {
+        self.get_state().into()
+    }
+}
+
+impl ProtocolInstance for Holder {
+    fn protocol() -> ProtocolKind {
+        ProtocolKind::Holder
+    }
+
+    fn instance_thread_id(&self) -> String {
+        self.get_thread_id().unwrap_or_default()
+    }
+
+    fn instance_source_id(&self) -> String {
+        self.get_source_id()
+    }
+
+    fn instance_state(&self) -> u32 {
+        self.get_state().into()
+    }
+}
+
+impl ProtocolInstance for Prover {
+    fn protocol() -> ProtocolKind {
+        ProtocolKind::Prover
+    }
+
+    fn instance_thread_id(&self) -> String {
+        self.get_thread_id().unwrap_or_default()
+    }
+
+    fn instance_source_id(&self) -> String {
+        self.get_source_id()
+    }
+
+    fn instance_state(&self) -> u32 {
+        self.get_state().into()
+    }
+}
+
+impl ProtocolInstance for Verifier {
+    fn protocol() -> ProtocolKind {
+        ProtocolKind::Verifier
+    }
+
+    fn instance_thread_id(&self) -> String {
+        self.get_thread_id().unwrap_or_default()
+    }
+
+    fn instance_source_id(&self) -> String {
+        self.get_source_id()
+    }
+
+    fn instance_state(&self) -> u32 {
+        self.get_state().into()
+    }
+}
+
+/// Stored protocol instance. `state` is the numeric state used by libvcx, `updated_at` is unix time
+/// in milliseconds and `data` is the serialized handler.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProtocolRecord {
+    pub protocol: ProtocolKind,
+    pub thread_id: String,
+    pub source_id: String,
+    pub connection_id: Option<String>,
+    pub state: u32,
+    pub updated_at: i64,
+    pub data: String,
+}
+
+impl ProtocolRecord {
+    pub fn record_id(protocol: ProtocolKind, thread_id: &str) -> String {
+        format!("{}:{}", protocol.to_string(), thread_id)
+    }
+
+    pub fn id(&self) -> String {
+        Self::record_id(self.protocol, &self.thread_id)
+    }
+
+    pub fn tags(&self) -> serde_json::Value {
+        let mut tags = json!({
+            "protocol": self.protocol.to_string(),
+            "thread_id": self.thread_id,
+            "source_id": self.source_id,
+            "state": self.state.to_string(),
+        });
+        if let Some(connection_id) = &self.connection_id {
+            tags["connection_id"] = json!(connection_id);
+        }
+        tags
+    }
+
+    pub fn instance<T: ProtocolInstance>(&self) -> VcxResult<T> {
+        if self.protocol != T::protocol() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidOption,
+                format!(
+                    "Record {} holds {:?} instance, not {:?}",
+                    self.id(),
+                    self.protocol,
+                    T::protocol()
+                ),
+            ));
+        }
+        serde_json::from_str(&self.data).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::InvalidJson,
+                format!(
+                    "Cannot deserialize {:?} instance {}: {:?}",
+                    self.protocol, self.thread_id, err
+                ),
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProtocolQuery {
+    pub protocol: Option<ProtocolKind>,
+    pub thread_id: Option<String>,
+    pub connection_id: Option<String>,
+    pub state: Option<u32>,
+}
+
+impl ProtocolQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_protocol(mut self, protocol: ProtocolKind) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    pub fn set_thread_id(mut self, thread_id: &str) -> Self {
+        self.thread_id = Some(thread_id.to_string());
+        self
+    }
+
+    pub fn set_connection_id(mut self, connection_id: &str) -> Self {
+        self.connection_id = Some(connection_id.to_string());
+        self
+    }
+
+    pub fn set_state(mut self, state: u32) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn matches(&self, record: &ProtocolRecord) -> bool {
+        self.protocol.map_or(true, |protocol| protocol == record.protocol)
+            && self
+                .thread_id
+                .as_ref()
+                .map_or(true, |thread_id| thread_id == &record.thread_id)
+            && self.connection_id.as_ref().map_or(true, |connection_id| {
+                Some(connection_id) == record.connection_id.as_ref()
+            })
+            && self.state.map_or(true, |state| state == record.state)
+    }
+
+    pub fn to_wql(&self) -> serde_json::Value {
+        let mut query = json!({});
+        if let Some(protocol) = self.protocol {
+            query["protocol"] = json!(protocol.to_string());
+        }
+        if let Some(thread_id) = &self.thread_id {
+            query["thread_id"] = json!(thread_id);
+        }
+        if let Some(connection_id) = &self.connection_id {
+            query["connection_id"] = json!(connection_id);
+        }
+        if let Some(state) = self.state {
+            query["state"] = json!(state.to_string());
+        }
+        query
+    }
+}
+
+/// Stores protocol instances keyed by thread id, so they can be restored after the agent restarts.
+pub struct ProtocolRepository {
+    storage: Box<dyn ProtocolRecordStorage>,
+}
+
+impl ProtocolRepository {
+    pub fn new(storage: Box<dyn ProtocolRecordStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// Creates or updates the record of the instance. Connection of an existing record is kept if
+    /// `connection_id` is not specified.
+    pub async fn save<T: ProtocolInstance>(
+        &self,
+        instance: &T,
+        connection_id: Option<&str>,
+    ) -> VcxResult<ProtocolRecord> {
+        let thread_id = instance.instance_thread_id();
+        trace!(
+            "ProtocolRepository::save >>> protocol: {:?}, thread_id: {}, connection_id: {:?}",
+            T::protocol(),
+            thread_id,
+            connection_id
+        );
+        if thread_id.is_empty() {
+            return Err(VcxError::from_msg(
+                VcxErrorKind::InvalidState,
+                format!("Cannot save {:?} instance which has no thread id yet", T::protocol()),
+            ));
+        }
+        let data = serde_json::to_string(instance).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::SerializationError,
+                format!("Cannot serialize {:?} instance: {:?}", T::protocol(), err),
+            )
+        })?;
+        let existing = self.get_record(T::protocol(), &thread_id).await?;
+        let record = ProtocolRecord {
+            protocol: T::protocol(),
+            thread_id,
+            source_id: instance.instance_source_id(),
+            connection_id: connection_id
+                .map(String::from)
+                .or_else(|| existing.as_ref().and_then(|record| record.connection_id.clone())),
+            state: instance.instance_state(),
+            updated_at: Utc::now().timestamp_millis(),
+            data,
+        };
+        match existing {
+            Some(_) => self.storage.update(record.clone()).await?,
+            None => self.storage.add(record.clone()).await?,
+        };
+        Ok(record)
+    }
+
+    pub async fn load<T: ProtocolInstance>(&self, thread_id: &str) -> VcxResult<Option<T>> {
+        trace!(
+            "ProtocolRepository::load >>> protocol: {:?}, thread_id: {}",
+            T::protocol(),
+            thread_id
+        );
+        match self.get_record(T::protocol(), thread_id).await? {
+            Some(record) => record.instance().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_record(&self, protocol: ProtocolKind, thread_id: &str) -> VcxResult<Option<ProtocolRecord>> {
+        let query = ProtocolQuery::new().set_protocol(protocol).set_thread_id(thread_id);
+        Ok(self.storage.search(query).await?.pop())
+    }
+
+    pub async fn find(&self, query: ProtocolQuery) -> VcxResult<Vec<ProtocolRecord>> {
+        trace!("ProtocolRepository::find >>> query: {:?}", query);
+        let mut records = self.storage.search(query).await?;
+        records.sort_by_key(|record| record.updated_at);
+        Ok(records)
+    }
+
+    pub async fn find_instances<T: ProtocolInstance>(&self, query: ProtocolQuery) -> VcxResult<Vec<T>> {
+        self.find(query.set_protocol(T::protocol()))
+            .await?
+            .iter()
+            .map(|record| record.instance())
+            .collect()
+    }
+
+    pub async fn delete(&self, protocol: ProtocolKind, thread_id: &str) -> VcxResult<()> {
+        trace!(
+            "ProtocolRepository::delete >>> protocol: {:?}, thread_id: {}",
+            protocol,
+            thread_id
+        );
+        self.storage.delete(protocol, thread_id.to_string()).await
+    }
+}
+
+/// Saves the instance into the global repository, if one is set. Instances without thread id are
+/// skipped, they are saved with the first transition which assigns it. Failures are only logged,
+/// the transition has already happened and must not be reported as failed.
+pub async fn persist<T: ProtocolInstance>(instance: &T, connection_id: Option<&str>) {
+    if let Some(repository) = get_protocol_repository() {
+        if !instance.instance_thread_id().is_empty() {
+            if let Err(err) = repository.save(instance, connection_id).await {
+                warn!(
+                    "persist >> failed to save {:?} instance {}: {}",
+                    T::protocol(),
+                    instance.instance_thread_id(),
+                    err
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use crate::messages::issuance::credential_offer::test_utils::_credential_offer;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::protocol_repository::storage::InMemoryStorage;
+
+    use super::*;
+
+    fn _repository() -> ProtocolRepository {
+        ProtocolRepository::new(Box::new(InMemoryStorage::default()))
+    }
+
+    fn _holder() -> Holder {
+        Holder::create_from_offer("test_source_id", _credential_offer()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_instance() {
+        let _setup = SetupMocks::init();
+        let repository = _repository();
+        let holder = _holder();
+        let thread_id = holder.get_thread_id().unwrap();
+
+        repository.save(&holder, Some("connection_id")).await.unwrap();
+        let record = repository.save(&holder, None).await.unwrap();
+        assert_eq!(record.connection_id, Some("connection_id".to_string()));
+
+        let loaded: Holder = repository.load(&thread_id).await.unwrap().unwrap();
+        assert_eq!(loaded.get_state(), holder.get_state());
+        assert!(repository.load::<Prover>(&thread_id).await.unwrap().is_none());
+
+        repository.delete(ProtocolKind::Holder, &thread_id).await.unwrap();
+        assert!(repository.load::<Holder>(&thread_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_by_connection_and_state() {
+        let _setup = SetupMocks::init();
+        let repository = _repository();
+        let holder = _holder();
+        repository.save(&holder, Some("connection_id")).await.unwrap();
+
+        let query = ProtocolQuery::new()
+            .set_connection_id("connection_id")
+            .set_state(holder.get_state().into());
+        let holders: Vec<Holder> = repository.find_instances(query.clone()).await.unwrap();
+        assert_eq!(holders.len(), 1);
+        assert_eq!(
+            repository.find(query.set_protocol(ProtocolKind::Issuer)).await.unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            repository
+                .find(ProtocolQuery::new().set_connection_id("other_connection_id"))
+                .await
+                .unwrap(),
+            vec![]
+        );
+    }
+}
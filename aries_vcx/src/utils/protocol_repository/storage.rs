@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures::future::BoxFuture;
+use indy_sys::WalletHandle;
+
+use crate::error::prelude::*;
+use crate::events::ProtocolKind;
+use crate::libindy::utils::wallet::{
+    add_wallet_record, delete_wallet_record, search_wallet_records, update_wallet_record_tags,
+    update_wallet_record_value,
+};
+use crate::utils::protocol_repository::{ProtocolQuery, ProtocolRecord};
+
+pub const PROTOCOL_INSTANCE_RECORD_TYPE: &str = "protocol_instance";
+
+pub trait ProtocolRecordStorage: Send + Sync {
+    fn add(&self, record: ProtocolRecord) -> BoxFuture<'_, VcxResult<()>>;
+
+    fn update(&self, record: ProtocolRecord) -> BoxFuture<'_, VcxResult<()>>;
+
+    fn delete(&self, protocol: ProtocolKind, thread_id: String) -> BoxFuture<'_, VcxResult<()>>;
+
+    fn search(&self, query: ProtocolQuery) -> BoxFuture<'_, VcxResult<Vec<ProtocolRecord>>>;
+}
+
+#[derive(Default)]
+pub struct InMemoryStorage {
+    records: Mutex<HashMap<String, ProtocolRecord>>,
+}
+
+impl InMemoryStorage {
+    fn _lock(&self) -> VcxResult<std::sync::MutexGuard<HashMap<String, ProtocolRecord>>> {
+        self.records.lock().map_err(|_| {
+            VcxError::from_msg(
+                VcxErrorKind::PoisonedLock,
+                "Protocol repository storage lock is poisoned",
+            )
+        })
+    }
+}
+
+impl ProtocolRecordStorage for InMemoryStorage {
+    fn add(&self, record: ProtocolRecord) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            self._lock()?.insert(record.id(), record);
+            Ok(())
+        })
+    }
+
+    fn update(&self, record: ProtocolRecord) -> BoxFuture<'_, VcxResult<()>> {
+        self.add(record)
+    }
+
+    fn delete(&self, protocol: ProtocolKind, thread_id: String) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            self._lock()?.remove(&ProtocolRecord::record_id(protocol, &thread_id));
+            Ok(())
+        })
+    }
+
+    fn search(&self, query: ProtocolQuery) -> BoxFuture<'_, VcxResult<Vec<ProtocolRecord>>> {
+        Box::pin(async move {
+            Ok(self
+                ._lock()?
+                .values()
+                .filter(|record| query.matches(record))
+                .cloned()
+                .collect())
+        })
+    }
+}
+
+/// Keeps protocol instances as wallet records, tagged by protocol, thread id, connection and state.
+pub struct WalletStorage {
+    wallet_handle: WalletHandle,
+}
+
+impl WalletStorage {
+    pub fn new(wallet_handle: WalletHandle) -> Self {
+        Self { wallet_handle }
+    }
+
+    fn _serialize(record: &ProtocolRecord) -> VcxResult<String> {
+        serde_json::to_string(record).map_err(|err| {
+            VcxError::from_msg(
+                VcxErrorKind::SerializationError,
+                format!("Cannot serialize protocol record: {}", err),
+            )
+        })
+    }
+}
+
+impl ProtocolRecordStorage for WalletStorage {
+    fn add(&self, record: ProtocolRecord) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            add_wallet_record(
+                self.wallet_handle,
+                PROTOCOL_INSTANCE_RECORD_TYPE,
+                &record.id(),
+                &Self::_serialize(&record)?,
+                Some(&record.tags().to_string()),
+            )
+            .await
+        })
+    }
+
+    fn update(&self, record: ProtocolRecord) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            update_wallet_record_value(
+                self.wallet_handle,
+                PROTOCOL_INSTANCE_RECORD_TYPE,
+                &record.id(),
+                &Self::_serialize(&record)?,
+            )
+            .await?;
+            update_wallet_record_tags(
+                self.wallet_handle,
+                PROTOCOL_INSTANCE_RECORD_TYPE,
+                &record.id(),
+                &record.tags().to_string(),
+            )
+            .await
+        })
+    }
+
+    fn delete(&self, protocol: ProtocolKind, thread_id: String) -> BoxFuture<'_, VcxResult<()>> {
+        Box::pin(async move {
+            delete_wallet_record(
+                self.wallet_handle,
+                PROTOCOL_INSTANCE_RECORD_TYPE,
+                &ProtocolRecord::record_id(protocol, &thread_id),
+            )
+            .await
+        })
+    }
+
+    fn search(&self, query: ProtocolQuery) -> BoxFuture<'_, VcxResult<Vec<ProtocolRecord>>> {
+        Box::pin(async move {
+            let records = search_wallet_records(
+                self.wallet_handle,
+                PROTOCOL_INSTANCE_RECORD_TYPE,
+                &query.to_wql().to_string(),
+            )
+            .await?;
+            Ok(records
+                .iter()
+                .filter_map(|record| {
+                    serde_json::from_str::<ProtocolRecord>(record.value.as_deref().unwrap_or_default())
+                        .map_err(|err| warn!("WalletStorage >>> skipping malformed protocol record, err: {:?}", err))
+                        .ok()
+                })
+                .collect())
+        })
+    }
+}
@@ -8,6 +8,13 @@ pub fn is_fully_qualified(entity: &str) -> bool {
     REGEX.is_match(entity)
 }
 
+/// Parses DID of the issuer from unqualified credential definition id `<did>:3:CL:<schema>:<tag>`
+/// as well as from fully qualified `creddef:<method>:did:<method>:<did>:3:CL:<schema>:<tag>`.
+pub fn cred_def_issuer_did(cred_def_id: &str) -> Option<&str> {
+    let parts: Vec<&str> = cred_def_id.split(':').collect();
+    parts.windows(2).find(|pair| pair[1] == "3").map(|pair| pair[0])
+}
+
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod test {
@@ -19,4 +26,17 @@ mod test {
         assert!(!is_fully_qualified("did:indy"));
         assert!(!is_fully_qualified("indy:some"));
     }
+
+    #[test]
+    fn cred_def_issuer_did_works() {
+        assert_eq!(
+            cred_def_issuer_did("V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1"),
+            Some("V4SGRU86Z58d6TV7PBUe6f")
+        );
+        assert_eq!(
+            cred_def_issuer_did("creddef:sov:did:sov:V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1"),
+            Some("V4SGRU86Z58d6TV7PBUe6f")
+        );
+        assert_eq!(cred_def_issuer_did("V4SGRU86Z58d6TV7PBUe6f"), None);
+    }
 }
@@ -940,6 +940,77 @@ pub extern "C" fn vcx_disclosed_proof_generate_proof(
     error::SUCCESS.code_num
 }
 
+/// Same as vcx_disclosed_proof_generate_proof, but the values of chosen attributes are not disclosed
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to identify the disclosed proof object
+///
+/// selected_credentials: a json string with a credential for each proof request attribute, see vcx_disclosed_proof_generate_proof
+///
+/// self_attested_attrs: a json string with attributes self attested by user
+///
+/// unrevealed_attrs: a json array of requested attribute referents which are proven without disclosing their values
+/// # Examples
+/// unrevealed_attrs -> "["attribute_0"]"
+/// cb: Callback that returns error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_disclosed_proof_generate_proof_with_reveal_choices(
+    command_handle: CommandHandle,
+    proof_handle: u32,
+    selected_credentials: *const c_char,
+    self_attested_attrs: *const c_char,
+    unrevealed_attrs: *const c_char,
+    cb: Option<extern "C" fn(xcommand_handle: CommandHandle, err: u32)>,
+) -> u32 {
+    info!("vcx_disclosed_proof_generate_proof_with_reveal_choices >>>");
+
+    check_useful_c_str!(selected_credentials, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(self_attested_attrs, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(unrevealed_attrs, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = disclosed_proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_disclosed_proof_generate_proof_with_reveal_choices(command_handle: {}, proof_handle: {}, selected_credentials: {}, self_attested_attrs: {}, unrevealed_attrs: {}) source_id: {}", command_handle, proof_handle, selected_credentials, self_attested_attrs, unrevealed_attrs, source_id);
+
+    execute_async::<BoxFuture<'static, Result<(), ()>>>(Box::pin(async move {
+        match disclosed_proof::generate_proof_with_reveal_choices(
+            proof_handle,
+            &selected_credentials,
+            &self_attested_attrs,
+            &unrevealed_attrs,
+        )
+        .await
+        {
+            Ok(_) => {
+                trace!(
+                    "vcx_disclosed_proof_generate_proof_with_reveal_choices(command_handle: {}, rc: {}) source_id: {}",
+                    command_handle,
+                    error::SUCCESS.message,
+                    source_id
+                );
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(err) => {
+                set_current_error_vcx(&err);
+                error!(
+                    "vcx_disclosed_proof_generate_proof_with_reveal_choices(command_handle: {}, rc: {}) source_id: {}",
+                    command_handle, err, source_id
+                );
+                cb(command_handle, err.into());
+            }
+        };
+
+        Ok(())
+    }));
+
+    error::SUCCESS.code_num
+}
+
 /// Declines presentation request.
 /// There are two ways of following interaction:
 ///     - Prover wants to propose using a different presentation - pass `proposal` parameter.
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use libc::c_char;
+
+use aries_vcx::error::{VcxError, VcxErrorKind};
+use aries_vcx::events;
+use aries_vcx::events::Event;
+#[cfg(feature = "webhook")]
+use aries_vcx::global::settings;
+use aries_vcx::indy_sys::CommandHandle;
+use aries_vcx::utils::error;
+
+use crate::api_lib::utils::cstring::CStringUtils;
+use crate::api_lib::utils::error::set_current_error_vcx;
+
+/// Subscribes callback to events emitted by connections, issuer and holder credentials, proofs and
+/// disclosed proofs: state transitions, received messages and problem reports.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback called for every published event with the event json, e.g.
+/// {
+///     "id": "2d9a8c57-...", "protocol": "issuer", "source_id": "alice-credential", "thread_id": "8b13...",
+///     "timestamp": "2022-05-04T12:00:00+00:00", "type": "state_changed", "previous_state": "OfferSent",
+///     "state": "RequestReceived"
+/// }
+/// The callback is called from the thread which processed the change and must not block.
+///
+/// subscription_id_p: Reference that will contain id of the subscription, used to unsubscribe.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_events_subscribe(
+    command_handle: CommandHandle,
+    cb: Option<extern "C" fn(xcommand_handle: CommandHandle, event: *const c_char)>,
+    subscription_id_p: *mut u32,
+) -> u32 {
+    info!("vcx_events_subscribe >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    if subscription_id_p.is_null() {
+        let err = VcxError::from_msg(
+            VcxErrorKind::InvalidOption,
+            "Invalid subscription id pointer has been passed",
+        );
+        set_current_error_vcx(&err);
+        return err.into();
+    }
+
+    trace!("vcx_events_subscribe(command_handle: {})", command_handle);
+
+    let subscription_id = events::subscribe(Arc::new(move |event: &Event| {
+        let event_json = json!(event).to_string();
+        trace!(
+            "vcx_events_subscribe_cb(command_handle: {}, event: {})",
+            command_handle,
+            event_json
+        );
+        let event_json = CStringUtils::string_to_cstring(event_json);
+        cb(command_handle, event_json.as_ptr());
+    }));
+    unsafe {
+        *subscription_id_p = subscription_id;
+    }
+
+    trace!("vcx_events_subscribe <<< subscription_id: {}", subscription_id);
+    error::SUCCESS.code_num
+}
+
+/// Subscribes a webhook which receives every published event as JSON in the body of a POST request.
+/// The url is stored as `webhook_url` config value. Available only with the `webhook` feature.
+///
+/// #Params
+/// webhook_url: url to which the events are posted
+///
+/// subscription_id_p: Reference that will contain id of the subscription, used to unsubscribe.
+///
+/// #Returns
+/// Error code as a u32
+#[cfg(feature = "webhook")]
+#[no_mangle]
+pub extern "C" fn vcx_events_subscribe_webhook(webhook_url: *const c_char, subscription_id_p: *mut u32) -> u32 {
+    info!("vcx_events_subscribe_webhook >>>");
+
+    check_useful_c_str!(webhook_url, VcxErrorKind::InvalidOption);
+    if subscription_id_p.is_null() {
+        let err = VcxError::from_msg(
+            VcxErrorKind::InvalidOption,
+            "Invalid subscription id pointer has been passed",
+        );
+        set_current_error_vcx(&err);
+        return err.into();
+    }
+
+    trace!("vcx_events_subscribe_webhook(webhook_url: {})", webhook_url);
+
+    let subscription = settings::set_config_value(settings::CONFIG_WEBHOOK_URL, &webhook_url)
+        .and_then(|_| events::webhook::init_webhook_from_settings());
+    match subscription {
+        Ok(Some(subscription_id)) => {
+            unsafe {
+                *subscription_id_p = subscription_id;
+            }
+            trace!("vcx_events_subscribe_webhook <<< subscription_id: {}", subscription_id);
+            error::SUCCESS.code_num
+        }
+        Ok(None) => {
+            let err = VcxError::from_msg(VcxErrorKind::InvalidUrl, "Webhook url must not be empty");
+            set_current_error_vcx(&err);
+            err.into()
+        }
+        Err(err) => {
+            set_current_error_vcx(&err);
+            err.into()
+        }
+    }
+}
+
+/// Removes subscription created by vcx_events_subscribe, the callback is not called afterwards.
+///
+/// #Params
+/// subscription_id: id of the subscription returned by vcx_events_subscribe
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_events_unsubscribe(subscription_id: u32) -> u32 {
+    info!("vcx_events_unsubscribe >>>");
+
+    trace!("vcx_events_unsubscribe(subscription_id: {})", subscription_id);
+
+    if events::unsubscribe(subscription_id) {
+        error::SUCCESS.code_num
+    } else {
+        let err = VcxError::from_msg(
+            VcxErrorKind::InvalidHandle,
+            format!("No event subscription with id {}", subscription_id),
+        );
+        set_current_error_vcx(&err);
+        err.into()
+    }
+}
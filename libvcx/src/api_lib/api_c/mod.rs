@@ -3,6 +3,7 @@ pub mod connection;
 pub mod credential;
 pub mod credential_def;
 pub mod disclosed_proof;
+pub mod events;
 mod filters;
 pub mod issuer_credential;
 pub mod logger;
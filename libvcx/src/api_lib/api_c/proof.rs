@@ -173,6 +173,344 @@ pub extern "C" fn vcx_proof_create(
     error::SUCCESS.code_num
 }
 
+/// Stores proof request template in the wallet, so that proofs can be repeatedly created from it
+/// by `vcx_proof_create_from_template`. Template of the same name and version is replaced.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// name: name of the template, used as name of the proof requests created from it.
+///
+/// version: version of the template.
+///
+/// requested_attrs: Describes requested attributes, see `vcx_proof_create`.
+///
+/// requested_predicates: Describes requested predicates, see `vcx_proof_create`.
+///
+/// revocation_interval: Revocation interval, see `vcx_proof_create`.
+///
+/// cb: Callback that provides error status of request.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_proof_request_template_store(
+    command_handle: CommandHandle,
+    name: *const c_char,
+    version: *const c_char,
+    requested_attrs: *const c_char,
+    requested_predicates: *const c_char,
+    revocation_interval: *const c_char,
+    cb: Option<extern "C" fn(xcommand_handle: CommandHandle, err: u32)>,
+) -> u32 {
+    info!("vcx_proof_request_template_store >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(name, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(version, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(requested_attrs, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(requested_predicates, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(revocation_interval, VcxErrorKind::InvalidOption);
+
+    trace!(
+        "vcx_proof_request_template_store(command_handle: {}, name: {}, version: {}, requested_attrs: {}, requested_predicates: {}, revocation_interval: {})",
+        command_handle, name, version, requested_attrs, requested_predicates, revocation_interval
+    );
+
+    execute_async::<BoxFuture<'static, Result<(), ()>>>(Box::pin(async move {
+        let err = match proof::store_proof_request_template(
+            name,
+            version,
+            requested_attrs,
+            requested_predicates,
+            revocation_interval,
+        )
+        .await
+        {
+            Ok(()) => {
+                trace!(
+                    "vcx_proof_request_template_store_cb(command_handle: {}, rc: {})",
+                    command_handle,
+                    error::SUCCESS.message
+                );
+                error::SUCCESS.code_num
+            }
+            Err(err) => {
+                set_current_error_vcx(&err);
+                error!(
+                    "vcx_proof_request_template_store_cb(command_handle: {}, rc: {})",
+                    command_handle, err
+                );
+                err.into()
+            }
+        };
+        cb(command_handle, err);
+
+        Ok(())
+    }));
+
+    error::SUCCESS.code_num
+}
+
+/// Create a new Proof object from proof request template stored in the wallet. The proof request
+/// gets a fresh nonce.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Enterprise's personal identification for the user.
+///
+/// name: name of the stored template.
+///
+/// version: Optional<string> version of the template, the latest version is used if not provided.
+///
+/// overrides: Optional<string> parameters replacing parts of the template
+///     {
+///         "name": Optional<string>, // name of the proof request
+///         "non_revoked": Optional<revocation_interval>,
+///         "requested_attributes": Optional<{"<referent>": <requested_attr>}>, // added or replaced attributes
+///         "requested_predicates": Optional<{"<referent>": <requested_predicate>}>, // added or replaced predicates
+///         "predicate_values": Optional<{"<referent>": int}> // new values of existing predicates
+///     }
+///
+/// cb: Callback that provides proof handle and error status of request.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_proof_create_from_template(
+    command_handle: CommandHandle,
+    source_id: *const c_char,
+    name: *const c_char,
+    version: *const c_char,
+    overrides: *const c_char,
+    cb: Option<extern "C" fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>,
+) -> u32 {
+    info!("vcx_proof_create_from_template >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(name, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(version, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(overrides, VcxErrorKind::InvalidOption);
+
+    trace!(
+        "vcx_proof_create_from_template(command_handle: {}, source_id: {}, name: {}, version: {:?}, overrides: {:?})",
+        command_handle,
+        source_id,
+        name,
+        version,
+        overrides
+    );
+
+    execute_async::<BoxFuture<'static, Result<(), ()>>>(Box::pin(async move {
+        let overrides = overrides.unwrap_or_else(|| "{}".to_string());
+        let (rc, handle) = match proof::create_proof_from_template(source_id, name, version, overrides).await {
+            Ok(handle) => {
+                trace!(
+                    "vcx_proof_create_from_template_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                    command_handle,
+                    error::SUCCESS.message,
+                    handle,
+                    proof::get_source_id(handle).unwrap_or_default()
+                );
+                (error::SUCCESS.code_num, handle)
+            }
+            Err(err) => {
+                set_current_error_vcx(&err);
+                error!(
+                    "vcx_proof_create_from_template_cb(command_handle: {}, rc: {}, handle: {})",
+                    command_handle, err, 0
+                );
+                (err.into(), 0)
+            }
+        };
+        cb(command_handle, rc, handle);
+
+        Ok(())
+    }));
+
+    error::SUCCESS.code_num
+}
+
+/// Retrieves proof request template stored in the wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// name: name of the stored template.
+///
+/// version: Optional<string> version of the template, the latest version is used if not provided.
+///
+/// cb: Callback that provides the template as json string and error status of request.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_proof_request_template_get(
+    command_handle: CommandHandle,
+    name: *const c_char,
+    version: *const c_char,
+    cb: Option<extern "C" fn(xcommand_handle: CommandHandle, err: u32, template: *const c_char)>,
+) -> u32 {
+    info!("vcx_proof_request_template_get >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(name, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(version, VcxErrorKind::InvalidOption);
+
+    trace!(
+        "vcx_proof_request_template_get(command_handle: {}, name: {}, version: {:?})",
+        command_handle,
+        name,
+        version
+    );
+
+    execute_async::<BoxFuture<'static, Result<(), ()>>>(Box::pin(async move {
+        match proof::get_proof_request_template(name, version).await {
+            Ok(template) => {
+                trace!(
+                    "vcx_proof_request_template_get_cb(command_handle: {}, rc: {}, template: {})",
+                    command_handle,
+                    error::SUCCESS.message,
+                    template
+                );
+                let template = CStringUtils::string_to_cstring(template);
+                cb(command_handle, error::SUCCESS.code_num, template.as_ptr());
+            }
+            Err(err) => {
+                set_current_error_vcx(&err);
+                error!(
+                    "vcx_proof_request_template_get_cb(command_handle: {}, rc: {})",
+                    command_handle, err
+                );
+                cb(command_handle, err.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    }));
+
+    error::SUCCESS.code_num
+}
+
+/// Lists proof request templates stored in the wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// name: Optional<string> name of the templates, all versions of it are listed. All templates are
+/// listed if not provided.
+///
+/// cb: Callback that provides json array of the templates and error status of request.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_proof_request_template_list(
+    command_handle: CommandHandle,
+    name: *const c_char,
+    cb: Option<extern "C" fn(xcommand_handle: CommandHandle, err: u32, templates: *const c_char)>,
+) -> u32 {
+    info!("vcx_proof_request_template_list >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(name, VcxErrorKind::InvalidOption);
+
+    trace!(
+        "vcx_proof_request_template_list(command_handle: {}, name: {:?})",
+        command_handle,
+        name
+    );
+
+    execute_async::<BoxFuture<'static, Result<(), ()>>>(Box::pin(async move {
+        match proof::list_proof_request_templates(name).await {
+            Ok(templates) => {
+                trace!(
+                    "vcx_proof_request_template_list_cb(command_handle: {}, rc: {}, templates: {})",
+                    command_handle,
+                    error::SUCCESS.message,
+                    templates
+                );
+                let templates = CStringUtils::string_to_cstring(templates);
+                cb(command_handle, error::SUCCESS.code_num, templates.as_ptr());
+            }
+            Err(err) => {
+                set_current_error_vcx(&err);
+                error!(
+                    "vcx_proof_request_template_list_cb(command_handle: {}, rc: {})",
+                    command_handle, err
+                );
+                cb(command_handle, err.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    }));
+
+    error::SUCCESS.code_num
+}
+
+/// Deletes proof request template of given name and version from the wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// name: name of the stored template.
+///
+/// version: version of the stored template.
+///
+/// cb: Callback that provides error status of request.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern "C" fn vcx_proof_request_template_delete(
+    command_handle: CommandHandle,
+    name: *const c_char,
+    version: *const c_char,
+    cb: Option<extern "C" fn(xcommand_handle: CommandHandle, err: u32)>,
+) -> u32 {
+    info!("vcx_proof_request_template_delete >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(name, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(version, VcxErrorKind::InvalidOption);
+
+    trace!(
+        "vcx_proof_request_template_delete(command_handle: {}, name: {}, version: {})",
+        command_handle,
+        name,
+        version
+    );
+
+    execute_async::<BoxFuture<'static, Result<(), ()>>>(Box::pin(async move {
+        let err = match proof::delete_proof_request_template(name, version).await {
+            Ok(()) => {
+                trace!(
+                    "vcx_proof_request_template_delete_cb(command_handle: {}, rc: {})",
+                    command_handle,
+                    error::SUCCESS.message
+                );
+                error::SUCCESS.code_num
+            }
+            Err(err) => {
+                set_current_error_vcx(&err);
+                error!(
+                    "vcx_proof_request_template_delete_cb(command_handle: {}, rc: {})",
+                    command_handle, err
+                );
+                err.into()
+            }
+        };
+        cb(command_handle, err);
+
+        Ok(())
+    }));
+
+    error::SUCCESS.code_num
+}
+
 /// Query the agency for the received messages.
 /// Checks for any messages changing state in the object and updates the state attribute.
 ///
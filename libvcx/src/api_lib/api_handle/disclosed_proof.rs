@@ -191,6 +191,31 @@ pub async fn generate_proof(handle: u32, credentials: &str, self_attested_attrs:
     Ok(error::SUCCESS.code_num)
 }
 
+pub async fn generate_proof_with_reveal_choices(
+    handle: u32,
+    credentials: &str,
+    self_attested_attrs: &str,
+    unrevealed_attrs: &str,
+) -> VcxResult<u32> {
+    let unrevealed_attrs: Vec<String> = serde_json::from_str(unrevealed_attrs).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::InvalidJson,
+            format!("Cannot deserialize unrevealed attributes: {}", err),
+        )
+    })?;
+    let mut proof = HANDLE_MAP.get_cloned(handle)?;
+    proof
+        .generate_presentation_with_reveal_choices(
+            get_main_wallet_handle(),
+            credentials.to_string(),
+            self_attested_attrs.to_string(),
+            unrevealed_attrs,
+        )
+        .await?;
+    HANDLE_MAP.insert(handle, proof)?;
+    Ok(error::SUCCESS.code_num)
+}
+
 pub async fn decline_presentation_request(
     handle: u32,
     connection_handle: u32,
@@ -339,6 +364,21 @@ mod tests {
         assert_eq!(create_proof("1", "{}").unwrap_err().kind(), VcxErrorKind::InvalidJson);
     }
 
+    #[tokio::test]
+    #[cfg(feature = "general_test")]
+    async fn test_generate_proof_with_reveal_choices_fails_with_invalid_unrevealed_attrs() {
+        let _setup = SetupMocks::init();
+
+        let handle = create_proof("1", ARIES_PROOF_REQUEST_PRESENTATION).unwrap();
+        assert_eq!(
+            generate_proof_with_reveal_choices(handle, "{}", "{}", "attribute_0")
+                .await
+                .unwrap_err()
+                .kind(),
+            VcxErrorKind::InvalidJson
+        );
+    }
+
     #[tokio::test]
     #[cfg(feature = "general_test")]
     async fn test_proof_cycle() {
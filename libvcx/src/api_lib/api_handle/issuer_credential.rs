@@ -4,6 +4,7 @@ use aries_vcx::error::{VcxError, VcxErrorKind, VcxResult};
 use aries_vcx::handlers::issuance::issuer::Issuer;
 use aries_vcx::messages::a2a::A2AMessage;
 use aries_vcx::messages::issuance::credential_offer::OfferInfo;
+use aries_vcx::protocols::issuance::actions::CredentialIssuanceAction;
 use aries_vcx::utils::error;
 
 use crate::api_lib::api_handle::connection;
@@ -52,6 +53,14 @@ pub async fn update_state(handle: u32, message: Option<&str>, connection_handle:
                 .step(get_main_wallet_handle(), msg.into(), Some(send_message))
                 .await?;
             connection::update_message_status(connection_handle, &uid).await?;
+        } else if credential.is_expired() {
+            credential
+                .step(
+                    get_main_wallet_handle(),
+                    CredentialIssuanceAction::CredentialOfferExpired,
+                    Some(send_message),
+                )
+                .await?;
         }
     }
     let res: u32 = credential.get_state().into();
@@ -2,8 +2,10 @@ use serde_json;
 
 use aries_vcx::error::{VcxError, VcxErrorKind, VcxResult};
 use aries_vcx::handlers::proof_presentation::verifier::Verifier;
+use aries_vcx::libindy::proofs::proof_request_template::{ProofRequestOverrides, ProofRequestTemplate};
 use aries_vcx::messages::a2a::A2AMessage;
 use aries_vcx::messages::proof_presentation::presentation_request::PresentationRequestData;
+use aries_vcx::protocols::proof_presentation::verifier::messages::VerifierMessages;
 use aries_vcx::utils::error;
 
 use crate::api_lib::api_handle::connection;
@@ -39,6 +41,67 @@ pub async fn create_proof(
         .or(Err(VcxError::from(VcxErrorKind::CreateProof)))
 }
 
+pub async fn store_proof_request_template(
+    name: String,
+    version: String,
+    requested_attrs: String,
+    requested_predicates: String,
+    revocation_details: String,
+) -> VcxResult<()> {
+    let presentation_request = PresentationRequestData::create(&name)
+        .await?
+        .set_requested_attributes_as_string(requested_attrs)?
+        .set_requested_predicates_as_string(requested_predicates)?
+        .set_not_revoked_interval(revocation_details)?;
+    ProofRequestTemplate::from_request(&version, presentation_request)
+        .store(get_main_wallet_handle())
+        .await
+}
+
+pub async fn get_proof_request_template(name: String, version: Option<String>) -> VcxResult<String> {
+    let template = ProofRequestTemplate::get(get_main_wallet_handle(), &name, version.as_deref()).await?;
+    serde_json::to_string(&template).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::SerializationError,
+            format!("Cannot serialize proof request template: {}", err),
+        )
+    })
+}
+
+pub async fn list_proof_request_templates(name: Option<String>) -> VcxResult<String> {
+    let templates = ProofRequestTemplate::list(get_main_wallet_handle(), name.as_deref()).await?;
+    serde_json::to_string(&templates).map_err(|err| {
+        VcxError::from_msg(
+            VcxErrorKind::SerializationError,
+            format!("Cannot serialize proof request templates: {}", err),
+        )
+    })
+}
+
+pub async fn delete_proof_request_template(name: String, version: String) -> VcxResult<()> {
+    ProofRequestTemplate::delete(get_main_wallet_handle(), &name, &version).await
+}
+
+pub async fn create_proof_from_template(
+    source_id: String,
+    name: String,
+    version: Option<String>,
+    overrides: String,
+) -> VcxResult<u32> {
+    let overrides = ProofRequestOverrides::from_string(&overrides)?;
+    let verifier = Verifier::create_from_template(
+        source_id,
+        get_main_wallet_handle(),
+        &name,
+        version.as_deref(),
+        &overrides,
+    )
+    .await?;
+    PROOF_MAP
+        .add(verifier)
+        .or(Err(VcxError::from(VcxErrorKind::CreateProof)))
+}
+
 pub async fn is_valid_handle(handle: u32) -> bool {
     PROOF_MAP.has_handle(handle)
 }
@@ -78,6 +141,14 @@ pub async fn update_state(handle: u32, message: Option<&str>, connection_handle:
                 .handle_message(get_main_wallet_handle(), message.into(), Some(send_message))
                 .await?;
             connection::update_message_status(connection_handle, &uid).await?;
+        } else if proof.is_expired() {
+            proof
+                .handle_message(
+                    get_main_wallet_handle(),
+                    VerifierMessages::PresentationRequestExpired,
+                    Some(send_message),
+                )
+                .await?;
         };
     }
     let state: u32 = proof.get_state().into();
@@ -174,6 +245,7 @@ pub mod tests {
 
     use crate::api_lib::api_handle::connection::tests::build_test_connection_inviter_requested;
     use crate::api_lib::api_handle::proof;
+    use crate::api_lib::utils::devsetup::SetupGlobalsLibraryWallet;
     use crate::aries_vcx::protocols::proof_presentation::verifier::state_machine::VerifierState;
 
     use super::*;
@@ -197,6 +269,73 @@ pub mod tests {
         create_default_proof().await;
     }
 
+    #[tokio::test]
+    #[cfg(feature = "general_test")]
+    async fn test_proof_request_template_round_trip() {
+        let _setup = SetupGlobalsLibraryWallet::init().await;
+
+        store_proof_request_template(
+            "Optional".to_owned(),
+            "1.0".to_owned(),
+            REQUESTED_ATTRS.to_owned(),
+            REQUESTED_PREDICATES.to_owned(),
+            r#"{"support_revocation":false}"#.to_string(),
+        )
+        .await
+        .unwrap();
+
+        let template: Value =
+            serde_json::from_str(&get_proof_request_template("Optional".to_owned(), None).await.unwrap()).unwrap();
+        assert_eq!(template["name"], "Optional");
+        assert_eq!(template["version"], "1.0");
+        let templates: Value = serde_json::from_str(&list_proof_request_templates(None).await.unwrap()).unwrap();
+        assert_eq!(templates.as_array().unwrap().len(), 1);
+
+        let handle = create_proof_from_template(
+            "1".to_string(),
+            "Optional".to_owned(),
+            Some("1.0".to_owned()),
+            "{}".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_state(handle).await.unwrap(),
+            VerifierState::PresentationRequestSet as u32
+        );
+
+        delete_proof_request_template("Optional".to_owned(), "1.0".to_owned())
+            .await
+            .unwrap();
+        assert_eq!(
+            get_proof_request_template("Optional".to_owned(), Some("1.0".to_owned()))
+                .await
+                .unwrap_err()
+                .kind(),
+            VcxErrorKind::WalletRecordNotFound
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "general_test")]
+    async fn test_create_proof_from_missing_template_fails() {
+        let _setup = SetupGlobalsLibraryWallet::init().await;
+
+        store_proof_request_template(
+            "Optional".to_owned(),
+            "1.0".to_owned(),
+            REQUESTED_ATTRS.to_owned(),
+            REQUESTED_PREDICATES.to_owned(),
+            r#"{"support_revocation":false}"#.to_string(),
+        )
+        .await
+        .unwrap();
+        let err = create_proof_from_template("1".to_string(), "Missing".to_owned(), None, "{}".to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::WalletRecordNotFound);
+    }
+
     #[tokio::test]
     #[cfg(feature = "general_test")]
     async fn test_revocation_details() {
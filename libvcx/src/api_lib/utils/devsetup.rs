@@ -1,4 +1,4 @@
-use aries_vcx::utils::devsetup::SetupWalletPoolAgency;
+use aries_vcx::utils::devsetup::{SetupLibraryWallet, SetupWalletPoolAgency};
 
 use crate::api_lib::global::agency_client::{reset_main_agency_client, set_main_agency_client};
 use crate::api_lib::global::wallet::{reset_main_wallet_handle, set_main_wallet_handle};
@@ -22,3 +22,21 @@ impl Drop for SetupGlobalsWalletPoolAgency {
         reset_main_agency_client();
     }
 }
+
+pub struct SetupGlobalsLibraryWallet {
+    pub setup: SetupLibraryWallet,
+}
+
+impl SetupGlobalsLibraryWallet {
+    pub async fn init() -> SetupGlobalsLibraryWallet {
+        let setup = SetupLibraryWallet::init().await;
+        set_main_wallet_handle(setup.wallet_handle);
+        SetupGlobalsLibraryWallet { setup }
+    }
+}
+
+impl Drop for SetupGlobalsLibraryWallet {
+    fn drop(&mut self) {
+        reset_main_wallet_handle();
+    }
+}